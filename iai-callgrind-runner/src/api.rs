@@ -7,6 +7,13 @@ use std::path::PathBuf;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnnotateConfig {
+    pub event_kind: Option<EventKind>,
+    pub context: Option<usize>,
+    pub limit: Option<usize>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Arg {
     pub id: Option<String>,
@@ -39,6 +46,13 @@ pub struct BinaryBenchmarkConfig {
     pub envs: Vec<(OsString, Option<OsString>)>,
     pub flamegraph: Option<FlamegraphConfig>,
     pub regression: Option<RegressionConfig>,
+    pub hot_functions: Option<HotFunctionsConfig>,
+    pub function_diff: Option<FunctionDiffConfig>,
+    pub annotate: Option<AnnotateConfig>,
+    pub hot_instructions: Option<HotInstructionsConfig>,
+    pub pprof: Option<PprofConfig>,
+    pub speedscope: Option<SpeedscopeConfig>,
+    pub dot: Option<DotConfig>,
     pub tools: Tools,
     pub tools_override: Option<Tools>,
 }
@@ -69,6 +83,14 @@ pub enum Direction {
     BottomToTop,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DotConfig {
+    pub event_kind: Option<EventKind>,
+    pub node_threshold: Option<f64>,
+    pub edge_threshold: Option<f64>,
+    pub differential: Option<bool>,
+}
+
 /// All `EventKind`s callgrind produces and additionally some derived events
 ///
 /// Depending on the options passed to Callgrind, these are the events that Callgrind can produce.
@@ -160,6 +182,8 @@ pub struct FlamegraphConfig {
     pub title: Option<String>,
     pub subtitle: Option<String>,
     pub min_width: Option<f64>,
+    pub max_depth: Option<usize>,
+    pub folded: Option<bool>,
 }
 
 /// The kind of `Flamegraph` which is going to be constructed
@@ -176,6 +200,26 @@ pub enum FlamegraphKind {
     None,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionDiffConfig {
+    pub event_kinds: Option<Vec<EventKind>>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotFunctionsConfig {
+    pub limit: Option<usize>,
+    pub event_kind: Option<EventKind>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotInstructionsConfig {
+    pub event_kind: Option<EventKind>,
+    pub functions: Option<usize>,
+    pub limit: Option<usize>,
+    pub objdump: Option<PathBuf>,
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct LibraryBenchmark {
     pub config: LibraryBenchmarkConfig,
@@ -204,6 +248,13 @@ pub struct LibraryBenchmarkConfig {
     pub envs: Vec<(OsString, Option<OsString>)>,
     pub flamegraph: Option<FlamegraphConfig>,
     pub regression: Option<RegressionConfig>,
+    pub hot_functions: Option<HotFunctionsConfig>,
+    pub function_diff: Option<FunctionDiffConfig>,
+    pub annotate: Option<AnnotateConfig>,
+    pub hot_instructions: Option<HotInstructionsConfig>,
+    pub pprof: Option<PprofConfig>,
+    pub speedscope: Option<SpeedscopeConfig>,
+    pub dot: Option<DotConfig>,
     pub tools: Tools,
     pub tools_override: Option<Tools>,
     pub custom_entry_point: Option<String>,
@@ -216,6 +267,11 @@ pub struct LibraryBenchmarkGroup {
     pub benches: Vec<LibraryBenchmarkBenches>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PprofConfig {
+    pub event_kinds: Option<Vec<EventKind>>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RawArgs(pub Vec<String>);
 
@@ -225,6 +281,11 @@ pub struct RegressionConfig {
     pub fail_fast: Option<bool>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeedscopeConfig {
+    pub event_kinds: Option<Vec<EventKind>>,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Run {
     pub cmd: Option<Cmd>,
@@ -280,6 +341,13 @@ impl BinaryBenchmarkConfig {
             self.envs.extend_from_slice(&other.envs);
             self.flamegraph = update_option(&self.flamegraph, &other.flamegraph);
             self.regression = update_option(&self.regression, &other.regression);
+            self.hot_functions = update_option(&self.hot_functions, &other.hot_functions);
+            self.function_diff = update_option(&self.function_diff, &other.function_diff);
+            self.annotate = update_option(&self.annotate, &other.annotate);
+            self.hot_instructions = update_option(&self.hot_instructions, &other.hot_instructions);
+            self.pprof = update_option(&self.pprof, &other.pprof);
+            self.speedscope = update_option(&self.speedscope, &other.speedscope);
+            self.dot = update_option(&self.dot, &other.dot);
             if let Some(other_tools) = &other.tools_override {
                 self.tools = other_tools.clone();
             } else if !other.tools.is_empty() {
@@ -424,6 +492,13 @@ impl LibraryBenchmarkConfig {
             self.envs.extend_from_slice(&other.envs);
             self.flamegraph = update_option(&self.flamegraph, &other.flamegraph);
             self.regression = update_option(&self.regression, &other.regression);
+            self.hot_functions = update_option(&self.hot_functions, &other.hot_functions);
+            self.function_diff = update_option(&self.function_diff, &other.function_diff);
+            self.annotate = update_option(&self.annotate, &other.annotate);
+            self.hot_instructions = update_option(&self.hot_instructions, &other.hot_instructions);
+            self.pprof = update_option(&self.pprof, &other.pprof);
+            self.speedscope = update_option(&self.speedscope, &other.speedscope);
+            self.dot = update_option(&self.dot, &other.dot);
             if let Some(other_tools) = &other.tools_override {
                 self.tools = other_tools.clone();
             } else if !other.tools.is_empty() {
@@ -565,6 +640,13 @@ mod tests {
             envs: vec![(OsString::from("MY_ENV"), Some(OsString::from("value")))],
             flamegraph: Some(FlamegraphConfig::default()),
             regression: Some(RegressionConfig::default()),
+            hot_functions: Some(HotFunctionsConfig::default()),
+            function_diff: Some(FunctionDiffConfig::default()),
+            annotate: Some(AnnotateConfig::default()),
+            hot_instructions: Some(HotInstructionsConfig::default()),
+            pprof: Some(PprofConfig::default()),
+            speedscope: Some(SpeedscopeConfig::default()),
+            dot: Some(DotConfig::default()),
             tools: Tools(vec![Tool {
                 kind: ValgrindTool::DHAT,
                 enable: None,
@@ -588,6 +670,13 @@ mod tests {
             envs: vec![(OsString::from("MY_ENV"), Some(OsString::from("value")))],
             flamegraph: Some(FlamegraphConfig::default()),
             regression: Some(RegressionConfig::default()),
+            hot_functions: Some(HotFunctionsConfig::default()),
+            function_diff: Some(FunctionDiffConfig::default()),
+            annotate: Some(AnnotateConfig::default()),
+            hot_instructions: Some(HotInstructionsConfig::default()),
+            pprof: Some(PprofConfig::default()),
+            speedscope: Some(SpeedscopeConfig::default()),
+            dot: Some(DotConfig::default()),
             tools: Tools(vec![Tool {
                 kind: ValgrindTool::DHAT,
                 enable: None,
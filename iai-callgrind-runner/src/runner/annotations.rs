@@ -0,0 +1,171 @@
+use std::fmt::Write;
+
+use regex::Regex;
+
+use super::message::MessageFormat;
+use super::report::title;
+use super::summary::{BenchmarkKind, BenchmarkSummary, CallgrindSummary};
+use crate::runner::envs;
+use crate::util::to_string_signed_short;
+
+/// The format of the annotations of regressions and errors specified with `--annotations`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum AnnotationsFormat {
+    /// GitHub Actions workflow commands (`::warning` and `::error`)
+    Github,
+    /// Don't print any annotations
+    None,
+}
+
+impl AnnotationsFormat {
+    /// Return the [`AnnotationsFormat`] in use or `None` if no annotations should be printed
+    ///
+    /// Without `--annotations`, the GitHub annotations are switched on if running in GitHub
+    /// Actions (the `GITHUB_ACTIONS` environment variable is `true`) but only if the output is
+    /// human readable. The annotations would otherwise break the newline delimited json messages.
+    pub fn resolve(format: Option<Self>, message_format: MessageFormat) -> Option<Self> {
+        match format {
+            Some(Self::None) => None,
+            Some(format) => Some(format),
+            None if message_format == MessageFormat::Human
+                && std::env::var(envs::GITHUB_ACTIONS).map_or(false, |value| value == "true") =>
+            {
+                Some(Self::Github)
+            }
+            None => None,
+        }
+    }
+}
+
+/// The GitHub Actions workflow commands of the regressions and errors of a benchmark
+///
+/// The annotations point to the benchmark file and the line of the `#[library_benchmark]`
+/// attribute of library benchmarks or the line of the `binary_benchmark_group!` macro of binary
+/// benchmarks. The line is looked up in the benchmark file and omitted if it can't be found.
+/// Regressions are reported as `::warning` unless they are configured to fail fast, then they are
+/// reported as `::error` like benchmarks which could not be run successfully.
+pub struct GithubAnnotations;
+
+impl GithubAnnotations {
+    /// Print the annotations of the benchmark with the `summary` to stdout
+    pub fn print(summary: &BenchmarkSummary) {
+        print!("{}", Self::create(summary));
+    }
+
+    /// Create the annotations of the benchmark with the `summary`, one workflow command per line
+    pub fn create(summary: &BenchmarkSummary) -> String {
+        let mut annotations = String::new();
+        let has_annotations = summary.error.is_some()
+            || summary
+                .callgrind_summary
+                .as_ref()
+                .map_or(false, CallgrindSummary::is_regressed);
+        if !has_annotations {
+            return annotations;
+        }
+
+        let file = summary
+            .benchmark_file
+            .strip_prefix(&summary.project_root)
+            .unwrap_or(&summary.benchmark_file);
+        let mut properties = format!("file={}", escape_property(&file.to_string_lossy()));
+        if let Some(line) = find_line(summary) {
+            write!(properties, ",line={line}").unwrap();
+        }
+        write!(properties, ",title={}", escape_property(&title(summary))).unwrap();
+
+        if let Some(callgrind) = &summary.callgrind_summary {
+            let level = if callgrind.regression_fail_fast {
+                "error"
+            } else {
+                "warning"
+            };
+            for regression in callgrind.summaries.iter().flat_map(|run| &run.regressions) {
+                let message = format!(
+                    "Performance has regressed: {} ({} -> {}): {}% exceeds limit of {}%",
+                    regression.event_kind,
+                    regression.old,
+                    regression.new,
+                    to_string_signed_short(regression.diff_pct),
+                    to_string_signed_short(regression.limit)
+                );
+                writeln!(
+                    annotations,
+                    "::{level} {properties}::{}",
+                    escape_data(&message)
+                )
+                .unwrap();
+            }
+        }
+        if let Some(error) = &summary.error {
+            writeln!(annotations, "::error {properties}::{}", escape_data(error)).unwrap();
+        }
+        annotations
+    }
+}
+
+/// Return the line (starting with `1`) of the definition of the benchmark in the benchmark file
+fn find_line(summary: &BenchmarkSummary) -> Option<usize> {
+    let source =
+        std::fs::read_to_string(summary.project_root.join(&summary.benchmark_file)).ok()?;
+    let segments = summary.module_path.split("::").collect::<Vec<&str>>();
+    match summary.kind {
+        BenchmarkKind::LibraryBenchmark => find_library_benchmark(
+            &source,
+            segments.last().expect("A module path is not empty"),
+        ),
+        BenchmarkKind::BinaryBenchmark => find_binary_benchmark_group(&source, segments.get(1)?),
+    }
+}
+
+/// Return the line of the `#[library_benchmark]` attribute of the `function`
+///
+/// If the function is found but not the attribute, the line of the function is returned.
+pub fn find_library_benchmark(source: &str, function: &str) -> Option<usize> {
+    let re = Regex::new(&format!(r"\bfn\s+{}\b", regex::escape(function)))
+        .expect("Regex should compile");
+    let lines = source.lines().collect::<Vec<&str>>();
+    let function_index = lines.iter().position(|line| re.is_match(line))?;
+    let index = lines[..function_index]
+        .iter()
+        .rposition(|line| line.contains("#[library_benchmark"))
+        .filter(|index| {
+            !lines[index + 1..function_index]
+                .iter()
+                .any(|line| line.contains("fn "))
+        })
+        .unwrap_or(function_index);
+    Some(index + 1)
+}
+
+/// Return the line of the `binary_benchmark_group!` macro with the name `group`
+pub fn find_binary_benchmark_group(source: &str, group: &str) -> Option<usize> {
+    let re = Regex::new(&format!(r"\bname\s*=\s*{}\b", regex::escape(group)))
+        .expect("Regex should compile");
+    let lines = source.lines().collect::<Vec<&str>>();
+    let starts = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.contains("binary_benchmark_group!"))
+        .map(|(index, _)| index)
+        .collect::<Vec<usize>>();
+    starts.iter().enumerate().find_map(|(i, start)| {
+        let end = starts.get(i + 1).copied().unwrap_or(lines.len());
+        lines[*start..end]
+            .iter()
+            .any(|line| re.is_match(line))
+            .then(|| start + 1)
+    })
+}
+
+/// Escape the message of a workflow command
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape the value of a property of a workflow command
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
@@ -1,7 +1,14 @@
+use std::path::PathBuf;
+
 use clap::builder::BoolishValueParser;
 use clap::{ArgAction, Parser};
 
-use super::summary::SummaryFormat;
+use super::annotations::AnnotationsFormat;
+use super::git::GitRevision;
+use super::message::MessageFormat;
+use super::project_config::ModulePathPattern;
+use super::report::{ExportOutput, ReportFormat, ReportOutput};
+use super::summary::{ExportFormat, SummaryFormat};
 use crate::api::{EventKind, RawArgs, RegressionConfig};
 
 /// The command line arguments the user provided after `--` when running cargo bench
@@ -27,22 +34,28 @@ pub struct CommandLineArgs {
     /// The raw arguments to pass through to Callgrind
     ///
     /// This is a space separated list of command-line-arguments specified as if they were
-    /// passed directly to valgrind.
+    /// passed directly to valgrind. The arguments can be restricted to the benchmarks with a
+    /// module path matching a glob pattern with a `<pattern>:` prefix. This option can be
+    /// specified multiple times.
     ///
     /// Examples:
     ///   * --callgrind-args=--dump-instr=yes
     ///   * --callgrind-args='--dump-instr=yes --collect-systime=yes'
+    ///   * --callgrind-args='heavy*:--branch-sim=yes'
     #[clap(
         long = "callgrind-args",
         required = false,
-        value_parser = parse_args,
+        value_parser = parse_scoped_args,
         takes_value = true,
         verbatim_doc_comment
     )]
-    pub callgrind_args: Option<RawArgs>,
+    pub callgrind_args: Vec<Scoped<RawArgs>>,
 
     /// Save a machine-readable summary of each benchmark run in json format next to the usual
     /// benchmark output
+    ///
+    /// Besides `json` and `pretty-json`, the formats `csv` and `tsv` save a flat table of the
+    /// callgrind events with one row per `EventKind`.
     #[clap(
         long = "save-summary",
         value_enum,
@@ -52,6 +65,48 @@ pub struct CommandLineArgs {
     )]
     pub save_summary: Option<SummaryFormat>,
 
+    /// Save a single json summary of the whole benchmark run to the given file
+    ///
+    /// The file contains the summaries of all benchmarks of all benchmark files, the start and end
+    /// time of the benchmark run, the version of the runner, the command line arguments and
+    /// whether any benchmark has regressed. The json schema is stored in
+    /// `iai-callgrind-runner/schemas/run-summary.v1.schema.json`.
+    ///
+    /// Examples: --save-summary-to=target/iai/summary.json
+    #[clap(
+        long = "save-summary-to",
+        env = "IAI_CALLGRIND_SAVE_SUMMARY_TO",
+        required = false
+    )]
+    pub save_summary_to: Option<PathBuf>,
+
+    /// The format of the output on stdout
+    ///
+    /// With `json`, the usual human readable output is replaced by newline delimited json
+    /// messages. Each message is a json object on a single line with a `reason` field, which is
+    /// one of `benchmark-started`, `costs`, `regression`, `tool-summary`, `benchmark-finished`
+    /// and `error`. Log messages are still printed to stderr.
+    ///
+    /// Examples: --message-format=json
+    #[clap(
+        long = "message-format",
+        env = "IAI_CALLGRIND_MESSAGE_FORMAT",
+        value_enum,
+        default_value = "human"
+    )]
+    pub message_format: MessageFormat,
+
+    /// Print annotations of performance regressions and errors for a CI system
+    ///
+    /// With `github`, the regressions and errors are printed as GitHub Actions workflow commands
+    /// (`::warning` and `::error`), which show up inline in pull requests. If not specified, the
+    /// `github` annotations are printed if running in GitHub Actions (`GITHUB_ACTIONS=true`) and
+    /// the `--message-format` is `human`. Use `none` to switch off the annotations.
+    ///
+    /// Examples: --annotations=github
+    #[clap(long = "annotations", env = "IAI_CALLGRIND_ANNOTATIONS", value_enum)]
+    pub annotations: Option<AnnotationsFormat>,
+
     /// Allow ASLR (Address Space Layout Randomization)
     ///
     /// If possible, ASLR is disabled on platforms that support it (linux, freebsd) because ASLR
@@ -75,14 +130,19 @@ pub struct CommandLineArgs {
     /// value comes below the limit. The `EventKind` is matched case insensitive. For a list of
     /// valid `EventKinds` see the docs: https://docs.rs/iai-callgrind/latest/iai_callgrind/enum.EventKind.html
     ///
-    /// Examples: --regression='ir=0.0' or --regression='ir=0, EstimatedCycles=10'
+    /// The limits can be restricted to the benchmarks with a module path matching a glob pattern
+    /// with a `<pattern>:` prefix. This option can be specified multiple times and the limits of
+    /// a matching pattern take precedence over the limits without a pattern.
+    ///
+    /// Examples: `--regression='ir=0.0'` or `--regression='ir=0, EstimatedCycles=10'` or
+    /// `--regression='parser::*:Ir=2'`
     #[clap(
         required = false,
         long = "regression",
         env = "IAI_CALLGRIND_REGRESSION",
-        value_parser = parse_regression_config
+        value_parser = parse_scoped_regression_config
     )]
-    pub regression: Option<RegressionConfig>,
+    pub regression: Vec<Scoped<RegressionConfig>>,
 
     /// If true, the first failed performance regression check fails the whole benchmark run
     ///
@@ -95,6 +155,212 @@ pub struct CommandLineArgs {
         value_parser = BoolishValueParser::new()
     )]
     pub regression_fail_fast: Option<bool>,
+
+    /// Show the N most expensive functions (inclusive and exclusive costs) of each benchmark
+    ///
+    /// The functions are sorted by the `EventKind` configured with `HotFunctionsConfig` in the
+    /// benchmark (default: `Ir`). This option takes precedence over the limit configured in the
+    /// benchmark. A value of `0` disables the hot functions report.
+    ///
+    /// Examples: --show-functions=5 or --show-functions
+    #[clap(
+        long = "show-functions",
+        env = "IAI_CALLGRIND_SHOW_FUNCTIONS",
+        required = false,
+        default_missing_value = "10",
+        value_parser = clap::value_parser!(usize)
+    )]
+    pub show_functions: Option<usize>,
+
+    /// Export the callgrind output of each benchmark in other formats
+    ///
+    /// This is a `,` separated list of formats. The exported files of the pprof, speedscope and
+    /// dot formats are stored next to the callgrind output files. A format configured in the
+    /// benchmark (for example with `PprofConfig`) takes precedence over the default configuration
+    /// used by this option. The openmetrics format requires a path separated by a `:` and exports
+    /// the costs and regressions of all benchmarks at the end of the benchmark run as openmetrics
+    /// text file, for example for the textfile collector of the Prometheus node exporter.
+    ///
+    /// Examples: --export=pprof, --export=pprof,speedscope,dot,
+    /// --export=openmetrics:target/iai/iai.prom
+    #[clap(
+        long = "export",
+        env = "IAI_CALLGRIND_EXPORT",
+        required = false,
+        value_parser = parse_export,
+        use_value_delimiter = true,
+        value_delimiter = ','
+    )]
+    pub export: Vec<ExportOutput>,
+
+    /// Create a static html report of all benchmarks at the end of the benchmark run
+    ///
+    /// The report is saved as `report.html` in the `target/iai/<package>` directory and contains
+    /// an index of all benchmarks with their costs, the regressions and links to the flamegraphs
+    /// and log files.
+    #[clap(
+        long = "html-report",
+        env = "IAI_CALLGRIND_HTML_REPORT",
+        default_missing_value = "yes",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub html_report: Option<bool>,
+
+    /// Append the costs of all benchmarks to the local history (Default: no)
+    ///
+    /// The history is stored as json lines in the `target/iai/<package>/history` directory. Each
+    /// line records the costs of a benchmark run with the time, the git commit if available and
+    /// the environment. Use `iai-callgrind-runner history` to show the trends of the benchmarks.
+    #[clap(
+        long = "save-history",
+        env = "IAI_CALLGRIND_SAVE_HISTORY",
+        default_missing_value = "yes",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub save_history: Option<bool>,
+
+    /// Save the callgrind output as baseline of the current git commit (Default: no)
+    ///
+    /// The output is copied into the `baselines/commits/<hash>` directory next to the output of
+    /// each benchmark and if on a branch also into the `baselines/branches/<branch>` directory.
+    /// The commit and branch are read by running `git` in the project root. Use
+    /// --baseline-commit to compare against a saved baseline.
+    #[clap(
+        long = "save-baseline-commit",
+        env = "IAI_CALLGRIND_SAVE_BASELINE_COMMIT",
+        default_missing_value = "yes",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub save_baseline_commit: Option<bool>,
+
+    /// Compare against the baseline saved with --save-baseline-commit of this git revision
+    ///
+    /// The revision is anything `git rev-parse` understands like a branch, a tag or a commit hash
+    /// or `merge-base(<rev>)` for the merge base of `HEAD` and the revision. If there is no
+    /// baseline of the resolved commit but the revision is a branch, the latest baseline saved on
+    /// this branch is used. The baseline replaces the output of the previous benchmark run.
+    ///
+    /// Examples: --baseline-commit=main, --baseline-commit=HEAD~1,
+    /// --baseline-commit='merge-base(main)'
+    #[clap(
+        long = "baseline-commit",
+        env = "IAI_CALLGRIND_BASELINE_COMMIT",
+        value_parser = GitRevision::parse
+    )]
+    pub baseline_commit: Option<GitRevision>,
+
+    /// Create a report of all benchmarks in the given format at the end of the benchmark run
+    ///
+    /// The value is the format and the path of the report separated by a `:`. This option can be
+    /// specified multiple times or as `,` separated list. The formats are:
+    ///
+    ///   * markdown: A document suitable for pull request comments with a table per group of
+    ///     benchmarks and the collapsible details of each benchmark
+    ///   * junit: A junit xml document with a testcase per benchmark
+    ///   * csv: A table with one row per benchmark and event kind
+    ///   * tsv: Like csv but tab separated
+    ///   * bmf: A json document in the Bencher Metric Format with each event kind as measure
+    ///
+    /// Examples: --report=markdown:target/iai/report.md, --report=junit:target/iai/junit.xml
+    #[clap(
+        long = "report",
+        env = "IAI_CALLGRIND_REPORT",
+        required = false,
+        value_parser = parse_report,
+        use_value_delimiter = true,
+        value_delimiter = ',',
+        verbatim_doc_comment
+    )]
+    pub report: Vec<ReportOutput>,
+
+    /// Create a junit xml report of all benchmarks at the end of the benchmark run
+    ///
+    /// This is the same as --report=junit:<path>. Each benchmark is a testcase, a performance
+    /// regression is a failure and a benchmark which could not be run successfully is an error.
+    ///
+    /// Examples: --junit=target/iai/junit.xml
+    #[clap(long = "junit", env = "IAI_CALLGRIND_JUNIT", required = false)]
+    pub junit: Option<PathBuf>,
+
+    /// The `EventKinds` shown in the tables of the reports (default: Ir,EstimatedCycles)
+    ///
+    /// This is a `,` separated list of `EventKinds` which applies to the --html-report and the
+    /// --report option. The `EventKind` is matched case insensitive.
+    ///
+    /// Examples: --report-event-kinds=Ir or --report-event-kinds='ir, l1hits, totalrw'
+    #[clap(
+        long = "report-event-kinds",
+        env = "IAI_CALLGRIND_REPORT_EVENT_KINDS",
+        required = false,
+        value_parser = parse_event_kinds
+    )]
+    pub report_event_kinds: Option<EventKinds>,
+}
+
+/// The value of a command line argument optionally restricted to the benchmarks with a module path
+/// matching the `pattern`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scoped<T> {
+    pub pattern: Option<ModulePathPattern>,
+    pub value: T,
+}
+
+impl<T> Scoped<T> {
+    /// Return true if this value applies to the benchmark with the `module_path`
+    pub fn matches(&self, module_path: &str) -> bool {
+        self.pattern
+            .as_ref()
+            .map_or(true, |pattern| pattern.matches(module_path))
+    }
+}
+
+/// Split the optional `<pattern>:` prefix from the `value` of a scoped command line argument
+///
+/// The pattern ends at the first `:` which is not part of a `::`. Values like
+/// `--toggle-collect=a::b` or `--callgrind-out-file=c:/out` have no prefix because the part before
+/// the `:` starts with `-` or contains a `=` or whitespace. A prefix which is an [`EventKind`] like
+/// in the mistyped `--regression=Ir:10` is no pattern either, so the error shows the whole value.
+fn split_scope(value: &str) -> Result<(Option<ModulePathPattern>, &str), String> {
+    let bytes = value.as_bytes();
+    let index = bytes.iter().enumerate().position(|(index, byte)| {
+        *byte == b':'
+            && (index == 0 || bytes[index - 1] != b':')
+            && bytes.get(index + 1) != Some(&b':')
+    });
+
+    if let Some(index) = index {
+        let prefix = value[..index].trim();
+        if !prefix.starts_with('-')
+            && !prefix.contains(|c: char| c == '=' || c.is_whitespace())
+            && EventKind::from_str_ignore_case(prefix).is_none()
+        {
+            return ModulePathPattern::new(prefix)
+                .map(|pattern| (Some(pattern), &value[index + 1..]));
+        }
+    }
+    Ok((None, value))
+}
+
+/// Return the `values` which apply to the `module_path` with the values without a pattern first
+fn matching<'a, T>(
+    values: &'a [Scoped<T>],
+    module_path: &str,
+) -> impl Iterator<Item = &'a Scoped<T>> {
+    let (scoped, unscoped): (Vec<_>, Vec<_>) = values
+        .iter()
+        .filter(|value| value.matches(module_path))
+        .partition(|value| value.pattern.is_some());
+    unscoped.into_iter().chain(scoped)
+}
+
+fn parse_scoped_args(value: &str) -> Result<Scoped<RawArgs>, String> {
+    let (pattern, value) = split_scope(value)?;
+    parse_args(value).map(|value| Scoped { pattern, value })
+}
+
+fn parse_scoped_regression_config(value: &str) -> Result<Scoped<RegressionConfig>, String> {
+    let (pattern, value) = split_scope(value)?;
+    parse_regression_config(value).map(|value| Scoped { pattern, value })
 }
 
 /// This function parses a space separated list of raw argument strings into [`crate::api::RawArgs`]
@@ -104,7 +370,7 @@ fn parse_args(value: &str) -> Result<RawArgs, String> {
         .map(RawArgs::new)
 }
 
-fn parse_regression_config(value: &str) -> Result<RegressionConfig, String> {
+pub(crate) fn parse_regression_config(value: &str) -> Result<RegressionConfig, String> {
     let value = value.trim();
     if value.is_empty() {
         return Err("No limits found: At least one limit must be specified".to_owned());
@@ -141,13 +407,96 @@ fn parse_regression_config(value: &str) -> Result<RegressionConfig, String> {
     Ok(regression)
 }
 
-impl From<&CommandLineArgs> for Option<RegressionConfig> {
-    fn from(value: &CommandLineArgs) -> Self {
-        let mut config = value.regression.clone();
-        if let Some(config) = config.as_mut() {
-            config.fail_fast = value.regression_fail_fast;
+fn parse_report(value: &str) -> Result<ReportOutput, String> {
+    let (format, path) = value
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid report: '{value}': Expected format:path"))?;
+    let format = <ReportFormat as clap::ValueEnum>::from_str(format.trim(), true)
+        .map_err(|_| format!("Unknown report format: '{}'", format.trim()))?;
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(format!("Missing path of the report: '{value}'"));
+    }
+
+    Ok(ReportOutput {
+        format,
+        path: path.into(),
+    })
+}
+
+fn parse_export(value: &str) -> Result<ExportOutput, String> {
+    match value.split_once(':') {
+        Some((format, path)) if format.trim().eq_ignore_ascii_case("openmetrics") => {
+            let path = path.trim();
+            if path.is_empty() {
+                return Err(format!("Missing path of the export: '{value}'"));
+            }
+            Ok(ExportOutput::OpenMetrics(path.into()))
         }
-        config
+        Some(_) => Err(format!(
+            "Invalid export: '{value}': Only the openmetrics format takes a path"
+        )),
+        None if value.trim().eq_ignore_ascii_case("openmetrics") => Err(format!(
+            "Missing path of the export: '{value}': Expected openmetrics:path"
+        )),
+        None => <ExportFormat as clap::ValueEnum>::from_str(value.trim(), true)
+            .map(ExportOutput::Callgrind)
+            .map_err(|_| format!("Unknown export format: '{}'", value.trim())),
+    }
+}
+
+/// A `,` separated list of [`EventKind`]s parsed with [`parse_event_kinds`]
+///
+/// clap treats an `Option<Vec<T>>` as argument with multiple values of `T`, so this alias is
+/// needed to parse the whole list at once.
+pub(crate) type EventKinds = Vec<EventKind>;
+
+pub(crate) fn parse_event_kinds(value: &str) -> Result<EventKinds, String> {
+    let mut event_kinds = vec![];
+    for split in value.split(',') {
+        let split = split.trim();
+        let event_kind = EventKind::from_str_ignore_case(split)
+            .ok_or_else(|| format!("Unknown event kind: '{split}'"))?;
+        event_kinds.push(event_kind);
+    }
+    Ok(event_kinds)
+}
+
+impl CommandLineArgs {
+    /// Return the `--callgrind-args` which apply to the benchmark with the `module_path`
+    ///
+    /// The arguments without a pattern come first followed by the arguments with a matching
+    /// pattern, so the latter take precedence.
+    pub fn callgrind_args(&self, module_path: &str) -> RawArgs {
+        let mut args = RawArgs::default();
+        for scoped in matching(&self.callgrind_args, module_path) {
+            args.extend_ignore_flag(scoped.value.0.iter());
+        }
+        args
+    }
+
+    /// Return the `--regression` config which applies to the benchmark with the `module_path`
+    ///
+    /// The last config with a matching pattern takes precedence over the last config without a
+    /// pattern.
+    pub fn regression_config(&self, module_path: &str) -> Option<RegressionConfig> {
+        matching(&self.regression, module_path)
+            .last()
+            .map(|scoped| RegressionConfig {
+                fail_fast: self.regression_fail_fast,
+                ..scoped.value.clone()
+            })
+    }
+
+    /// Return the [`ExportFormat`]s of the callgrind output specified with `--export`
+    pub fn export_formats(&self) -> Vec<ExportFormat> {
+        self.export
+            .iter()
+            .filter_map(|export| match export {
+                ExportOutput::Callgrind(format) => Some(*format),
+                ExportOutput::OpenMetrics(_) => None,
+            })
+            .collect()
     }
 }
 
@@ -211,4 +560,331 @@ mod tests {
             expected_reason,
         );
     }
+
+    #[rstest]
+    #[case::no_scope("Ir=2", None, "Ir=2")]
+    #[case::scope("parser::*:Ir=2", Some("parser::*"), "Ir=2")]
+    #[case::scope_with_whitespace(" parser::* : Ir=2", Some("parser::*"), " Ir=2")]
+    #[case::callgrind_args("heavy::*:--branch-sim=yes", Some("heavy::*"), "--branch-sim=yes")]
+    #[case::double_colon_in_value("--toggle-collect=a::b", None, "--toggle-collect=a::b")]
+    #[case::scope_and_double_colon_in_value(
+        "heavy::*:--toggle-collect=a::b",
+        Some("heavy::*"),
+        "--toggle-collect=a::b"
+    )]
+    #[case::colon_in_value("--callgrind-out-file=c:/out", None, "--callgrind-out-file=c:/out")]
+    #[case::colon_after_whitespace("--some=yes --other=c:/x", None, "--some=yes --other=c:/x")]
+    #[case::event_kind_is_no_scope("Ir:10", None, "Ir:10")]
+    #[case::event_kind_case_insensitive("ir:10", None, "ir:10")]
+    fn test_split_scope(
+        #[case] value: &str,
+        #[case] expected_pattern: Option<&str>,
+        #[case] expected_value: &str,
+    ) {
+        let (pattern, value) = split_scope(value).unwrap();
+        assert_eq!(
+            pattern,
+            expected_pattern.map(|p| ModulePathPattern::new(p).unwrap())
+        );
+        assert_eq!(value, expected_value);
+    }
+
+    #[test]
+    fn test_scoped_regression_when_event_kind_prefix_then_error_with_whole_value() {
+        let error = CommandLineArgs::try_parse_from(["--regression=Ir:10"]).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Invalid format of key/value pair: 'Ir:10'"));
+    }
+
+    #[rstest]
+    #[case::empty_pattern(":Ir=2")]
+    #[case::invalid_pattern("parser::[:Ir=2")]
+    fn test_split_scope_then_error(#[case] value: &str) {
+        assert!(split_scope(value).is_err());
+    }
+
+    #[rstest]
+    #[case::no_regression(&[], "bench::parser::json", None)]
+    #[case::unscoped(&["--regression=Ir=5"], "bench::parser::json", Some(vec![(Ir, 5f64)]))]
+    #[case::scoped_matching(
+        &["--regression=parser::*:Ir=2"],
+        "bench::parser::json",
+        Some(vec![(Ir, 2f64)])
+    )]
+    #[case::scoped_not_matching(&["--regression=parser::*:Ir=2"], "bench::lexer::token", None)]
+    #[case::scoped_module_needs_glob(&["--regression=parser:Ir=2"], "bench::parser::json", None)]
+    #[case::scoped_wins(
+        &["--regression=parser::*:Ir=2", "--regression=Ir=5"],
+        "bench::parser::json",
+        Some(vec![(Ir, 2f64)])
+    )]
+    #[case::unscoped_when_scoped_not_matching(
+        &["--regression=parser::*:Ir=2", "--regression=Ir=5"],
+        "bench::lexer::token",
+        Some(vec![(Ir, 5f64)])
+    )]
+    #[case::last_scoped_wins(
+        &["--regression=parser::*:Ir=2", "--regression=json:Ir=1"],
+        "bench::parser::json",
+        Some(vec![(Ir, 1f64)])
+    )]
+    fn test_regression_config_by_module_path(
+        #[case] args: &[&str],
+        #[case] module_path: &str,
+        #[case] expected: Option<Vec<(EventKind, f64)>>,
+    ) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(
+            actual.regression_config(module_path),
+            expected.map(|limits| RegressionConfig {
+                limits,
+                fail_fast: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_regression_config_with_fail_fast() {
+        let actual = CommandLineArgs::try_parse_from([
+            "--regression=parser::*:Ir=2",
+            "--regression-fail-fast",
+        ])
+        .unwrap();
+        assert_eq!(
+            actual.regression_config("bench::parser::json"),
+            Some(RegressionConfig {
+                limits: vec![(Ir, 2f64)],
+                fail_fast: Some(true)
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::no_args(&[], "bench::heavy::run", &[])]
+    #[case::unscoped(&["--callgrind-args=--dump-instr=yes"], "bench::heavy::run", &["--dump-instr=yes"])]
+    #[case::scoped_matching(
+        &["--callgrind-args=heavy::*:--branch-sim=yes"],
+        "bench::heavy::run",
+        &["--branch-sim=yes"]
+    )]
+    #[case::scoped_not_matching(&["--callgrind-args=heavy::*:--branch-sim=yes"], "bench::light::run", &[])]
+    #[case::scoped_after_unscoped(
+        &["--callgrind-args=heavy::*:--branch-sim=yes", "--callgrind-args=--branch-sim=no --dump-instr=yes"],
+        "bench::heavy::run",
+        &["--branch-sim=no", "--dump-instr=yes", "--branch-sim=yes"]
+    )]
+    fn test_callgrind_args_by_module_path(
+        #[case] args: &[&str],
+        #[case] module_path: &str,
+        #[case] expected: &[&str],
+    ) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(
+            actual.callgrind_args(module_path),
+            RawArgs::from_iter(expected)
+        );
+    }
+
+    #[rstest]
+    #[case::not_present(&[], None)]
+    #[case::without_value(&["--show-functions"], Some(10))]
+    #[case::with_value(&["--show-functions=5"], Some(5))]
+    #[case::zero(&["--show-functions=0"], Some(0))]
+    fn test_show_functions(#[case] args: &[&str], #[case] expected: Option<usize>) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(actual.show_functions, expected);
+    }
+
+    #[rstest]
+    #[case::not_present(&[], &[])]
+    #[case::pprof(&["--export=pprof"], &[ExportFormat::Pprof])]
+    #[case::multiple(&["--export=pprof", "--export=pprof"], &[ExportFormat::Pprof, ExportFormat::Pprof])]
+    #[case::comma_separated(&["--export=pprof,pprof"], &[ExportFormat::Pprof, ExportFormat::Pprof])]
+    #[case::speedscope(&["--export=speedscope"], &[ExportFormat::Speedscope])]
+    #[case::dot(&["--export=dot"], &[ExportFormat::Dot])]
+    #[case::all(
+        &["--export=pprof,speedscope,dot"],
+        &[ExportFormat::Pprof, ExportFormat::Speedscope, ExportFormat::Dot]
+    )]
+    #[case::with_openmetrics(&["--export=pprof,openmetrics:iai.prom"], &[ExportFormat::Pprof])]
+    fn test_export(#[case] args: &[&str], #[case] expected: &[ExportFormat]) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(actual.export_formats(), expected);
+    }
+
+    #[rstest]
+    #[case::pprof("pprof", ExportOutput::Callgrind(ExportFormat::Pprof))]
+    #[case::case_insensitive("Dot", ExportOutput::Callgrind(ExportFormat::Dot))]
+    #[case::openmetrics(
+        "openmetrics:target/iai.prom",
+        ExportOutput::OpenMetrics("target/iai.prom".into())
+    )]
+    #[case::openmetrics_with_whitespace(
+        " OpenMetrics : iai.prom ",
+        ExportOutput::OpenMetrics("iai.prom".into())
+    )]
+    fn test_parse_export(#[case] value: &str, #[case] expected: ExportOutput) {
+        assert_eq!(parse_export(value).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::unknown_format("wrong", "Unknown export format: 'wrong'")]
+    #[case::openmetrics_without_path(
+        "openmetrics",
+        "Missing path of the export: 'openmetrics': Expected openmetrics:path"
+    )]
+    #[case::openmetrics_empty_path("openmetrics:", "Missing path of the export: 'openmetrics:'")]
+    #[case::path_not_allowed(
+        "pprof:file.pb",
+        "Invalid export: 'pprof:file.pb': Only the openmetrics format takes a path"
+    )]
+    fn test_parse_export_then_error(#[case] value: &str, #[case] expected: &str) {
+        assert_eq!(&parse_export(value).unwrap_err(), expected);
+    }
+
+    #[rstest]
+    #[case::markdown("markdown:report.md", ReportFormat::Markdown, "report.md")]
+    #[case::case_insensitive("MarkDown:report.md", ReportFormat::Markdown, "report.md")]
+    #[case::with_whitespace(
+        " markdown : some/report.md ",
+        ReportFormat::Markdown,
+        "some/report.md"
+    )]
+    #[case::path_with_colon("markdown:c:/report.md", ReportFormat::Markdown, "c:/report.md")]
+    #[case::junit("junit:junit.xml", ReportFormat::Junit, "junit.xml")]
+    #[case::csv("csv:report.csv", ReportFormat::Csv, "report.csv")]
+    #[case::tsv("tsv:report.tsv", ReportFormat::Tsv, "report.tsv")]
+    #[case::bmf("bmf:bmf.json", ReportFormat::Bmf, "bmf.json")]
+    #[case::openmetrics("openmetrics:iai.prom", ReportFormat::OpenMetrics, "iai.prom")]
+    fn test_parse_report(#[case] value: &str, #[case] format: ReportFormat, #[case] path: &str) {
+        assert_eq!(
+            parse_report(value).unwrap(),
+            ReportOutput {
+                format,
+                path: path.into()
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::missing_separator("markdown", "Invalid report: 'markdown': Expected format:path")]
+    #[case::unknown_format("wrong:report.md", "Unknown report format: 'wrong'")]
+    #[case::missing_path("markdown:", "Missing path of the report: 'markdown:'")]
+    fn test_parse_report_then_error(#[case] value: &str, #[case] expected: &str) {
+        assert_eq!(&parse_report(value).unwrap_err(), expected);
+    }
+
+    #[rstest]
+    #[case::single("ir", &[Ir])]
+    #[case::multiple("Ir,EstimatedCycles", &[Ir, EstimatedCycles])]
+    #[case::with_whitespace("ir , l1hits", &[Ir, L1hits])]
+    fn test_parse_event_kinds(#[case] value: &str, #[case] expected: &[EventKind]) {
+        assert_eq!(parse_event_kinds(value).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_event_kinds_when_unknown_then_error() {
+        assert_eq!(
+            parse_event_kinds("Ir,wrong").unwrap_err(),
+            "Unknown event kind: 'wrong'"
+        );
+    }
+
+    #[rstest]
+    #[case::not_present(&[], &[])]
+    #[case::single(&["--report=markdown:report.md"], &["report.md"])]
+    #[case::multiple(&["--report=markdown:a.md", "--report=markdown:b.md"], &["a.md", "b.md"])]
+    #[case::comma_separated(&["--report=markdown:a.md,markdown:b.md"], &["a.md", "b.md"])]
+    fn test_report(#[case] args: &[&str], #[case] expected: &[&str]) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(
+            actual.report,
+            expected
+                .iter()
+                .map(|path| ReportOutput {
+                    format: ReportFormat::Markdown,
+                    path: path.into()
+                })
+                .collect::<Vec<ReportOutput>>()
+        );
+    }
+
+    #[rstest]
+    #[case::not_present(&[], None)]
+    #[case::single(&["--report-event-kinds=ir"], Some(vec![Ir]))]
+    #[case::multiple(&["--report-event-kinds=Ir, l1hits,TotalRW"], Some(vec![Ir, L1hits, TotalRW]))]
+    fn test_report_event_kinds(#[case] args: &[&str], #[case] expected: Option<Vec<EventKind>>) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(actual.report_event_kinds, expected);
+    }
+
+    #[rstest]
+    #[case::not_present(&[], None)]
+    #[case::with_path(&["--junit=target/junit.xml"], Some("target/junit.xml"))]
+    fn test_junit(#[case] args: &[&str], #[case] expected: Option<&str>) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(actual.junit, expected.map(PathBuf::from));
+    }
+
+    #[rstest]
+    #[case::not_present(&[], None)]
+    #[case::with_path(&["--save-summary-to=target/summary.json"], Some("target/summary.json"))]
+    fn test_save_summary_to(#[case] args: &[&str], #[case] expected: Option<&str>) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(actual.save_summary_to, expected.map(PathBuf::from));
+    }
+
+    #[rstest]
+    #[case::not_present(&[], None)]
+    #[case::without_value(&["--save-summary"], Some(SummaryFormat::Json))]
+    #[case::pretty_json(&["--save-summary=pretty-json"], Some(SummaryFormat::PrettyJson))]
+    #[case::csv(&["--save-summary=csv"], Some(SummaryFormat::Csv))]
+    #[case::tsv(&["--save-summary=tsv"], Some(SummaryFormat::Tsv))]
+    fn test_save_summary(#[case] args: &[&str], #[case] expected: Option<SummaryFormat>) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(actual.save_summary, expected);
+    }
+
+    #[rstest]
+    #[case::not_present(&[], MessageFormat::Human)]
+    #[case::human(&["--message-format=human"], MessageFormat::Human)]
+    #[case::json(&["--message-format=json"], MessageFormat::Json)]
+    fn test_message_format(#[case] args: &[&str], #[case] expected: MessageFormat) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(actual.message_format, expected);
+    }
+
+    #[rstest]
+    #[case::not_present(&[], None)]
+    #[case::github(&["--annotations=github"], Some(AnnotationsFormat::Github))]
+    #[case::none(&["--annotations=none"], Some(AnnotationsFormat::None))]
+    fn test_annotations(#[case] args: &[&str], #[case] expected: Option<AnnotationsFormat>) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(actual.annotations, expected);
+    }
+
+    #[rstest]
+    #[case::not_present(&[], None)]
+    #[case::branch(&["--baseline-commit=main"], Some(GitRevision::Rev("main".to_owned())))]
+    #[case::relative(&["--baseline-commit=HEAD~1"], Some(GitRevision::Rev("HEAD~1".to_owned())))]
+    #[case::merge_base(
+        &["--baseline-commit=merge-base(main)"],
+        Some(GitRevision::MergeBase("main".to_owned()))
+    )]
+    #[case::merge_base_with_spaces(
+        &["--baseline-commit= merge-base( origin/main ) "],
+        Some(GitRevision::MergeBase("origin/main".to_owned()))
+    )]
+    fn test_baseline_commit(#[case] args: &[&str], #[case] expected: Option<GitRevision>) {
+        let actual = CommandLineArgs::try_parse_from(args).unwrap();
+        assert_eq!(actual.baseline_commit, expected);
+    }
+
+    #[rstest]
+    #[case::empty(&["--baseline-commit="])]
+    #[case::empty_merge_base(&["--baseline-commit=merge-base()"])]
+    fn test_baseline_commit_when_invalid_then_error(#[case] args: &[&str]) {
+        assert!(CommandLineArgs::try_parse_from(args).is_err());
+    }
 }
@@ -1,6 +1,6 @@
 use std::ffi::OsString;
 use std::fmt::Display;
-use std::io::stdout;
+use std::io::{stderr, stdout};
 use std::path::PathBuf;
 use std::process::Command;
 
@@ -8,17 +8,19 @@ use anyhow::{anyhow, Result};
 use log::{debug, info, log_enabled, trace, Level};
 use tempfile::TempDir;
 
+use super::annotations::{AnnotationsFormat, GithubAnnotations};
+use super::callgrind::analysis::Config as AnalysisConfig;
 use super::callgrind::args::Args;
-use super::callgrind::flamegraph::{Config as FlamegraphConfig, Flamegraph};
 use super::callgrind::parser::{Parser, Sentinel};
 use super::callgrind::sentinel_parser::SentinelParser;
 use super::callgrind::summary_parser::SummaryParser;
-use super::callgrind::{CallgrindCommand, Regression};
+use super::callgrind::CallgrindCommand;
+use super::message::{Message, MessageFormat};
 use super::meta::Metadata;
 use super::print::{Formatter, Header, VerticalFormat};
 use super::summary::BenchmarkSummary;
 use super::tool::{RunOptions, ToolConfigs};
-use super::Config;
+use super::{history, report, Config};
 use crate::api::{self, BinaryBenchmark, BinaryBenchmarkConfig};
 use crate::error::Error;
 use crate::runner::print::tool_summary_header;
@@ -32,8 +34,7 @@ struct Assistant {
     kind: AssistantKind,
     bench: bool,
     callgrind_args: Args,
-    regression: Option<Regression>,
-    flamegraph: Option<FlamegraphConfig>,
+    analysis: AnalysisConfig,
     tools: ToolConfigs,
 }
 
@@ -61,8 +62,7 @@ struct BinBench {
     args: Vec<OsString>,
     options: RunOptions,
     callgrind_args: Args,
-    flamegraph: Option<FlamegraphConfig>,
-    regression: Option<Regression>,
+    analysis: AnalysisConfig,
     tools: ToolConfigs,
 }
 
@@ -98,8 +98,7 @@ impl Assistant {
         kind: AssistantKind,
         bench: bool,
         callgrind_args: Args,
-        regression: Option<Regression>,
-        flamegraph: Option<FlamegraphConfig>,
+        analysis: AnalysisConfig,
         tools: ToolConfigs,
     ) -> Self {
         Self {
@@ -107,12 +106,31 @@ impl Assistant {
             kind,
             bench,
             callgrind_args,
-            regression,
-            flamegraph,
+            analysis,
             tools,
         }
     }
 
+    /// Create the [`BenchmarkSummary`] of this benchmark without any results
+    fn create_summary(
+        &self,
+        config: &Config,
+        group: &Group,
+        summary_output: Option<SummaryOutput>,
+    ) -> BenchmarkSummary {
+        BenchmarkSummary::new(
+            BenchmarkKind::BinaryBenchmark,
+            config.meta.project_root.clone(),
+            config.package_dir.clone(),
+            config.bench_file.clone(),
+            config.bench_bin.clone(),
+            &[&group.module_path, &self.kind.id(), &self.name],
+            None,
+            None,
+            summary_output,
+        )
+    }
+
     /// Run the assistant and benchmark this run
     #[allow(clippy::too_many_lines)]
     fn run_bench(&self, config: &Config, group: &Group) -> Result<BenchmarkSummary> {
@@ -136,6 +154,8 @@ impl Assistant {
             &format!("{}.{}", &self.name, self.kind.id()),
         );
 
+        config.meta.git_baselines.load(&output_path)?;
+
         let log_path = output_path.to_log_output();
         log_path.init();
 
@@ -145,27 +165,22 @@ impl Assistant {
             output
         });
 
-        let mut benchmark_summary = BenchmarkSummary::new(
-            BenchmarkKind::BinaryBenchmark,
-            config.meta.project_root.clone(),
-            config.package_dir.clone(),
-            config.bench_file.clone(),
-            config.bench_bin.clone(),
-            &[&group.module_path, &self.kind.id(), &self.name],
-            None,
-            None,
-            summary_output,
-        );
+        let mut benchmark_summary = self.create_summary(config, group, summary_output);
 
+        let is_human = config.meta.args.message_format == MessageFormat::Human;
         let header = Header::from_segments(
             [&group.module_path, &self.kind.id(), &self.name],
             None,
             None,
         );
 
-        header.print();
-        if self.tools.has_tools_enabled() {
-            println!("{}", tool_summary_header(ValgrindTool::Callgrind));
+        if is_human {
+            header.print();
+            if self.tools.has_tools_enabled() {
+                println!("{}", tool_summary_header(ValgrindTool::Callgrind));
+            }
+        } else {
+            header.to_message().print()?;
         }
 
         let options = RunOptions {
@@ -184,6 +199,7 @@ impl Assistant {
 
         let sentinel = Sentinel::from_path(&config.module, &self.name);
         let new_costs = SentinelParser::new(&sentinel).parse(&output_path)?;
+        config.meta.git_baselines.save(&output_path)?;
 
         let old_output = output_path.to_old_output();
 
@@ -195,21 +211,26 @@ impl Assistant {
         };
 
         let costs_summary = CostsSummary::new(&new_costs, old_costs.as_ref());
-        let format = VerticalFormat::default().format(&costs_summary)?;
-        print!("{format}");
+        if is_human {
+            print!("{}", VerticalFormat::default().format(&costs_summary)?);
+        }
 
-        output.dump_log(log::Level::Info);
-        log_path.dump_log(log::Level::Info, &mut stdout())?;
+        let reports = self.analysis.create_reports(
+            &output_path,
+            Some(&sentinel),
+            &config.meta.project_root,
+            &header.to_title(),
+            is_human,
+        )?;
 
-        let (regressions, fail_fast) = if let Some(regression) = &self.regression {
-            (
-                regression.check_and_print(&costs_summary),
-                regression.fail_fast,
-            )
+        output.dump_log(log::Level::Info);
+        if is_human {
+            log_path.dump_log(log::Level::Info, &mut stdout())?;
         } else {
-            (vec![], false)
-        };
+            log_path.dump_log(log::Level::Info, &mut stderr())?;
+        }
 
+        let (regressions, fail_fast) = self.analysis.check_regressions(&costs_summary, is_human);
         let callgrind_summary = benchmark_summary
             .callgrind_summary
             .insert(CallgrindSummary::new(
@@ -217,7 +238,6 @@ impl Assistant {
                 vec![log_path.to_path()],
                 vec![output_path.to_path()],
             ));
-
         callgrind_summary.add_summary(
             &config.bench_bin,
             &executable_args,
@@ -225,15 +245,7 @@ impl Assistant {
             costs_summary,
             regressions,
         );
-
-        if let Some(flamegraph_config) = self.flamegraph.clone() {
-            callgrind_summary.flamegraphs = Flamegraph::new(header.to_title(), flamegraph_config)
-                .create(
-                &output_path,
-                Some(&sentinel),
-                &config.meta.project_root,
-            )?;
-        }
+        reports.store(callgrind_summary);
 
         benchmark_summary.tool_summaries = self.tools.run(
             &config.meta,
@@ -339,6 +351,26 @@ impl Default for BenchmarkAssistants {
 }
 
 impl BinBench {
+    /// Create the [`BenchmarkSummary`] of this benchmark without any results
+    fn create_summary(
+        &self,
+        config: &Config,
+        group: &Group,
+        summary_output: Option<SummaryOutput>,
+    ) -> BenchmarkSummary {
+        BenchmarkSummary::new(
+            BenchmarkKind::BinaryBenchmark,
+            config.meta.project_root.clone(),
+            config.package_dir.clone(),
+            config.bench_file.clone(),
+            config.bench_bin.clone(),
+            &[&group.module_path],
+            Some(self.id.clone()),
+            Some(self.to_string()),
+            summary_output,
+        )
+    }
+
     /// Run the binary benchmark
     ///
     /// This method sets `is_regressed` to true if a non-fatal regression occurred (but doesn't
@@ -358,6 +390,8 @@ impl BinBench {
             &format!("{}.{}", self.display, self.id),
         );
 
+        config.meta.git_baselines.load(&output_path)?;
+
         let log_path = output_path.to_log_output();
         log_path.init();
 
@@ -367,23 +401,17 @@ impl BinBench {
             output
         });
 
-        let mut benchmark_summary = BenchmarkSummary::new(
-            BenchmarkKind::BinaryBenchmark,
-            config.meta.project_root.clone(),
-            config.package_dir.clone(),
-            config.bench_file.clone(),
-            config.bench_bin.clone(),
-            &[&group.module_path],
-            Some(self.id.clone()),
-            Some(self.to_string()),
-            summary_output,
-        );
+        let mut benchmark_summary = self.create_summary(config, group, summary_output);
 
+        let is_human = config.meta.args.message_format == MessageFormat::Human;
         let header = Header::new(&group.module_path, self.id.clone(), self.to_string());
-        header.print();
-
-        if self.tools.has_tools_enabled() {
-            println!("{}", tool_summary_header(ValgrindTool::Callgrind));
+        if is_human {
+            header.print();
+            if self.tools.has_tools_enabled() {
+                println!("{}", tool_summary_header(ValgrindTool::Callgrind));
+            }
+        } else {
+            header.to_message().print()?;
         }
 
         let output = callgrind_command.run(
@@ -395,6 +423,7 @@ impl BinBench {
         )?;
 
         let new_costs = SummaryParser.parse(&output_path)?;
+        config.meta.git_baselines.save(&output_path)?;
 
         let old_output = output_path.to_old_output();
         #[allow(clippy::if_then_some_else_none)]
@@ -405,21 +434,27 @@ impl BinBench {
         };
 
         let costs_summary = CostsSummary::new(&new_costs, old_costs.as_ref());
-        let output_format = VerticalFormat::default().format(&costs_summary)?;
-        print!("{output_format}");
+        if is_human {
+            print!("{}", VerticalFormat::default().format(&costs_summary)?);
+        }
 
-        output.dump_log(log::Level::Info);
-        log_path.dump_log(log::Level::Info, &mut stdout())?;
+        let sentinel = self.options.entry_point.as_ref().map(Sentinel::new);
+        let reports = self.analysis.create_reports(
+            &output_path,
+            sentinel.as_ref(),
+            &config.meta.project_root,
+            &header.to_title(),
+            is_human,
+        )?;
 
-        let (regressions, fail_fast) = if let Some(regression) = &self.regression {
-            (
-                regression.check_and_print(&costs_summary),
-                regression.fail_fast,
-            )
+        output.dump_log(log::Level::Info);
+        if is_human {
+            log_path.dump_log(log::Level::Info, &mut stdout())?;
         } else {
-            (vec![], false)
-        };
+            log_path.dump_log(log::Level::Info, &mut stderr())?;
+        }
 
+        let (regressions, fail_fast) = self.analysis.check_regressions(&costs_summary, is_human);
         let callgrind_summary = benchmark_summary
             .callgrind_summary
             .insert(CallgrindSummary::new(
@@ -427,7 +462,6 @@ impl BinBench {
                 vec![log_path.to_path()],
                 vec![output_path.to_path()],
             ));
-
         callgrind_summary.add_summary(
             &self.command,
             &self.args,
@@ -435,16 +469,7 @@ impl BinBench {
             costs_summary,
             regressions,
         );
-
-        let sentinel = self.options.entry_point.as_ref().map(Sentinel::new);
-        if let Some(flamegraph_config) = self.flamegraph.clone() {
-            callgrind_summary.flamegraphs = Flamegraph::new(header.to_title(), flamegraph_config)
-                .create(
-                &output_path,
-                sentinel.as_ref(),
-                &config.meta.project_root,
-            )?;
-        }
+        reports.store(callgrind_summary);
 
         benchmark_summary.tool_summaries = self.tools.run(
             &config.meta,
@@ -478,17 +503,37 @@ impl Group {
         &self,
         assistant: &mut Assistant,
         is_regressed: &mut bool,
+        summaries: &mut Vec<BenchmarkSummary>,
         config: &Config,
     ) -> Result<()> {
-        if let Some(summary) = assistant.run(config, self)? {
+        let summary = match assistant.run(config, self) {
+            Ok(summary) => summary,
+            Err(error) => {
+                let summary = assistant
+                    .create_summary(config, self, None)
+                    .with_error(&error);
+                print_messages(config, &summary)?;
+                summaries.push(summary);
+                return Err(error);
+            }
+        };
+        if let Some(summary) = summary {
             summary.save()?;
-            summary.check_regression(is_regressed)?;
+            print_messages(config, &summary)?;
+            let result = summary.check_regression(is_regressed);
+            summaries.push(summary);
+            result?;
         }
 
         Ok(())
     }
 
-    fn run(&self, is_regressed: &mut bool, config: &Config) -> Result<()> {
+    fn run(
+        &self,
+        is_regressed: &mut bool,
+        summaries: &mut Vec<BenchmarkSummary>,
+        config: &Config,
+    ) -> Result<()> {
         let sandbox = if self.sandbox {
             debug!("Setting up sandbox");
             Some(Sandbox::setup(&self.fixtures)?)
@@ -503,25 +548,36 @@ impl Group {
         let mut assists = self.assists.clone();
 
         if let Some(before) = assists.before.as_mut() {
-            self.run_assistant(before, is_regressed, config)?;
+            self.run_assistant(before, is_regressed, summaries, config)?;
         }
 
         for bench in &self.benches {
             if let Some(setup) = assists.setup.as_mut() {
-                self.run_assistant(setup, is_regressed, config)?;
+                self.run_assistant(setup, is_regressed, summaries, config)?;
             }
 
-            let summary = bench.run(config, self)?;
+            let summary = match bench.run(config, self) {
+                Ok(summary) => summary,
+                Err(error) => {
+                    let summary = bench.create_summary(config, self, None).with_error(&error);
+                    print_messages(config, &summary)?;
+                    summaries.push(summary);
+                    return Err(error);
+                }
+            };
             summary.save()?;
-            summary.check_regression(is_regressed)?;
+            print_messages(config, &summary)?;
+            let result = summary.check_regression(is_regressed);
+            summaries.push(summary);
+            result?;
 
             if let Some(teardown) = assists.teardown.as_mut() {
-                self.run_assistant(teardown, is_regressed, config)?;
+                self.run_assistant(teardown, is_regressed, summaries, config)?;
             }
         }
 
         if let Some(after) = assists.after.as_mut() {
-            self.run_assistant(after, is_regressed, config)?;
+            self.run_assistant(after, is_regressed, summaries, config)?;
         }
 
         if let Some(sandbox) = sandbox {
@@ -543,7 +599,7 @@ impl Groups {
     ) -> Result<Vec<BinBench>> {
         let mut benches = vec![];
         let mut counter: usize = 0;
-        let meta_callgrind_args = meta.args.callgrind_args.clone().unwrap_or_default();
+        let meta_callgrind_args = meta.args.callgrind_args(module_path);
 
         for run in runs {
             if run.args.is_empty() {
@@ -564,11 +620,12 @@ impl Groups {
             };
             let config = group_config.clone().update_from_all([Some(&run.config)]);
             let envs = config.resolve_envs();
-            let flamegraph = config.flamegraph.map(std::convert::Into::into);
-            let regression = api::update_option(&config.regression, &meta.regression_config)
-                .map(std::convert::Into::into);
-            let callgrind_args =
+            let analysis = AnalysisConfig::new(&config, module_path, meta);
+            let mut callgrind_args =
                 Args::from_raw_args(&[&config.raw_callgrind_args, &meta_callgrind_args])?;
+            if analysis.hot_instructions.is_some() {
+                callgrind_args.set_dump_instr(true);
+            }
             let tools = ToolConfigs(config.tools.0.into_iter().map(Into::into).collect());
             for args in run.args {
                 let id = if let Some(id) = args.id {
@@ -591,8 +648,7 @@ impl Groups {
                         envs: envs.clone(),
                     },
                     callgrind_args: callgrind_args.clone(),
-                    flamegraph: flamegraph.clone(),
-                    regression: regression.clone(),
+                    analysis: analysis.clone(),
                     tools: tools.clone(),
                 });
             }
@@ -603,8 +659,7 @@ impl Groups {
     fn parse_assists(
         assists: Vec<crate::api::Assistant>,
         callgrind_args: &Args,
-        regression: Option<&Regression>,
-        flamegraph: Option<&FlamegraphConfig>,
+        analysis: &AnalysisConfig,
         tools: &ToolConfigs,
     ) -> BenchmarkAssistants {
         let mut bench_assists = BenchmarkAssistants::default();
@@ -616,8 +671,7 @@ impl Groups {
                         AssistantKind::Before,
                         assist.bench,
                         callgrind_args.clone(),
-                        regression.cloned(),
-                        flamegraph.cloned(),
+                        analysis.clone(),
                         tools.clone(),
                     ));
                 }
@@ -627,8 +681,7 @@ impl Groups {
                         AssistantKind::After,
                         assist.bench,
                         callgrind_args.clone(),
-                        regression.cloned(),
-                        flamegraph.cloned(),
+                        analysis.clone(),
                         tools.clone(),
                     ));
                 }
@@ -638,8 +691,7 @@ impl Groups {
                         AssistantKind::Setup,
                         assist.bench,
                         callgrind_args.clone(),
-                        regression.cloned(),
-                        flamegraph.cloned(),
+                        analysis.clone(),
                         tools.clone(),
                     ));
                 }
@@ -649,8 +701,7 @@ impl Groups {
                         AssistantKind::Teardown,
                         assist.bench,
                         callgrind_args.clone(),
-                        regression.cloned(),
-                        flamegraph.cloned(),
+                        analysis.clone(),
                         tools.clone(),
                     ));
                 }
@@ -666,7 +717,6 @@ impl Groups {
         meta: &Metadata,
     ) -> Result<Self> {
         let global_config = benchmark.config;
-        let meta_callgrind_args = meta.args.callgrind_args.clone().unwrap_or_default();
 
         let mut groups = vec![];
         for group in benchmark.groups {
@@ -675,13 +725,19 @@ impl Groups {
             } else {
                 module.to_owned()
             };
-            let group_config = global_config
-                .clone()
-                .update_from_all([group.config.as_ref()]);
+            let group_config = meta
+                .project_config
+                .binary_benchmark_config(&module_path)
+                .update_from_all([Some(&global_config), group.config.as_ref()]);
+            let meta_callgrind_args = meta.args.callgrind_args(&module_path);
+            let analysis = AnalysisConfig::new(&group_config, &module_path, meta);
             let benches =
                 Self::parse_runs(&module_path, &group.cmd, group.benches, &group_config, meta)?;
-            let callgrind_args =
+            let mut callgrind_args =
                 Args::from_raw_args(&[&group_config.raw_callgrind_args, &meta_callgrind_args])?;
+            if analysis.hot_instructions.is_some() {
+                callgrind_args.set_dump_instr(true);
+            }
             let config = Group {
                 id: group.id,
                 module_path,
@@ -691,10 +747,7 @@ impl Groups {
                 assists: Self::parse_assists(
                     group.assists,
                     &callgrind_args,
-                    api::update_option(&group_config.regression, &meta.regression_config)
-                        .map(std::convert::Into::into)
-                        .as_ref(),
-                    group_config.flamegraph.map(Into::into).as_ref(),
+                    &analysis,
                     &ToolConfigs(group_config.tools.0.into_iter().map(Into::into).collect()),
                 ),
             };
@@ -712,9 +765,15 @@ impl Groups {
     /// * [`Error::RegressionError`] if a regression occurred.
     fn run(&self, config: &Config) -> Result<()> {
         let mut is_regressed = false;
-        for group in &self.0 {
-            group.run(&mut is_regressed, config)?;
-        }
+        let mut summaries = vec![];
+        let result = self
+            .0
+            .iter()
+            .try_for_each(|group| group.run(&mut is_regressed, &mut summaries, config));
+
+        history::save(&config.meta, &config.module, &summaries)?;
+        report::create(&config.meta, &config.module, summaries)?;
+        result?;
 
         if is_regressed {
             Err(Error::RegressionError(false).into())
@@ -778,6 +837,18 @@ impl Sandbox {
     }
 }
 
+/// Print the json messages with `--message-format=json` and the annotations of the finished
+/// benchmark
+fn print_messages(config: &Config, summary: &BenchmarkSummary) -> Result<()> {
+    if config.meta.args.message_format == MessageFormat::Json {
+        Message::print_summary(summary)?;
+    }
+    if let Some(AnnotationsFormat::Github) = config.meta.annotations {
+        GithubAnnotations::print(summary);
+    }
+    Ok(())
+}
+
 pub fn run(binary_benchmark: BinaryBenchmark, config: Config) -> Result<()> {
     Runner::generate(binary_benchmark, config)?.run()
 }
@@ -0,0 +1,321 @@
+//! The analyses of the callgrind output of a single benchmark
+//!
+//! The [`Config`] bundles the regression checks, reports and exports configured for a benchmark,
+//! so the library benchmarks, binary benchmarks and their assistants create them the same way.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::annotate::{Annotate, Config as AnnotateConfig};
+use super::export::{Config as ExportConfig, Exporter};
+use super::flamegraph::{Config as FlamegraphConfig, Flamegraph};
+use super::function_diff::{Config as FunctionDiffConfig, FunctionDiff};
+use super::hashmap_parser::{CallgrindMap, HashMapParser};
+use super::hot_functions::{Config as HotFunctionsConfig, HotFunctions};
+use super::hot_instructions::{Config as HotInstructionsConfig, HotInstructions};
+use super::parser::{Parser, Sentinel};
+use super::Regression;
+use crate::api::{self, BinaryBenchmarkConfig, EventKind, FlamegraphKind, LibraryBenchmarkConfig};
+use crate::runner::meta::Metadata;
+use crate::runner::print::{format_function_diff, format_hot_functions};
+use crate::runner::summary::{
+    AnnotateOutput, CallgrindRegressionSummary, CallgrindSummary, CostsSummary, ExportSummary,
+    FlamegraphSummary, FunctionDiffOutput, HotFunctionsSummary,
+};
+use crate::runner::tool::ToolOutputPath;
+
+/// The configuration of all analyses of the callgrind output of a benchmark
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub regression: Option<Regression>,
+    pub flamegraph: Option<FlamegraphConfig>,
+    pub hot_functions: Option<HotFunctionsConfig>,
+    pub function_diff: Option<FunctionDiffConfig>,
+    pub annotate: Option<AnnotateConfig>,
+    pub hot_instructions: Option<HotInstructionsConfig>,
+    pub export: ExportConfig,
+}
+
+/// The reports created from the callgrind output of a benchmark
+#[derive(Debug, Default)]
+pub struct Reports {
+    pub hot_functions: Option<HotFunctionsSummary>,
+    pub function_diff: Option<FunctionDiffOutput>,
+    pub annotate: Option<AnnotateOutput>,
+    pub hot_instructions: Option<PathBuf>,
+    pub flamegraphs: Vec<FlamegraphSummary>,
+    pub exports: Vec<ExportSummary>,
+}
+
+/// The analysis options shared by the [`LibraryBenchmarkConfig`] and [`BinaryBenchmarkConfig`]
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkConfig<'a> {
+    regression: &'a Option<api::RegressionConfig>,
+    flamegraph: &'a Option<api::FlamegraphConfig>,
+    hot_functions: &'a Option<api::HotFunctionsConfig>,
+    function_diff: &'a Option<api::FunctionDiffConfig>,
+    annotate: &'a Option<api::AnnotateConfig>,
+    hot_instructions: &'a Option<api::HotInstructionsConfig>,
+    pprof: &'a Option<api::PprofConfig>,
+    speedscope: &'a Option<api::SpeedscopeConfig>,
+    dot: &'a Option<api::DotConfig>,
+}
+
+impl Config {
+    /// Create the `Config` of the library or binary benchmark with the `module_path`
+    pub fn new<'a, T>(config: T, module_path: &str, meta: &Metadata) -> Self
+    where
+        T: Into<BenchmarkConfig<'a>>,
+    {
+        let config = config.into();
+        Self {
+            regression: api::update_option(
+                config.regression,
+                &meta.args.regression_config(module_path),
+            )
+            .map(Into::into),
+            flamegraph: config.flamegraph.clone().map(Into::into),
+            hot_functions: HotFunctionsConfig::new(
+                config.hot_functions.clone(),
+                meta.args.show_functions,
+            ),
+            function_diff: config.function_diff.clone().map(Into::into),
+            annotate: config.annotate.clone().map(Into::into),
+            hot_instructions: config.hot_instructions.clone().map(Into::into),
+            export: ExportConfig::new(
+                config.pprof.clone(),
+                config.speedscope.clone(),
+                config.dot.clone(),
+                &meta.args.export_formats(),
+            ),
+        }
+    }
+
+    /// Create all configured reports and exports of the callgrind output
+    ///
+    /// The callgrind output and the old output are parsed only once and shared by all reports. The
+    /// hot functions and the function-level differential report are printed to stdout if
+    /// `is_human` is true. The `title` is the title of the benchmark used in the reports.
+    pub fn create_reports(
+        &self,
+        output_path: &ToolOutputPath,
+        sentinel: Option<&Sentinel>,
+        project_root: &Path,
+        title: &str,
+        is_human: bool,
+    ) -> Result<Reports> {
+        if !self.has_reports() {
+            return Ok(Reports::default());
+        }
+
+        let parser = HashMapParser {
+            sentinel: sentinel.cloned(),
+            project_root: project_root.to_owned(),
+        };
+        let mut map = parser.parse(output_path)?;
+        let old_output = output_path.to_old_output();
+        #[allow(clippy::if_then_some_else_none)]
+        let mut old_map = if self.uses_old_output() && old_output.exists() {
+            Some(parser.parse(&old_output)?)
+        } else {
+            None
+        };
+
+        if self.event_kinds(&map).iter().any(EventKind::is_derived) {
+            map.make_summary()?;
+            if let Some(old_map) = old_map.as_mut() {
+                old_map.make_summary()?;
+            }
+        }
+        let old_map = old_map.as_ref();
+
+        let hot_functions = self
+            .hot_functions
+            .clone()
+            .map(|config| HotFunctions::new(config).summarize(&map, old_map));
+        if is_human {
+            if let Some(hot_functions) = &hot_functions {
+                print!("{}", format_hot_functions(hot_functions)?);
+            }
+        }
+
+        let function_diff = if let Some(config) = self.function_diff.clone() {
+            let limit = config.limit;
+            FunctionDiff::new(config)
+                .create(output_path, &map, old_map, title.to_owned())?
+                .map(|(report, output)| -> Result<_> {
+                    if is_human {
+                        print!("{}", format_function_diff(&report, limit)?);
+                    }
+                    Ok(output)
+                })
+                .transpose()?
+        } else {
+            None
+        };
+
+        let annotate = self
+            .annotate
+            .clone()
+            .map(|config| {
+                Annotate::new(config).create(
+                    output_path,
+                    &map,
+                    old_map,
+                    project_root,
+                    title.to_owned(),
+                )
+            })
+            .transpose()?;
+
+        let hot_instructions = self
+            .hot_instructions
+            .clone()
+            .map(|config| {
+                HotInstructions::new(config).create(
+                    output_path,
+                    &map,
+                    project_root,
+                    title.to_owned(),
+                )
+            })
+            .transpose()?;
+
+        let flamegraphs = if let Some(config) = self.flamegraph.clone() {
+            Flamegraph::new(title.to_owned(), config).create_from_maps(
+                output_path,
+                &map,
+                old_map,
+            )?
+        } else {
+            vec![]
+        };
+
+        let exports = Exporter::new(self.export.clone()).create(output_path, &map, old_map)?;
+
+        Ok(Reports {
+            hot_functions,
+            function_diff,
+            annotate,
+            hot_instructions,
+            flamegraphs,
+            exports,
+        })
+    }
+
+    /// Check the `costs_summary` for regressions and print them if `is_human` is true
+    ///
+    /// Returns the detected regressions and if they should fail the benchmark run immediately.
+    pub fn check_regressions(
+        &self,
+        costs_summary: &CostsSummary,
+        is_human: bool,
+    ) -> (Vec<CallgrindRegressionSummary>, bool) {
+        if let Some(regression) = &self.regression {
+            let regressions = if is_human {
+                regression.check_and_print(costs_summary)
+            } else {
+                regression.check(costs_summary)
+            };
+            (regressions, regression.fail_fast)
+        } else {
+            (vec![], false)
+        }
+    }
+
+    /// Return true if any report or export is configured
+    fn has_reports(&self) -> bool {
+        self.hot_functions.is_some()
+            || self.function_diff.is_some()
+            || self.annotate.is_some()
+            || self.hot_instructions.is_some()
+            || self
+                .flamegraph
+                .as_ref()
+                .map_or(false, |config| config.kind != FlamegraphKind::None)
+            || !self.export.is_empty()
+    }
+
+    /// Return true if any configured report compares with the old callgrind output
+    fn uses_old_output(&self) -> bool {
+        self.hot_functions.is_some()
+            || self.function_diff.is_some()
+            || self.annotate.is_some()
+            || self.flamegraph.is_some()
+            || self
+                .export
+                .dot
+                .as_ref()
+                .map_or(false, |config| config.differential)
+    }
+
+    /// Return the [`EventKind`]s of all configured reports and exports
+    fn event_kinds(&self, map: &CallgrindMap) -> Vec<EventKind> {
+        let mut event_kinds = vec![];
+        event_kinds.extend(self.hot_functions.as_ref().map(|config| config.event_kind));
+        if let Some(config) = &self.function_diff {
+            event_kinds.extend(config.event_kinds.iter().copied());
+        }
+        event_kinds.extend(self.annotate.as_ref().map(|config| config.event_kind));
+        event_kinds.extend(
+            self.hot_instructions
+                .as_ref()
+                .map(|config| config.event_kind),
+        );
+        if let Some(config) = &self.flamegraph {
+            event_kinds.extend(config.event_kinds.iter().copied());
+        }
+        event_kinds.extend(self.export.event_kinds(map));
+        event_kinds
+    }
+}
+
+impl Reports {
+    /// Move the reports into the `callgrind_summary`
+    ///
+    /// The hot functions belong to the summary of the last callgrind run, so this method needs to
+    /// be called after [`CallgrindSummary::add_summary`].
+    pub fn store(self, callgrind_summary: &mut CallgrindSummary) {
+        if let Some(summary) = callgrind_summary.summaries.last_mut() {
+            summary.hot_functions = self.hot_functions;
+        }
+        callgrind_summary.function_diff = self.function_diff;
+        callgrind_summary.annotate = self.annotate;
+        callgrind_summary.hot_instructions = self.hot_instructions;
+        callgrind_summary.flamegraphs = self.flamegraphs;
+        callgrind_summary.exports = self.exports;
+    }
+}
+
+impl<'a> From<&'a LibraryBenchmarkConfig> for BenchmarkConfig<'a> {
+    fn from(value: &'a LibraryBenchmarkConfig) -> Self {
+        Self {
+            regression: &value.regression,
+            flamegraph: &value.flamegraph,
+            hot_functions: &value.hot_functions,
+            function_diff: &value.function_diff,
+            annotate: &value.annotate,
+            hot_instructions: &value.hot_instructions,
+            pprof: &value.pprof,
+            speedscope: &value.speedscope,
+            dot: &value.dot,
+        }
+    }
+}
+
+impl<'a> From<&'a BinaryBenchmarkConfig> for BenchmarkConfig<'a> {
+    fn from(value: &'a BinaryBenchmarkConfig) -> Self {
+        Self {
+            regression: &value.regression,
+            flamegraph: &value.flamegraph,
+            hot_functions: &value.hot_functions,
+            function_diff: &value.function_diff,
+            annotate: &value.annotate,
+            hot_instructions: &value.hot_instructions,
+            pprof: &value.pprof,
+            speedscope: &value.speedscope,
+            dot: &value.dot,
+        }
+    }
+}
@@ -0,0 +1,520 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as FmtWrite;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+
+use super::hashmap_parser::{CallgrindMap, SourcePath};
+use crate::api::{self, EventKind};
+use crate::runner::summary::{AnnotateOutput, CostsDiff};
+use crate::runner::tool::ToolOutputPath;
+use crate::util::{escape_html, format_pct, to_string_signed_short};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub event_kind: EventKind,
+    pub context: usize,
+    pub limit: usize,
+}
+
+pub struct Annotate {
+    pub config: Config,
+}
+
+/// The source line annotation report of a single benchmark
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    pub benchmark: String,
+    pub event_kind: EventKind,
+    /// The total costs of the whole benchmark run including the costs outside of the project
+    pub total: CostsDiff,
+    /// The annotated files sorted by their costs starting with the most expensive file
+    pub files: Vec<AnnotatedFile>,
+}
+
+/// A source file of the project with the exclusive costs of its lines
+#[derive(Debug, PartialEq)]
+pub struct AnnotatedFile {
+    /// The path relative to the project root
+    pub path: PathBuf,
+    pub total: CostsDiff,
+    pub lines: BTreeMap<u64, CostsDiff>,
+    /// The lines of the source file if the file could be read
+    pub source: Option<Vec<String>>,
+}
+
+impl From<api::AnnotateConfig> for Config {
+    fn from(value: api::AnnotateConfig) -> Self {
+        Self {
+            event_kind: value.event_kind.unwrap_or(EventKind::Ir),
+            context: value.context.unwrap_or(8),
+            limit: value.limit.unwrap_or(10),
+        }
+    }
+}
+
+impl Annotate {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Create the annotation [`Report`] of the parsed `map` and save it as text and html file next
+    /// to the callgrind output
+    ///
+    /// The costs of the `old_map` are included in the report if present. Reports of
+    /// previous benchmark runs are overwritten.
+    pub fn create(
+        &self,
+        callgrind_output_path: &ToolOutputPath,
+        map: &CallgrindMap,
+        old_map: Option<&CallgrindMap>,
+        project_root: &Path,
+        benchmark: String,
+    ) -> Result<AnnotateOutput> {
+        if old_map.is_none() {
+            debug!("No old callgrind output found. Annotating without old costs.");
+        }
+        let report = self.annotate(benchmark, map, old_map, project_root);
+
+        let path = callgrind_output_path.to_path();
+        let output = AnnotateOutput {
+            text_path: path.with_extension("annotate.txt"),
+            html_path: path.with_extension("annotate.html"),
+        };
+        std::fs::write(&output.text_path, self.to_text(&report)).with_context(|| {
+            format!(
+                "Failed writing annotate report '{}'",
+                output.text_path.display()
+            )
+        })?;
+        std::fs::write(&output.html_path, self.to_html(&report)).with_context(|| {
+            format!(
+                "Failed writing annotate report '{}'",
+                output.html_path.display()
+            )
+        })?;
+
+        Ok(output)
+    }
+
+    /// Map the exclusive costs of the `map` and `old_map` to the source lines of the project files
+    ///
+    /// Only files relative to the `project_root` are annotated. Costs without line information are
+    /// only part of the total costs.
+    pub fn annotate(
+        &self,
+        benchmark: String,
+        map: &CallgrindMap,
+        old_map: Option<&CallgrindMap>,
+        project_root: &Path,
+    ) -> Report {
+        let event_kind = self.config.event_kind;
+        let new_lines = collect_lines(map, event_kind);
+        let old_lines = old_map.map(|old_map| collect_lines(old_map, event_kind));
+
+        let mut paths = new_lines.keys().collect::<Vec<&PathBuf>>();
+        if let Some(old_lines) = &old_lines {
+            paths.extend(old_lines.keys());
+        }
+        paths.sort();
+        paths.dedup();
+
+        let empty = BTreeMap::new();
+        let mut files = paths
+            .into_iter()
+            .map(|path| {
+                let new = new_lines.get(path).unwrap_or(&empty);
+                let old = old_lines
+                    .as_ref()
+                    .map(|old| old.get(path).unwrap_or(&empty));
+
+                let mut numbers = new.keys().collect::<Vec<&u64>>();
+                if let Some(old) = old {
+                    numbers.extend(old.keys());
+                }
+                numbers.sort();
+                numbers.dedup();
+
+                let lines = numbers
+                    .into_iter()
+                    .map(|number| {
+                        let old_cost = old.map(|old| old.get(number).copied().unwrap_or(0));
+                        let new_cost = new.get(number).copied().unwrap_or(0);
+                        (*number, CostsDiff::new(Some(new_cost), old_cost))
+                    })
+                    .collect::<BTreeMap<u64, CostsDiff>>();
+
+                let source = std::fs::read_to_string(project_root.join(path))
+                    .map(|content| content.lines().map(ToOwned::to_owned).collect())
+                    .map_err(|error| {
+                        warn!(
+                            "Unable to read source file '{}' for the annotate report: {error}",
+                            path.display()
+                        );
+                    })
+                    .ok();
+
+                AnnotatedFile {
+                    path: path.clone(),
+                    total: CostsDiff::new(
+                        Some(new.values().sum()),
+                        old.map(|old| old.values().sum()),
+                    ),
+                    lines,
+                    source,
+                }
+            })
+            .collect::<Vec<AnnotatedFile>>();
+        files.sort_by(|a, b| {
+            b.total
+                .new
+                .cmp(&a.total.new)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+
+        Report {
+            benchmark,
+            event_kind,
+            total: CostsDiff::new(
+                Some(total_costs(map, event_kind)),
+                old_map.map(|old_map| total_costs(old_map, event_kind)),
+            ),
+            files,
+        }
+    }
+
+    /// Return the hottest lines of all files as (file index, line number, costs)
+    fn hottest_lines<'report>(
+        &self,
+        report: &'report Report,
+    ) -> Vec<(usize, u64, &'report CostsDiff)> {
+        let mut hottest = report
+            .files
+            .iter()
+            .enumerate()
+            .flat_map(|(index, file)| {
+                file.lines
+                    .iter()
+                    .filter(|(_, costs)| costs.new.map_or(false, |cost| cost > 0))
+                    .map(move |(number, costs)| (index, *number, costs))
+            })
+            .collect::<Vec<(usize, u64, &CostsDiff)>>();
+        hottest.sort_by(|a, b| {
+            b.2.new
+                .cmp(&a.2.new)
+                .then_with(|| (a.0, a.1).cmp(&(b.0, b.1)))
+        });
+        hottest.truncate(self.config.limit);
+        hottest
+    }
+
+    /// Convert the [`Report`] into a text report similar to the output of `callgrind_annotate`
+    ///
+    /// Only the lines with costs and the surrounding `context` lines of the source files are shown.
+    pub fn to_text(&self, report: &Report) -> String {
+        let with_old = report.total.old.is_some();
+        let total = report.total.new.unwrap_or(0);
+        let mut result = String::new();
+
+        writeln!(result, "{}", "-".repeat(80)).unwrap();
+        writeln!(result, "-- Annotate: {}", report.benchmark).unwrap();
+        writeln!(result, "-- Event: {}", report.event_kind).unwrap();
+        writeln!(result, "{}", "-".repeat(80)).unwrap();
+        writeln!(result).unwrap();
+        writeln!(
+            result,
+            "{}  Total",
+            format_columns(&report.total, total, with_old)
+        )
+        .unwrap();
+
+        writeln!(result).unwrap();
+        writeln!(result, "{}", "-".repeat(80)).unwrap();
+        writeln!(result, "-- Hottest lines").unwrap();
+        writeln!(result, "{}", "-".repeat(80)).unwrap();
+        writeln!(result).unwrap();
+        for (index, number, costs) in self.hottest_lines(report) {
+            writeln!(
+                result,
+                "{}  {}:{number}",
+                format_columns(costs, total, with_old),
+                report.files[index].path.display()
+            )
+            .unwrap();
+        }
+
+        for file in &report.files {
+            writeln!(result).unwrap();
+            writeln!(result, "{}", "-".repeat(80)).unwrap();
+            writeln!(result, "-- File: {}", file.path.display()).unwrap();
+            writeln!(result, "{}", "-".repeat(80)).unwrap();
+            writeln!(result).unwrap();
+            writeln!(
+                result,
+                "{}  Total",
+                format_columns(&file.total, total, with_old)
+            )
+            .unwrap();
+            writeln!(result).unwrap();
+
+            let mut last = None;
+            for number in self.visible_lines(file) {
+                if last.map_or(true, |last| last + 1 != number) {
+                    writeln!(result, "-- line {number} {}", "-".repeat(40)).unwrap();
+                }
+                last = Some(number);
+
+                let columns = file.lines.get(&number).map_or_else(
+                    || format_empty_columns(with_old),
+                    |costs| format_columns(costs, total, with_old),
+                );
+                let line = format!(
+                    "{columns}  {number:>5} {}",
+                    source_line(file, number).unwrap_or("<source not available>")
+                );
+                writeln!(result, "{}", line.trim_end()).unwrap();
+            }
+        }
+
+        result
+    }
+
+    /// Return the numbers of the lines with costs and the `context` lines around them
+    fn visible_lines(&self, file: &AnnotatedFile) -> Vec<u64> {
+        let max = file.source.as_ref().map_or(0, |source| source.len() as u64);
+        let context = self.config.context as u64;
+
+        let mut visible: Vec<u64> = vec![];
+        for number in file.lines.keys().filter(|number| **number > 0) {
+            let start = number.saturating_sub(context).max(1);
+            let end = (number + context).min(max).max(*number);
+            let start = visible.last().map_or(start, |last| start.max(last + 1));
+            // Lines after the end of the source file have no context
+            visible.extend((start..=end).filter(|line| *line <= max || line == number));
+        }
+        visible
+    }
+
+    /// Convert the [`Report`] into a self-contained html page with the hottest lines highlighted
+    pub fn to_html(&self, report: &Report) -> String {
+        let with_old = report.total.old.is_some();
+        let total = report.total.new.unwrap_or(0);
+        let hottest = self.hottest_lines(report);
+        let mut result = String::new();
+
+        let title = format!("Annotate: {}", escape_html(&report.benchmark));
+        writeln!(result, "<!DOCTYPE html>").unwrap();
+        writeln!(result, "<html>").unwrap();
+        writeln!(result, "<head>").unwrap();
+        writeln!(result, "<meta charset=\"utf-8\">").unwrap();
+        writeln!(result, "<title>{title}</title>").unwrap();
+        writeln!(result, "<style>{STYLE}</style>").unwrap();
+        writeln!(result, "</head>").unwrap();
+        writeln!(result, "<body>").unwrap();
+        writeln!(result, "<h1>{title}</h1>").unwrap();
+        writeln!(
+            result,
+            "<p>Event: {} | Total: {}</p>",
+            report.event_kind,
+            format_html_total(&report.total)
+        )
+        .unwrap();
+
+        writeln!(result, "<h2>Hottest lines</h2>").unwrap();
+        writeln!(result, "<table>").unwrap();
+        writeln!(
+            result,
+            "<tr>{}<th>Location</th></tr>",
+            html_header(with_old)
+        )
+        .unwrap();
+        for (index, number, costs) in &hottest {
+            let file = &report.files[*index];
+            writeln!(
+                result,
+                "<tr>{}<td><a href=\"#f{index}-l{number}\">{}:{number}</a></td></tr>",
+                html_columns(costs, total, with_old),
+                escape_html(&file.path.display().to_string())
+            )
+            .unwrap();
+        }
+        writeln!(result, "</table>").unwrap();
+
+        for (index, file) in report.files.iter().enumerate() {
+            let max = file
+                .lines
+                .values()
+                .filter_map(|costs| costs.new)
+                .max()
+                .unwrap_or(0);
+            let numbers = match &file.source {
+                Some(source) => (1..=source.len() as u64).collect::<Vec<u64>>(),
+                None => file.lines.keys().copied().collect(),
+            };
+
+            writeln!(
+                result,
+                "<h2 id=\"f{index}\">{}</h2>",
+                escape_html(&file.path.display().to_string())
+            )
+            .unwrap();
+            writeln!(result, "<p>Total: {}</p>", format_html_total(&file.total)).unwrap();
+            writeln!(result, "<table class=\"source\">").unwrap();
+            writeln!(
+                result,
+                "<tr>{}<th>Line</th><th>Source</th></tr>",
+                html_header(with_old)
+            )
+            .unwrap();
+            for number in numbers {
+                let costs = file.lines.get(&number);
+                let is_hot = hottest
+                    .iter()
+                    .any(|(hot_index, hot_number, _)| *hot_index == index && *hot_number == number);
+                #[allow(clippy::cast_precision_loss)]
+                let style = match costs.and_then(|costs| costs.new) {
+                    Some(cost) if cost > 0 && max > 0 => format!(
+                        " style=\"background-color: rgba(255, 80, 0, {:.3})\"",
+                        0.05f64 + cost as f64 / max as f64 * 0.55f64
+                    ),
+                    _ => String::new(),
+                };
+                writeln!(
+                    result,
+                    "<tr id=\"f{index}-l{number}\"{}{style}>{}<td \
+                     class=\"number\">{number}</td><td><pre>{}</pre></td></tr>",
+                    if is_hot { " class=\"hot\"" } else { "" },
+                    costs.map_or_else(
+                        || html_empty_columns(with_old),
+                        |costs| html_columns(costs, total, with_old)
+                    ),
+                    escape_html(source_line(file, number).unwrap_or(""))
+                )
+                .unwrap();
+            }
+            writeln!(result, "</table>").unwrap();
+        }
+
+        writeln!(result, "</body>").unwrap();
+        writeln!(result, "</html>").unwrap();
+        result
+    }
+}
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; } table { border-collapse: \
+                     collapse; } th, td { padding: 0 0.5em; text-align: right; white-space: \
+                     nowrap; } td:last-child, th:last-child { text-align: left; } pre { margin: \
+                     0; font-family: monospace; } tr.hot td { font-weight: bold; } td.number { \
+                     color: #888; }";
+
+/// Sum up the exclusive costs of the `map` by source file (relative to the project root) and line
+fn collect_lines(
+    map: &CallgrindMap,
+    event_kind: EventKind,
+) -> HashMap<PathBuf, BTreeMap<u64, u64>> {
+    let mut files: HashMap<PathBuf, BTreeMap<u64, u64>> = HashMap::new();
+    for (id, value) in map.iter() {
+        if let Some(SourcePath::Relative(path)) = &id.file {
+            for (number, costs) in &value.lines {
+                let cost = costs.cost_by_kind(&event_kind).unwrap_or(0);
+                if *number > 0 && cost > 0 {
+                    *files
+                        .entry(path.clone())
+                        .or_default()
+                        .entry(*number)
+                        .or_default() += cost;
+                }
+            }
+        }
+    }
+    files
+}
+
+fn total_costs(map: &CallgrindMap, event_kind: EventKind) -> u64 {
+    map.iter()
+        .filter_map(|(_, value)| value.self_costs.cost_by_kind(&event_kind))
+        .sum()
+}
+
+fn source_line(file: &AnnotatedFile, number: u64) -> Option<&str> {
+    let index = usize::try_from(number).ok()?.checked_sub(1)?;
+    file.source
+        .as_ref()
+        .and_then(|source| source.get(index))
+        .map(String::as_str)
+}
+
+fn format_change(costs: &CostsDiff) -> String {
+    match costs.diff_pct {
+        Some(pct) if pct.is_finite() => {
+            format!("{:+} ({}%)", costs.delta(), to_string_signed_short(pct))
+        }
+        _ => format!("{:+}", costs.delta()),
+    }
+}
+
+fn format_columns(costs: &CostsDiff, total: u64, with_old: bool) -> String {
+    let new = costs.new.unwrap_or(0);
+    let mut result = format!("{new:>12} {:>8}", format_pct(new, total));
+    if with_old {
+        write!(
+            result,
+            " {:>12} {:>24}",
+            costs.old.unwrap_or(0),
+            format_change(costs)
+        )
+        .unwrap();
+    }
+    result
+}
+
+fn format_empty_columns(with_old: bool) -> String {
+    if with_old {
+        format!("{:>12} {:>8} {:>12} {:>24}", ".", "", ".", "")
+    } else {
+        format!("{:>12} {:>8}", ".", "")
+    }
+}
+
+fn format_html_total(costs: &CostsDiff) -> String {
+    match costs.old {
+        Some(old) => format!(
+            "{} (old: {old}, change: {})",
+            costs.new.unwrap_or(0),
+            format_change(costs)
+        ),
+        None => costs.new.unwrap_or(0).to_string(),
+    }
+}
+
+fn html_header(with_old: bool) -> &'static str {
+    if with_old {
+        "<th>New</th><th>%</th><th>Old</th><th>Change</th>"
+    } else {
+        "<th>Cost</th><th>%</th>"
+    }
+}
+
+fn html_columns(costs: &CostsDiff, total: u64, with_old: bool) -> String {
+    let new = costs.new.unwrap_or(0);
+    let mut result = format!("<td>{new}</td><td>{}</td>", format_pct(new, total));
+    if with_old {
+        write!(
+            result,
+            "<td>{}</td><td>{}</td>",
+            costs.old.unwrap_or(0),
+            format_change(costs)
+        )
+        .unwrap();
+    }
+    result
+}
+
+fn html_empty_columns(with_old: bool) -> String {
+    if with_old {
+        "<td></td><td></td><td></td><td></td>".to_owned()
+    } else {
+        "<td></td><td></td>".to_owned()
+    }
+}
@@ -112,6 +112,10 @@ impl Args {
         self.toggle_collect.push_front(arg.to_owned());
     }
 
+    pub fn set_dump_instr(&mut self, value: bool) {
+        self.dump_instr = value;
+    }
+
     pub fn set_output_file<T>(&mut self, arg: T)
     where
         T: AsRef<Path>,
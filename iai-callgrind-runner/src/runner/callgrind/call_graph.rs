@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
+
+use super::hashmap_parser::{CallgrindMap, Id, SourcePath, Value};
+use super::model::Costs;
+use crate::api::EventKind;
+
+/// A function identified by its name and object
+///
+/// Callgrind splits the costs of a function with inlined code by the source file of the inlined
+/// code. In the call graph all these parts belong to the same function.
+pub type FunctionKey<'map> = (&'map str, Option<&'map SourcePath>);
+
+/// A single frame of a call stack
+///
+/// The `id` is the part of the function (see [`FunctionKey`]) this frame belongs to. The `line` is
+/// the line of the call site if this frame calls another function or else the line of the costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame<'map> {
+    pub key: FunctionKey<'map>,
+    pub id: &'map Id,
+    pub line: u64,
+}
+
+#[derive(Debug)]
+struct Call<'map> {
+    id: &'map Id,
+    line: u64,
+    costs: Vec<u64>,
+}
+
+#[derive(Debug)]
+struct Function<'map> {
+    /// The part of the function with the most expensive calls to this function or the first part
+    /// if this function is never called
+    id: &'map Id,
+    lines: Vec<(&'map Id, u64, Vec<u64>)>,
+    calls: IndexMap<FunctionKey<'map>, Call<'map>>,
+}
+
+/// The `CallGraph` of a [`CallgrindMap`] with the costs of the configured [`EventKind`]s
+///
+/// Callgrind doesn't record full call stacks but only the costs of the calls between two
+/// functions. The call stacks are reconstructed by walking the call graph starting at the roots.
+/// The inclusive costs of a call are distributed proportionally over the calls of the called
+/// function. Direct recursive calls are already part of the costs of the function. Cycles are cut
+/// off at the first repetition of a function in the stack and the inclusive costs of the call
+/// closing the cycle are attributed to this repeated frame, so no costs get lost.
+#[derive(Debug)]
+pub struct CallGraph<'map> {
+    functions: HashMap<FunctionKey<'map>, Function<'map>>,
+    incoming: HashMap<FunctionKey<'map>, Vec<u64>>,
+    roots: Vec<FunctionKey<'map>>,
+    num_event_kinds: usize,
+}
+
+/// Round the fractional costs of the call stacks one after another
+///
+/// The running sum is rounded instead of the single costs, so the rounding errors don't add up and
+/// the sum of all rounded costs equals the total costs.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunningRound {
+    sum: f64,
+    rounded_sum: u64,
+}
+
+impl<'map> CallGraph<'map> {
+    /// Create a new `CallGraph`
+    ///
+    /// The roots are the sentinel if present or else all functions which are not called by any
+    /// other function.
+    ///
+    /// # Errors
+    ///
+    /// If one of the `event_kinds` is not present in the `map` or no entry point could be found
+    pub fn new(map: &'map CallgrindMap, event_kinds: &[EventKind]) -> Result<Self> {
+        // Sort to get a stable order of the calls and functions
+        let mut ids = map.iter().collect::<Vec<(&Id, &Value)>>();
+        ids.sort_by_cached_key(|(id, _)| to_source(id));
+
+        let mut functions: HashMap<FunctionKey, Function> = HashMap::new();
+        let mut incoming: HashMap<FunctionKey, Vec<u64>> = HashMap::new();
+        let mut callees: IndexMap<&Id, u64> = IndexMap::new();
+        for (id, value) in ids {
+            let key = to_key(id);
+            let function = functions.entry(key).or_insert_with(|| Function {
+                id,
+                lines: vec![],
+                calls: IndexMap::new(),
+            });
+
+            for (line, costs) in &value.lines {
+                function
+                    .lines
+                    .push((id, *line, try_costs(costs, event_kinds)?));
+            }
+
+            for (callee, call) in &value.calls {
+                let callee_key = to_key(callee);
+                // Recursive calls are already part of the costs of the function
+                if callee_key == key {
+                    continue;
+                }
+
+                let costs = try_costs(&call.costs, event_kinds)?;
+                add_costs(incoming.entry(callee_key).or_default(), &costs);
+                *callees.entry(callee).or_default() += costs.iter().sum::<u64>();
+                match function.calls.get_mut(&callee_key) {
+                    Some(other) => add_costs(&mut other.costs, &costs),
+                    None => {
+                        function.calls.insert(
+                            callee_key,
+                            Call {
+                                id,
+                                line: call.line,
+                                costs,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        // A called function is represented by the part with the most expensive calls
+        let mut representatives: HashMap<FunctionKey, (u64, &Id)> = HashMap::new();
+        for (callee, cost) in callees {
+            match representatives.get_mut(&to_key(callee)) {
+                Some(representative) if cost > representative.0 => {
+                    *representative = (cost, callee);
+                }
+                Some(_) => {}
+                None => {
+                    representatives.insert(to_key(callee), (cost, callee));
+                }
+            }
+        }
+        for (key, (_, id)) in representatives {
+            if let Some(function) = functions.get_mut(&key) {
+                function.id = id;
+            }
+        }
+
+        let roots = if let Some(id) = &map.sentinel_key {
+            vec![to_key(id)]
+        } else {
+            let mut roots = functions
+                .iter()
+                .filter(|(key, _)| !incoming.contains_key(*key))
+                .map(|(key, function)| (*key, to_source(function.id)))
+                .collect::<Vec<(FunctionKey, String)>>();
+            if roots.is_empty() {
+                roots.extend(
+                    functions
+                        .iter()
+                        .find(|(key, _)| key.0 == "main")
+                        .map(|(key, function)| (*key, to_source(function.id))),
+                );
+            }
+            roots.sort_by(|(_, a), (_, b)| a.cmp(b));
+            roots.into_iter().map(|(key, _)| key).collect()
+        };
+
+        if roots.is_empty() {
+            return Err(anyhow!(
+                "Failed creating the call graph: Unable to find an entry point"
+            ));
+        }
+
+        Ok(Self {
+            functions,
+            incoming,
+            roots,
+            num_event_kinds: event_kinds.len(),
+        })
+    }
+
+    /// Return the [`Id`] representing the function with the `key` in the call graph
+    pub fn id(&self, key: &FunctionKey<'map>) -> Option<&'map Id> {
+        self.functions.get(key).map(|function| function.id)
+    }
+
+    /// Walk the call graph from all roots and call `emit` for each reconstructed call stack
+    ///
+    /// The costs of the stack are in the same order as the `EventKind`s of the `CallGraph`. The
+    /// last frame of the stack is the frame with the costs. If `max_depth` is given, the stacks are
+    /// cut off at this depth and the remaining inclusive costs are attributed to the last frame.
+    /// The same applies to calls with costs below `1`, which are not walked any further.
+    pub fn walk<F>(&self, max_depth: Option<usize>, mut emit: F)
+    where
+        F: FnMut(&[Frame<'map>], &[f64]),
+    {
+        let mut stack = vec![];
+        for root in &self.roots {
+            if let Some(function) = self.functions.get(root) {
+                let mut costs = vec![0u64; self.num_event_kinds];
+                for (_, _, line_costs) in &function.lines {
+                    add_costs(&mut costs, line_costs);
+                }
+                for call in function.calls.values() {
+                    add_costs(&mut costs, &call.costs);
+                }
+
+                #[allow(clippy::cast_precision_loss)]
+                let costs = costs.iter().map(|c| *c as f64).collect::<Vec<f64>>();
+                self.visit(*root, &costs, None, max_depth, &mut stack, &mut emit);
+            }
+        }
+    }
+
+    /// Visit the function with the `key` and the `costs` attributed to this function by its caller
+    ///
+    /// The `basis` are the costs attributed to this function by all its callers, so `costs /
+    /// basis` is the share of this call. A root function has no `basis` and a share of `1`.
+    #[allow(clippy::cast_precision_loss)]
+    fn visit(
+        &self,
+        key: FunctionKey<'map>,
+        costs: &[f64],
+        basis: Option<&Vec<u64>>,
+        max_depth: Option<usize>,
+        stack: &mut Vec<Frame<'map>>,
+        emit: &mut dyn FnMut(&[Frame<'map>], &[f64]),
+    ) {
+        let function = match self.functions.get(&key) {
+            Some(function) => function,
+            // Functions without any costs are not in the map
+            None => return,
+        };
+
+        // Stop at the maximum depth, at cycles and at negligible costs attributing the inclusive
+        // costs of the call to this frame
+        if max_depth.map_or(false, |max| stack.len() + 1 >= max)
+            || stack.iter().any(|frame| frame.key == key)
+            || costs.iter().all(|cost| cost.round() < 1f64)
+        {
+            stack.push(Frame {
+                key,
+                id: function.id,
+                line: 0,
+            });
+            emit(stack, costs);
+            stack.pop();
+            return;
+        }
+
+        let scale = match basis {
+            Some(basis) => costs
+                .iter()
+                .zip(basis.iter())
+                .map(|(cost, basis)| {
+                    if *basis == 0 {
+                        0f64
+                    } else {
+                        cost / *basis as f64
+                    }
+                })
+                .collect::<Vec<f64>>(),
+            None => vec![1f64; costs.len()],
+        };
+
+        for (id, line, line_costs) in &function.lines {
+            stack.push(Frame {
+                key,
+                id,
+                line: *line,
+            });
+            emit(stack, &scale_costs(line_costs, &scale));
+            stack.pop();
+        }
+
+        for (callee, call) in &function.calls {
+            let call_costs = scale_costs(&call.costs, &scale);
+            stack.push(Frame {
+                key,
+                id: call.id,
+                line: call.line,
+            });
+            self.visit(
+                *callee,
+                &call_costs,
+                self.incoming.get(callee),
+                max_depth,
+                stack,
+                emit,
+            );
+            stack.pop();
+        }
+    }
+}
+
+impl RunningRound {
+    /// Add the `cost` to the running sum and return the rounded `cost`
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn round(&mut self, cost: f64) -> u64 {
+        self.sum += cost;
+        let next = self.sum.round() as u64;
+        let cost = next.saturating_sub(self.rounded_sum);
+        self.rounded_sum = next;
+        cost
+    }
+}
+
+/// Return the [`FunctionKey`] of the function this `id` belongs to
+pub fn to_key(id: &Id) -> FunctionKey<'_> {
+    (id.func.as_str(), id.obj.as_ref())
+}
+
+/// Format the `id` as `file:function [object]` omitting unknown paths
+pub fn to_source(id: &Id) -> String {
+    let mut source = String::new();
+    if let Some(file) = &id.file {
+        match file {
+            SourcePath::Unknown => write!(source, "{}", id.func).unwrap(),
+            SourcePath::Rust(path) | SourcePath::Relative(path) | SourcePath::Absolute(path) => {
+                write!(source, "{}:{}", path.display(), id.func).unwrap();
+            }
+        }
+    } else {
+        write!(source, "{}", id.func).unwrap();
+    }
+    if let Some(path) = &id.obj {
+        match path {
+            SourcePath::Unknown => {}
+            SourcePath::Rust(path) | SourcePath::Relative(path) | SourcePath::Absolute(path) => {
+                write!(source, " [{}]", path.display()).unwrap();
+            }
+        }
+    }
+    source
+}
+
+fn try_costs(costs: &Costs, event_kinds: &[EventKind]) -> Result<Vec<u64>> {
+    event_kinds
+        .iter()
+        .map(|event_kind| {
+            costs.cost_by_kind(event_kind).ok_or_else(|| {
+                anyhow!("Failed creating the call graph: Missing event type '{event_kind}'")
+            })
+        })
+        .collect()
+}
+
+fn add_costs(costs: &mut Vec<u64>, other: &[u64]) {
+    if costs.len() < other.len() {
+        costs.resize(other.len(), 0);
+    }
+    for (cost, other) in costs.iter_mut().zip(other.iter()) {
+        *cost += other;
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn scale_costs(costs: &[u64], scale: &[f64]) -> Vec<f64> {
+    costs
+        .iter()
+        .zip(scale.iter())
+        .map(|(cost, scale)| *cost as f64 * scale)
+        .collect()
+}
@@ -0,0 +1,324 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use indexmap::IndexMap;
+
+use super::call_graph::{to_key, to_source, FunctionKey};
+use super::hashmap_parser::{CallgrindMap, SourcePath};
+use super::model::Costs;
+use crate::api::{self, EventKind};
+use crate::runner::tool::ToolOutputPath;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub event_kind: EventKind,
+    pub node_threshold: f64,
+    pub edge_threshold: f64,
+    pub differential: bool,
+}
+
+pub struct Dot {
+    pub config: Config,
+}
+
+/// A function in the DOT call graph with the costs of the configured [`EventKind`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Node {
+    inclusive: u64,
+    exclusive: u64,
+    calls: u64,
+}
+
+/// The calls from one function to another function
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Edge {
+    count: u64,
+    inclusive: u64,
+}
+
+/// The nodes and edges of the call graph
+#[derive(Debug)]
+struct Graph<'map> {
+    nodes: IndexMap<FunctionKey<'map>, Node>,
+    edges: IndexMap<(FunctionKey<'map>, FunctionKey<'map>), Edge>,
+    total: u64,
+}
+
+impl From<api::DotConfig> for Config {
+    fn from(value: api::DotConfig) -> Self {
+        Self {
+            event_kind: value.event_kind.unwrap_or(EventKind::Ir),
+            node_threshold: value.node_threshold.unwrap_or(0.5f64),
+            edge_threshold: value.edge_threshold.unwrap_or(0.1f64),
+            differential: value.differential.unwrap_or(false),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        api::DotConfig::default().into()
+    }
+}
+
+impl Dot {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Save the `map` as DOT call graph next to the callgrind output
+    ///
+    /// If the `old_map` is present and the call graph is differential, the functions are colored
+    /// by the change of their inclusive costs.
+    pub fn save(
+        &self,
+        callgrind_output_path: &ToolOutputPath,
+        map: &CallgrindMap,
+        old_map: Option<&CallgrindMap>,
+    ) -> Result<PathBuf> {
+        let path = callgrind_output_path.to_path().with_extension("dot");
+        std::fs::write(&path, self.create(map, old_map)?)
+            .with_context(|| format!("Failed writing DOT file '{}'", path.display()))?;
+        Ok(path)
+    }
+
+    /// Create the call graph in the DOT format
+    ///
+    /// The nodes are the functions with their inclusive and self costs as percentage of the total
+    /// costs and the edges are the calls with the number of calls and the inclusive costs of the
+    /// calls. If a sentinel is present, only the functions called by the sentinel are shown and the
+    /// total costs are the inclusive costs of the sentinel. Functions and calls below the
+    /// thresholds are pruned.
+    ///
+    /// # Errors
+    ///
+    /// If the `EventKind` is not present in the `map`
+    pub fn create(&self, map: &CallgrindMap, old_map: Option<&CallgrindMap>) -> Result<String> {
+        let graph = Graph::new(map, self.config.event_kind)?;
+        let old_graph = match (self.config.differential, old_map) {
+            (true, Some(old_map)) => Some(Graph::new(old_map, self.config.event_kind)?),
+            _ => None,
+        };
+
+        let nodes = graph
+            .nodes
+            .iter()
+            .filter(|(_, node)| {
+                percentage(node.inclusive, graph.total) >= self.config.node_threshold
+            })
+            .map(|(key, node)| (*key, *node))
+            .collect::<IndexMap<FunctionKey, Node>>();
+
+        let mut dot = String::new();
+        writeln!(dot, "digraph {{").unwrap();
+        writeln!(
+            dot,
+            "  graph [fontname=\"Helvetica\", label=\"{}: {}\"];",
+            self.config.event_kind, graph.total
+        )
+        .unwrap();
+        writeln!(
+            dot,
+            "  node [fontname=\"Helvetica\", shape=box, style=\"filled,rounded\"];"
+        )
+        .unwrap();
+        writeln!(dot, "  edge [fontname=\"Helvetica\"];").unwrap();
+
+        for (index, (key, node)) in nodes.iter().enumerate() {
+            let inclusive = percentage(node.inclusive, graph.total);
+            let mut label = format!(
+                "{}\\n{:.2}%\\n({:.2}%)",
+                escape(key.0),
+                inclusive,
+                percentage(node.exclusive, graph.total)
+            );
+            if node.calls > 0 {
+                write!(label, "\\n{}\u{d7}", node.calls).unwrap();
+            }
+
+            let color = if let Some(old_graph) = &old_graph {
+                let old = old_graph.nodes.get(key).map(|node| node.inclusive);
+                let (text, color) = diff_color(node.inclusive, old);
+                write!(label, "\\n{text}").unwrap();
+                color
+            } else {
+                temperature(inclusive)
+            };
+
+            writeln!(
+                dot,
+                "  n{index} [label=\"{label}\", tooltip=\"{}\", fillcolor=\"{color}\"];",
+                escape(&tooltip(key))
+            )
+            .unwrap();
+        }
+
+        // Calls from or to pruned functions are pruned, too
+        let mut edges = graph
+            .edges
+            .iter()
+            .filter_map(|((caller, callee), edge)| {
+                match (nodes.get_index_of(caller), nodes.get_index_of(callee)) {
+                    (Some(from), Some(to)) => Some((from, to, edge)),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<(usize, usize, &Edge)>>();
+        edges.sort_by_key(|(from, to, _)| (*from, *to));
+
+        for (from, to, edge) in edges {
+            let inclusive = percentage(edge.inclusive, graph.total);
+            if inclusive < self.config.edge_threshold {
+                continue;
+            }
+
+            writeln!(
+                dot,
+                "  n{from} -> n{to} [label=\"{inclusive:.2}%\\n{}\u{d7}\", penwidth={:.2}];",
+                edge.count,
+                1f64 + inclusive / 20f64
+            )
+            .unwrap();
+        }
+
+        writeln!(dot, "}}").unwrap();
+        Ok(dot)
+    }
+}
+
+impl<'map> Graph<'map> {
+    /// Create the `Graph` with the costs of the `event_kind` from the `map`
+    ///
+    /// The parts of a function with inlined code (see [`FunctionKey`]) are merged into a single
+    /// node. The inclusive costs of the called part of such a function already contain the costs of
+    /// the inlined parts, so the inclusive costs of a node are the self costs and the costs of the
+    /// calls to other functions summed over all parts.
+    fn new(map: &'map CallgrindMap, event_kind: EventKind) -> Result<Self> {
+        // Sort to get a stable order of the nodes and edges
+        let mut ids = map.iter().collect::<Vec<_>>();
+        ids.sort_by_cached_key(|(id, _)| to_source(id));
+
+        let mut nodes: IndexMap<FunctionKey, Node> = IndexMap::new();
+        let mut edges: IndexMap<(FunctionKey, FunctionKey), Edge> = IndexMap::new();
+        let mut callees: HashMap<FunctionKey, Vec<FunctionKey>> = HashMap::new();
+        for (id, value) in &ids {
+            let key = to_key(id);
+            let node = nodes.entry(key).or_default();
+            let exclusive = try_cost(&value.self_costs, event_kind)?;
+            node.exclusive += exclusive;
+            node.inclusive += exclusive;
+
+            for (callee, call) in &value.calls {
+                let callee_key = to_key(callee);
+                let cost = try_cost(&call.costs, event_kind)?;
+                // Recursive calls are already part of the self costs of the function
+                if callee_key != key {
+                    node.inclusive += cost;
+                }
+                let edge = edges.entry((key, callee_key)).or_default();
+                edge.count += call.count;
+                edge.inclusive += cost;
+                callees.entry(key).or_default().push(callee_key);
+            }
+        }
+
+        for ((_, callee), edge) in &edges {
+            if let Some(node) = nodes.get_mut(callee) {
+                node.calls += edge.count;
+            }
+        }
+
+        let total = if let Some(sentinel) = &map.sentinel_key {
+            let root = to_key(sentinel);
+            let reachable = reachable(root, &callees);
+            nodes.retain(|key, _| reachable.contains(key));
+            edges.retain(|(caller, _), _| reachable.contains(caller));
+            nodes.get(&root).map_or(0, |node| node.inclusive)
+        } else {
+            nodes.values().map(|node| node.exclusive).sum()
+        };
+
+        nodes.sort_by(|_, a, _, b| b.inclusive.cmp(&a.inclusive));
+        Ok(Self {
+            nodes,
+            edges,
+            total,
+        })
+    }
+}
+
+/// Return all functions which are reachable from the `root` including the `root` itself
+fn reachable<'map>(
+    root: FunctionKey<'map>,
+    callees: &HashMap<FunctionKey<'map>, Vec<FunctionKey<'map>>>,
+) -> HashSet<FunctionKey<'map>> {
+    let mut reachable = HashSet::new();
+    let mut queue = vec![root];
+    while let Some(key) = queue.pop() {
+        if reachable.insert(key) {
+            if let Some(keys) = callees.get(&key) {
+                queue.extend(keys.iter().copied());
+            }
+        }
+    }
+    reachable
+}
+
+fn try_cost(costs: &Costs, event_kind: EventKind) -> Result<u64> {
+    costs.cost_by_kind(&event_kind).ok_or_else(|| {
+        anyhow!("Failed creating the DOT call graph: Missing event type '{event_kind}'")
+    })
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percentage(cost: u64, total: u64) -> f64 {
+    if total == 0 {
+        0f64
+    } else {
+        cost as f64 / total as f64 * 100f64
+    }
+}
+
+/// Return the color of a node in the `HSV` format of graphviz from blue (0%) to red (100%)
+fn temperature(percentage: f64) -> String {
+    let value = percentage.clamp(0f64, 100f64) / 100f64;
+    format!(
+        "{:.3} {:.3} 1.000",
+        (1f64 - value) * 0.6f64,
+        0.2f64 + value * 0.6f64
+    )
+}
+
+/// Return the label text and color of a node in a differential call graph
+///
+/// More expensive functions are red, cheaper functions green and the saturation increases with the
+/// size of the change up to a change of `50%`. New functions are red.
+#[allow(clippy::cast_precision_loss)]
+fn diff_color(new: u64, old: Option<u64>) -> (String, String) {
+    match old {
+        Some(old) if old == new => ("No change".to_owned(), "0.000 0.000 1.000".to_owned()),
+        Some(0) | None => ("New".to_owned(), "0.000 0.800 1.000".to_owned()),
+        Some(old) => {
+            let diff = (new as f64 - old as f64) / old as f64 * 100f64;
+            let saturation = 0.1f64 + (diff.abs() / 50f64).min(1f64) * 0.7f64;
+            let hue = if diff > 0f64 { 0f64 } else { 0.333f64 };
+            (
+                format!("{diff:+.2}%"),
+                format!("{hue:.3} {saturation:.3} 1.000"),
+            )
+        }
+    }
+}
+
+fn tooltip(key: &FunctionKey) -> String {
+    match key.1.and_then(SourcePath::to_path_buf) {
+        Some(path) => format!("{} [{}]", key.0, path.display()),
+        None => key.0.to_owned(),
+    }
+}
+
+fn escape(string: &str) -> String {
+    string.replace('\\', "\\\\").replace('"', "\\\"")
+}
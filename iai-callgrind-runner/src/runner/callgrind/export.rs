@@ -0,0 +1,134 @@
+use anyhow::Result;
+use log::{debug, warn};
+
+use super::dot::{Config as DotConfig, Dot};
+use super::hashmap_parser::CallgrindMap;
+use super::pprof::{Config as PprofConfig, Pprof};
+use super::speedscope::{Config as SpeedscopeConfig, Speedscope};
+use crate::api::{self, EventKind};
+use crate::runner::summary::{ExportFormat, ExportSummary};
+use crate::runner::tool::ToolOutputPath;
+
+/// The configuration of all formats in which the callgrind output is exported
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub pprof: Option<PprofConfig>,
+    pub speedscope: Option<SpeedscopeConfig>,
+    pub dot: Option<DotConfig>,
+}
+
+pub struct Exporter {
+    pub config: Config,
+}
+
+impl Config {
+    /// Create a new `Config` from the benchmark configuration and the command-line `formats`
+    ///
+    /// A format given on the command-line which is not configured in the benchmark is exported
+    /// with its default configuration.
+    pub fn new(
+        pprof: Option<api::PprofConfig>,
+        speedscope: Option<api::SpeedscopeConfig>,
+        dot: Option<api::DotConfig>,
+        formats: &[ExportFormat],
+    ) -> Self {
+        let mut pprof = pprof;
+        let mut speedscope = speedscope;
+        let mut dot = dot;
+        for format in formats {
+            match format {
+                ExportFormat::Pprof => {
+                    pprof.get_or_insert_with(api::PprofConfig::default);
+                }
+                ExportFormat::Speedscope => {
+                    speedscope.get_or_insert_with(api::SpeedscopeConfig::default);
+                }
+                ExportFormat::Dot => {
+                    dot.get_or_insert_with(api::DotConfig::default);
+                }
+            }
+        }
+
+        Self {
+            pprof: pprof.map(Into::into),
+            speedscope: speedscope.map(Into::into),
+            dot: dot.map(Into::into),
+        }
+    }
+
+    /// Return the [`EventKind`]s of all configured formats
+    pub fn event_kinds(&self, map: &CallgrindMap) -> Vec<EventKind> {
+        let mut event_kinds = vec![];
+        if let Some(pprof) = &self.pprof {
+            event_kinds.extend(Pprof::new(pprof.clone()).event_kinds(map));
+        }
+        if let Some(speedscope) = &self.speedscope {
+            event_kinds.extend(Speedscope::new(speedscope.clone()).event_kinds(map));
+        }
+        if let Some(dot) = &self.dot {
+            event_kinds.push(dot.event_kind);
+        }
+        event_kinds
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pprof.is_none() && self.speedscope.is_none() && self.dot.is_none()
+    }
+}
+
+impl Exporter {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Export the already parsed `map` in all configured formats
+    ///
+    /// The `map` needs to contain the costs of the [`Config::event_kinds`]. Only the DOT call
+    /// graph compares the `map` with the `old_map`.
+    pub fn create(
+        &self,
+        callgrind_output_path: &ToolOutputPath,
+        map: &CallgrindMap,
+        old_map: Option<&CallgrindMap>,
+    ) -> Result<Vec<ExportSummary>> {
+        if self.config.is_empty() {
+            return Ok(vec![]);
+        }
+
+        debug!("Exporting callgrind output '{}'", callgrind_output_path);
+        if map.is_empty() {
+            warn!("Unable to export the callgrind output: No functions found");
+            return Ok(vec![]);
+        }
+
+        let pprof = self.config.pprof.clone().map(Pprof::new);
+        let speedscope = self.config.speedscope.clone().map(Speedscope::new);
+        let dot = self.config.dot.clone().map(Dot::new);
+        let old_map = match &dot {
+            Some(dot) if dot.config.differential => old_map,
+            _ => None,
+        };
+
+        let mut summaries = vec![];
+        if let Some(pprof) = pprof {
+            summaries.push(ExportSummary {
+                format: ExportFormat::Pprof,
+                path: pprof.save(callgrind_output_path, map)?,
+            });
+        }
+        if let Some(speedscope) = speedscope {
+            summaries.push(ExportSummary {
+                format: ExportFormat::Speedscope,
+                path: speedscope.save(callgrind_output_path, map)?,
+            });
+        }
+        if let Some(dot) = dot {
+            summaries.push(ExportSummary {
+                format: ExportFormat::Dot,
+                path: dot.save(callgrind_output_path, map, old_map)?,
+            });
+        }
+
+        Ok(summaries)
+    }
+}
@@ -6,7 +6,8 @@ use anyhow::{Context, Result};
 use inferno::flamegraph::{Direction, Options};
 use log::warn;
 
-use super::flamegraph_parser::FlamegraphParser;
+use super::flamegraph_parser::to_stack_format;
+use super::hashmap_parser::{CallgrindMap, HashMapParser};
 use super::parser::{Parser, Sentinel};
 use crate::api::{self, EventKind, FlamegraphKind};
 use crate::runner::summary::FlamegraphSummary;
@@ -23,6 +24,8 @@ pub struct Config {
     pub title: Option<String>,
     pub subtitle: Option<String>,
     pub min_width: f64,
+    pub max_depth: Option<usize>,
+    pub folded: bool,
 }
 
 pub struct Flamegraph {
@@ -46,6 +49,8 @@ impl From<api::FlamegraphConfig> for Config {
             title: value.title.clone(),
             subtitle: value.subtitle.clone(),
             min_width: value.min_width.unwrap_or(0.1f64),
+            max_depth: value.max_depth,
+            folded: value.folded.unwrap_or(false),
         }
     }
 }
@@ -82,26 +87,65 @@ impl Flamegraph {
         callgrind_output_path: &ToolOutputPath,
         sentinel: Option<&Sentinel>,
         project_root: &Path,
+    ) -> Result<Vec<FlamegraphSummary>> {
+        let old_output = callgrind_output_path.to_old_output();
+        #[allow(clippy::if_then_some_else_none)]
+        let old_output = if old_output.exists() {
+            Some(&old_output)
+        } else {
+            None
+        };
+        self.create_with_old(callgrind_output_path, old_output, sentinel, project_root)
+    }
+
+    /// Create the flamegraphs of `callgrind_output_path` compared against `old_output_path`
+    ///
+    /// The old and differential flamegraphs are only created if the `old_output_path` is present.
+    /// Both are created from the `old_output_path`, so they show the stored git baseline if it
+    /// replaced the `*.old` output. The svg files are stored next to the `callgrind_output_path`.
+    pub fn create_with_old(
+        &self,
+        callgrind_output_path: &ToolOutputPath,
+        old_output_path: Option<&ToolOutputPath>,
+        sentinel: Option<&Sentinel>,
+        project_root: &Path,
     ) -> Result<Vec<FlamegraphSummary>> {
         if self.config.kind == FlamegraphKind::None {
             return Ok(vec![]);
         }
-        let summarize_events = [
-            EventKind::L1hits,
-            EventKind::LLhits,
-            EventKind::RamHits,
-            EventKind::TotalRW,
-            EventKind::EstimatedCycles,
-        ];
-        let summarize = self
-            .config
-            .event_kinds
-            .iter()
-            .any(|e| summarize_events.contains(e));
 
-        let parser = FlamegraphParser::new(sentinel, project_root);
-        // We need this map in all remaining cases of `FlamegraphKinds`
+        let parser = HashMapParser {
+            sentinel: sentinel.cloned(),
+            project_root: project_root.to_owned(),
+        };
         let mut map = parser.parse(callgrind_output_path)?;
+        let mut old_map = old_output_path
+            .map(|old_output| parser.parse(old_output))
+            .transpose()?;
+
+        if self.config.event_kinds.iter().any(EventKind::is_derived) {
+            map.make_summary()?;
+            if let Some(old_map) = old_map.as_mut() {
+                old_map.make_summary()?;
+            }
+        }
+
+        self.create_from_maps(callgrind_output_path, &map, old_map.as_ref())
+    }
+
+    /// Create the flamegraphs of the already parsed `map` compared against the `old_map`
+    ///
+    /// The maps need to contain the costs of all configured event kinds. The svg files are stored
+    /// next to the `callgrind_output_path`.
+    pub fn create_from_maps(
+        &self,
+        callgrind_output_path: &ToolOutputPath,
+        map: &CallgrindMap,
+        old_map: Option<&CallgrindMap>,
+    ) -> Result<Vec<FlamegraphSummary>> {
+        if self.config.kind == FlamegraphKind::None {
+            return Ok(vec![]);
+        }
         if map.is_empty() {
             warn!("Unable to create a flamegraph: No stacks found");
             return Ok(vec![]);
@@ -119,31 +163,15 @@ impl Flamegraph {
         options.subtitle = self.config.subtitle.clone();
         options.min_width = self.config.min_width;
 
-        let old_output = callgrind_output_path.to_old_output();
-
-        #[allow(clippy::if_then_some_else_none)]
-        let mut old_map = if (self.config.kind == FlamegraphKind::Differential
-            || self.config.kind == FlamegraphKind::All)
-            && old_output.exists()
-        {
-            Some(parser.parse(&old_output)?)
-        } else {
-            None
-        };
-
-        if summarize {
-            map.make_summary()?;
-            if let Some(map) = old_map.as_mut() {
-                map.make_summary()?;
-            }
-        }
+        let is_differential = self.config.kind == FlamegraphKind::Differential
+            || self.config.kind == FlamegraphKind::All;
 
         let mut flamegraph_summaries = vec![];
         for event_kind in &self.config.event_kinds {
             let mut flamegraph_summary = FlamegraphSummary::new(*event_kind);
 
             options.count_name = event_kind.to_string();
-            let stacks_lines = map.to_stack_format(event_kind)?;
+            let stacks_lines = to_stack_format(map, event_kind, self.config.max_depth)?;
 
             let output = Output::init(callgrind_output_path.to_path(), event_kind)?;
             if self.config.kind == FlamegraphKind::Regular
@@ -157,35 +185,42 @@ impl Flamegraph {
                 flamegraph_summary.regular_path = Some(output.as_path().to_owned());
             }
 
-            // Is Some if FlamegraphKind::Differential or FlamegraphKind::Both
-            if let Some(old_map) = old_map.as_ref() {
-                let old_stacks_lines = old_map.to_stack_format(event_kind)?;
+            if self.config.folded {
+                let folded_output = output.to_folded_output();
+                write_folded(&folded_output, &stacks_lines)?;
+                flamegraph_summary.folded_path = Some(folded_output.as_path().to_owned());
+            }
 
-                let cursor = Cursor::new(stacks_lines.join("\n"));
-                let old_cursor = Cursor::new(old_stacks_lines.join("\n"));
-                let mut result = Cursor::new(vec![]);
+            let old_stacks_lines = old_map
+                .map(|old_map| to_stack_format(old_map, event_kind, self.config.max_depth))
+                .transpose()?;
 
-                let differential_options = inferno::differential::Options {
-                    normalize: self.config.normalize_differential,
-                    ..Default::default()
-                };
+            if let (true, Some(old_stacks_lines)) = (self.config.folded, &old_stacks_lines) {
+                let old_folded_output = output.to_old_output().to_folded_output();
+                write_folded(&old_folded_output, old_stacks_lines)?;
+                flamegraph_summary.old_folded_path = Some(old_folded_output.as_path().to_owned());
+            }
 
-                inferno::differential::from_readers(
-                    differential_options,
-                    old_cursor,
-                    cursor,
-                    &mut result,
-                )
-                .context("Failed creating a differential flamegraph")?;
+            if let Some(old_stacks_lines) = &old_stacks_lines {
+                let old_output = output.to_old_output();
+                create_flamegraph(
+                    &old_output,
+                    &mut options,
+                    old_stacks_lines.iter().map(std::string::String::as_str),
+                )?;
+                flamegraph_summary.old_path = Some(old_output.as_path().to_owned());
+            }
 
+            // Is Some if FlamegraphKind::Differential or FlamegraphKind::All
+            if let (true, Some(old_stacks_lines)) = (is_differential, old_stacks_lines) {
                 let diff_output = output.to_diff_output();
-                create_flamegraph(
+                self.create_differential(
                     &diff_output,
                     &mut options,
-                    String::from_utf8_lossy(result.get_ref()).lines(),
+                    &stacks_lines,
+                    &old_stacks_lines,
                 )?;
 
-                flamegraph_summary.old_path = Some(output.to_old_output().as_path().to_owned());
                 flamegraph_summary.diff_path = Some(diff_output.as_path().to_owned());
             }
 
@@ -194,23 +229,53 @@ impl Flamegraph {
 
         Ok(flamegraph_summaries)
     }
+
+    fn create_differential(
+        &self,
+        output: &Output,
+        options: &mut Options<'_>,
+        stacks_lines: &[String],
+        old_stacks_lines: &[String],
+    ) -> Result<()> {
+        let cursor = Cursor::new(stacks_lines.join("\n"));
+        let old_cursor = Cursor::new(old_stacks_lines.join("\n"));
+        let mut result = Cursor::new(vec![]);
+
+        let differential_options = inferno::differential::Options {
+            normalize: self.config.normalize_differential,
+            ..Default::default()
+        };
+
+        inferno::differential::from_readers(differential_options, old_cursor, cursor, &mut result)
+            .context("Failed creating a differential flamegraph")?;
+
+        create_flamegraph(
+            output,
+            options,
+            String::from_utf8_lossy(result.get_ref()).lines(),
+        )
+    }
 }
 
 impl Output {
+    /// Create the `Output` of the `event_kind` and remove the flamegraphs of the previous run
+    ///
+    /// The old flamegraph is not moved over from the previous run but created again from the
+    /// `*.old` callgrind output, which is the git baseline if `--baseline-commit` is used.
     pub fn init<T>(path: T, event_kind: &EventKind) -> Result<Self>
     where
         T: AsRef<Path>,
     {
         let output = Self(path.as_ref().with_extension(format!("{event_kind}.svg")));
-        if output.exists() {
-            let old_svg = output.to_old_output();
-            std::fs::rename(output.as_path(), old_svg.as_path()).with_context(|| {
-                format!(
-                    "Failed moving flamegraph file '{}' -> '{}'",
-                    &output.as_path().display(),
-                    &old_svg.as_path().display(),
-                )
-            })?;
+        for stale in [&output, &output.to_old_output(), &output.to_diff_output()] {
+            if stale.exists() {
+                std::fs::remove_file(stale.as_path()).with_context(|| {
+                    format!(
+                        "Failed removing flamegraph file '{}'",
+                        stale.as_path().display()
+                    )
+                })?;
+            }
         }
 
         Ok(output)
@@ -233,6 +298,10 @@ impl Output {
         Self(self.0.with_extension("old.svg"))
     }
 
+    pub fn to_folded_output(&self) -> Self {
+        Self(self.0.with_extension("folded"))
+    }
+
     pub fn as_path(&self) -> &Path {
         self.0.as_path()
     }
@@ -251,3 +320,15 @@ fn create_flamegraph<'stacks>(
         .flush()
         .with_context(|| format!("Failed flushing content to '{}'", output.0.display()))
 }
+
+fn write_folded(output: &Output, stacks: &[String]) -> Result<()> {
+    let mut writer = BufWriter::new(output.create()?);
+    for stack in stacks {
+        writeln!(writer, "{stack}")
+            .with_context(|| format!("Failed writing folded stacks to '{}'", output.0.display()))?;
+    }
+
+    writer
+        .flush()
+        .with_context(|| format!("Failed flushing content to '{}'", output.0.display()))
+}
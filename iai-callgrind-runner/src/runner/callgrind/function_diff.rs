@@ -0,0 +1,249 @@
+use std::collections::HashSet;
+use std::fmt::Write as FmtWrite;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::debug;
+
+use super::hashmap_parser::{CallgrindMap, Id, SourcePath};
+use super::model::Costs;
+use crate::api::{self, EventKind};
+use crate::runner::summary::{
+    CostsDiff, FunctionDiff as FunctionDiffSummary, FunctionDiffKind, FunctionDiffOutput,
+    FunctionDiffReport, FunctionDiffSection,
+};
+use crate::runner::tool::ToolOutputPath;
+use crate::util::{format_cost, to_string_signed_short};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub event_kinds: Vec<EventKind>,
+    pub limit: usize,
+}
+
+pub struct FunctionDiff {
+    pub config: Config,
+}
+
+impl From<api::FunctionDiffConfig> for Config {
+    fn from(value: api::FunctionDiffConfig) -> Self {
+        Self {
+            event_kinds: value.event_kinds.unwrap_or_else(|| vec![EventKind::Ir]),
+            limit: value.limit.unwrap_or(10),
+        }
+    }
+}
+
+impl FunctionDiff {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Create the [`FunctionDiffReport`] of the parsed `map` and `old_map` and save it in json and
+    /// markdown format next to the callgrind output
+    ///
+    /// Without an old callgrind output there is nothing to compare with and `None` is returned.
+    /// Reports of previous benchmark runs are removed in any case.
+    pub fn create(
+        &self,
+        callgrind_output_path: &ToolOutputPath,
+        map: &CallgrindMap,
+        old_map: Option<&CallgrindMap>,
+        benchmark: String,
+    ) -> Result<Option<(FunctionDiffReport, FunctionDiffOutput)>> {
+        let output = FunctionDiffOutput::init(callgrind_output_path)?;
+
+        let old_map = if let Some(old_map) = old_map {
+            old_map
+        } else {
+            debug!("No old callgrind output found. Skipping the function diff report.");
+            return Ok(None);
+        };
+
+        let report = self.diff(benchmark, map, old_map);
+        output.save(&report)?;
+
+        Ok(Some((report, output)))
+    }
+
+    /// Join the new and old [`CallgrindMap`] by [`Id`] and create the [`FunctionDiffReport`]
+    pub fn diff(
+        &self,
+        benchmark: String,
+        map: &CallgrindMap,
+        old_map: &CallgrindMap,
+    ) -> FunctionDiffReport {
+        let ids = map
+            .iter()
+            .chain(old_map.iter())
+            .map(|(id, _)| id)
+            .collect::<HashSet<&Id>>();
+
+        let sections = self
+            .config
+            .event_kinds
+            .iter()
+            .map(|event_kind| {
+                let mut functions = ids
+                    .iter()
+                    .filter_map(|id| diff_function(id, map, old_map, *event_kind))
+                    .collect::<Vec<FunctionDiffSummary>>();
+
+                functions.sort_by(|a, b| {
+                    b.inclusive
+                        .delta()
+                        .abs()
+                        .cmp(&a.inclusive.delta().abs())
+                        .then_with(|| b.exclusive.delta().abs().cmp(&a.exclusive.delta().abs()))
+                        .then_with(|| a.function.cmp(&b.function))
+                        .then_with(|| a.file.cmp(&b.file))
+                });
+
+                FunctionDiffSection {
+                    event_kind: *event_kind,
+                    functions,
+                }
+            })
+            .collect();
+
+        FunctionDiffReport {
+            benchmark,
+            sections,
+        }
+    }
+}
+
+impl FunctionDiffOutput {
+    /// Create a new `FunctionDiffOutput` removing the reports of previous benchmark runs
+    pub fn init(callgrind_output_path: &ToolOutputPath) -> Result<Self> {
+        let path = callgrind_output_path.to_path();
+        let output = Self {
+            json_path: path.with_extension("function_diff.json"),
+            markdown_path: path.with_extension("function_diff.md"),
+        };
+
+        for path in [&output.json_path, &output.markdown_path] {
+            if path.exists() {
+                std::fs::remove_file(path).with_context(|| {
+                    format!("Failed removing function diff file '{}'", path.display())
+                })?;
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Save the [`FunctionDiffReport`] in json and markdown format
+    pub fn save(&self, report: &FunctionDiffReport) -> Result<()> {
+        let mut writer = BufWriter::new(create_file(&self.json_path)?);
+        serde_json::to_writer_pretty(&mut writer, report)
+            .with_context(|| "Failed to serialize function diff report to json".to_owned())?;
+        writer
+            .flush()
+            .with_context(|| format!("Failed writing '{}'", self.json_path.display()))?;
+
+        let mut writer = BufWriter::new(create_file(&self.markdown_path)?);
+        writer
+            .write_all(to_markdown(report)?.as_bytes())
+            .and_then(|()| writer.flush())
+            .with_context(|| format!("Failed writing '{}'", self.markdown_path.display()))
+    }
+}
+
+fn create_file(path: &Path) -> Result<File> {
+    File::create(path)
+        .with_context(|| format!("Failed creating function diff file '{}'", path.display()))
+}
+
+fn diff_function(
+    id: &Id,
+    map: &CallgrindMap,
+    old_map: &CallgrindMap,
+    event_kind: EventKind,
+) -> Option<FunctionDiffSummary> {
+    let new = map.get_key_value(id).map(|(_, value)| value);
+    let old = old_map.get_key_value(id).map(|(_, value)| value);
+
+    let cost = |costs: &Costs| costs.cost_by_kind(&event_kind).filter(|c| *c > 0);
+    let (new_inclusive, new_exclusive) = new.map_or((None, None), |value| {
+        (cost(&value.costs), cost(&value.self_costs))
+    });
+    let (old_inclusive, old_exclusive) = old.map_or((None, None), |value| {
+        (cost(&value.costs), cost(&value.self_costs))
+    });
+
+    let kind = match (new_inclusive, old_inclusive) {
+        (None, None) => return None,
+        (Some(_), None) => FunctionDiffKind::Appeared,
+        (None, Some(_)) => FunctionDiffKind::Disappeared,
+        (Some(_), Some(_)) if new_inclusive == old_inclusive && new_exclusive == old_exclusive => {
+            return None;
+        }
+        (Some(_), Some(_)) => FunctionDiffKind::Changed,
+    };
+
+    Some(FunctionDiffSummary {
+        function: id.func.clone(),
+        file: id.file.as_ref().and_then(SourcePath::to_path_buf),
+        object: id.obj.as_ref().and_then(SourcePath::to_path_buf),
+        kind,
+        inclusive: CostsDiff::new(new_inclusive, old_inclusive),
+        exclusive: to_costs_diff(new_exclusive, old_exclusive),
+    })
+}
+
+/// Functions may have no exclusive costs at all, so we count missing costs as `0` here
+fn to_costs_diff(new: Option<u64>, old: Option<u64>) -> CostsDiff {
+    match (new, old) {
+        (None, None) => CostsDiff::new(Some(0), Some(0)),
+        (new, old) => CostsDiff::new(new, old),
+    }
+}
+
+fn format_change(diff: &CostsDiff) -> String {
+    match diff.diff_pct {
+        Some(pct) => format!("{:+} ({}%)", diff.delta(), to_string_signed_short(pct)),
+        None => format!("{:+}", diff.delta()),
+    }
+}
+
+/// Convert the [`FunctionDiffReport`] into a markdown document with a table for each `EventKind`
+pub fn to_markdown(report: &FunctionDiffReport) -> Result<String> {
+    let mut result = String::new();
+    writeln!(result, "# Function diff: {}", report.benchmark)?;
+
+    for section in &report.sections {
+        writeln!(result)?;
+        writeln!(result, "## {}", section.event_kind)?;
+        writeln!(result)?;
+
+        if section.functions.is_empty() {
+            writeln!(result, "No changes")?;
+            continue;
+        }
+
+        writeln!(
+            result,
+            "| Status | Function | New | Old | Change | Self New | Self Old | Self Change |"
+        )?;
+        writeln!(result, "|---|---|--:|--:|--:|--:|--:|--:|")?;
+        for function in &section.functions {
+            writeln!(
+                result,
+                "| {:?} | `{}` | {} | {} | {} | {} | {} | {} |",
+                function.kind,
+                function.function.replace('|', "\\|"),
+                format_cost(function.inclusive.new),
+                format_cost(function.inclusive.old),
+                format_change(&function.inclusive),
+                format_cost(function.exclusive.new),
+                format_cost(function.exclusive.old),
+                format_change(&function.exclusive),
+            )?;
+        }
+    }
+
+    Ok(result)
+}
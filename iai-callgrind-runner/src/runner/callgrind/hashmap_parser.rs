@@ -4,12 +4,13 @@ use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::path::{Component, Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
 use log::trace;
 use serde::{Deserialize, Serialize};
 
-use super::model::Costs;
-use super::parser::{parse_header, Parser, Sentinel};
+use super::model::{Costs, PositionType};
+use super::parser::{is_cost_line, parse_header, NameDecoder, Parser, Sentinel};
 use crate::error::Error;
 use crate::runner::tool::ToolOutputPath;
 
@@ -60,9 +61,31 @@ pub enum SourcePath {
     Absolute(PathBuf),
 }
 
+/// The calls from one function to another function
+///
+/// The `costs` are the inclusive costs of all calls. The `line` is the line of the first call site
+/// in the calling function (or `0` if the callgrind output has no line information).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Call {
+    pub count: u64,
+    pub costs: Costs,
+    pub line: u64,
+}
+
+/// The costs of a function
+///
+/// The `costs` are the inclusive costs and the `self_costs` the exclusive costs of the function.
+/// The `lines` are the exclusive costs split by source line and the `instrs` the exclusive costs
+/// split by instruction address (only present if callgrind ran with `--dump-instr=yes`). The
+/// `calls` are the outgoing calls of this function to other functions in the order of their
+/// appearance in the callgrind output.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Value {
     pub costs: Costs,
+    pub self_costs: Costs,
+    pub lines: IndexMap<u64, Costs>,
+    pub instrs: IndexMap<u64, Costs>,
+    pub calls: IndexMap<Id, Call>,
 }
 
 impl CallgrindMap {
@@ -77,6 +100,44 @@ impl CallgrindMap {
     pub fn get_key_value(&self, k: &Id) -> Option<(&Id, &Value)> {
         self.map.get_key_value(k)
     }
+
+    pub fn get(&self, k: &Id) -> Option<&Value> {
+        self.map.get(k)
+    }
+
+    /// Calculate and add the derived summary events to the inclusive and exclusive costs
+    ///
+    /// # Errors
+    ///
+    /// If the necessary cache simulation events were not present
+    pub fn make_summary(&mut self) -> Result<()> {
+        for value in self.map.values_mut() {
+            value
+                .costs
+                .make_summary()
+                .map_err(|error| anyhow!("Failed calculating summary events: {error}"))?;
+            value
+                .self_costs
+                .make_summary()
+                .map_err(|error| anyhow!("Failed calculating summary events: {error}"))?;
+            for costs in value.lines.values_mut() {
+                costs
+                    .make_summary()
+                    .map_err(|error| anyhow!("Failed calculating summary events: {error}"))?;
+            }
+            for costs in value.instrs.values_mut() {
+                costs
+                    .make_summary()
+                    .map_err(|error| anyhow!("Failed calculating summary events: {error}"))?;
+            }
+            for call in value.calls.values_mut() {
+                call.costs
+                    .make_summary()
+                    .map_err(|error| anyhow!("Failed calculating summary events: {error}"))?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl From<Id> for CurrentId {
@@ -116,9 +177,18 @@ impl Parser for HashMapParser {
 
         let mut current_id = CurrentId::default();
         let mut cfn_record = None;
-
-        let mut cfn_totals = HashMap::<Id, Value>::new();
-        let mut fn_totals = HashMap::<Id, Value>::new();
+        let mut names = NameDecoder::default();
+        let mut positions = config.positions_prototype.clone();
+        let malformed = |line: &str| -> anyhow::Error {
+            Error::ParseError((output_path.to_path(), format!("Malformed line: '{line}'"))).into()
+        };
+
+        let mut cfn_totals = HashMap::<Id, Costs>::new();
+        let mut fn_totals = HashMap::<Id, Costs>::new();
+        let mut self_totals = HashMap::<Id, Costs>::new();
+        let mut calls_totals = HashMap::<Id, IndexMap<Id, Call>>::new();
+        let mut lines_totals = HashMap::<Id, IndexMap<u64, Costs>>::new();
+        let mut instrs_totals = HashMap::<Id, IndexMap<u64, Costs>>::new();
 
         let mut sentinel_key = None;
 
@@ -143,7 +213,12 @@ impl Parser for HashMapParser {
                 line.split_once('=')
             };
 
-            match split {
+            let split = split
+                .map(|(key, value)| names.decode(key, value).map(|value| (key, value)))
+                .transpose()
+                .map_err(|error| Error::ParseError((output_path.to_path(), error.to_string())))?;
+
+            match split.as_ref().map(|(key, value)| (*key, value.as_str())) {
                 Some(("ob", obj)) => {
                     current_id.obj = Some(make_path(&self.project_root, obj));
                 }
@@ -159,7 +234,8 @@ impl Parser for HashMapParser {
                         .map_or(false, |sentinel| sentinel.matches(func))
                     {
                         trace!("Found sentinel: {}", func);
-                        sentinel_key = Some(current_id.clone().try_into().expect("A valid id"));
+                        sentinel_key =
+                            Some(current_id.clone().try_into().map_err(|_| malformed(line))?);
                     }
                 }
                 Some(("fi" | "fe", inline)) => {
@@ -182,34 +258,73 @@ impl Parser for HashMapParser {
                     });
                 }
                 Some(("calls", calls)) => {
-                    let record = cfn_record.as_mut().expect("Valid calls line");
+                    let record = cfn_record
+                        .as_mut()
+                        .filter(|record| record.id.is_some())
+                        .ok_or_else(|| malformed(line))?;
                     record.calls = calls
                         .split_ascii_whitespace()
-                        .take(1)
-                        .map(|s| s.parse::<u64>().unwrap())
-                        .sum();
+                        .next()
+                        .and_then(|s| s.parse::<u64>().ok())
+                        .ok_or_else(|| malformed(line))?;
                 }
-                None if line.starts_with(|c: char| c.is_ascii_digit()) => {
+                None if is_cost_line(line) => {
                     let mut costs = config.costs_prototype.clone();
-                    costs.add_iter_str(
-                        line.split_whitespace()
-                            .skip(config.positions_prototype.len()),
-                    );
+                    costs.add_iter_str(line.split_whitespace().skip(positions.len()));
+
+                    positions
+                        .update_iter_str(line.split_whitespace())
+                        .map_err(|_| malformed(line))?;
+                    let id: Id = current_id.try_into().map_err(|_| malformed(line))?;
+                    let line_number = positions.get_by_type(PositionType::Line).unwrap_or(0);
+                    let address = positions.get_by_type(PositionType::Instr);
 
+                    // A cost line following a `calls=` line are the costs of the call, otherwise
+                    // it's the exclusive cost of the current function
                     if let Some(cfn_record) = cfn_record.take() {
-                        cfn_totals
-                            .entry(cfn_record.id.expect("cfn record id must be present"))
-                            .and_modify(|value| value.costs.add(&costs))
-                            .or_insert(Value {
+                        let cfn_id = cfn_record.id.ok_or_else(|| malformed(line))?;
+                        calls_totals
+                            .entry(id.clone())
+                            .or_default()
+                            .entry(cfn_id.clone())
+                            .and_modify(|call| {
+                                call.count += cfn_record.calls;
+                                call.costs.add(&costs);
+                            })
+                            .or_insert_with(|| Call {
+                                count: cfn_record.calls,
                                 costs: costs.clone(),
+                                line: line_number,
                             });
+                        cfn_totals
+                            .entry(cfn_id)
+                            .and_modify(|value| value.add(&costs))
+                            .or_insert_with(|| costs.clone());
+                    } else {
+                        self_totals
+                            .entry(id.clone())
+                            .and_modify(|value| value.add(&costs))
+                            .or_insert_with(|| costs.clone());
+                        lines_totals
+                            .entry(id.clone())
+                            .or_default()
+                            .entry(line_number)
+                            .and_modify(|value| value.add(&costs))
+                            .or_insert_with(|| costs.clone());
+                        if let Some(address) = address {
+                            instrs_totals
+                                .entry(id.clone())
+                                .or_default()
+                                .entry(address)
+                                .and_modify(|value| value.add(&costs))
+                                .or_insert_with(|| costs.clone());
+                        }
                     }
 
-                    let id = current_id.try_into().expect("A valid id");
                     match fn_totals.get_mut(&id) {
-                        Some(value) => value.costs.add(&costs),
+                        Some(value) => value.add(&costs),
                         None => {
-                            fn_totals.insert(id.clone(), Value { costs });
+                            fn_totals.insert(id.clone(), costs);
                         }
                     }
                     current_id = id.into();
@@ -220,7 +335,7 @@ impl Parser for HashMapParser {
                 None if line.starts_with("totals:") || line.starts_with("summary:") => {
                     // we ignore these
                 }
-                Some(_) | None => panic!("Malformed line: '{line}'"),
+                Some(_) | None => return Err(malformed(line)),
             }
         }
 
@@ -229,14 +344,48 @@ impl Parser for HashMapParser {
             fn_totals.insert(key, value);
         }
 
+        let map = fn_totals
+            .into_iter()
+            .map(|(id, costs)| {
+                let self_costs = self_totals
+                    .remove(&id)
+                    .unwrap_or_else(|| config.costs_prototype.clone());
+                let lines = lines_totals.remove(&id).unwrap_or_default();
+                let instrs = instrs_totals.remove(&id).unwrap_or_default();
+                let calls = calls_totals.remove(&id).unwrap_or_default();
+                (
+                    id,
+                    Value {
+                        costs,
+                        self_costs,
+                        lines,
+                        instrs,
+                        calls,
+                    },
+                )
+            })
+            .collect();
+
         Ok(CallgrindMap {
-            map: fn_totals,
+            map,
             sentinel: self.sentinel.clone(),
             sentinel_key,
         })
     }
 }
 
+impl SourcePath {
+    /// Return the path if it is known
+    pub fn to_path_buf(&self) -> Option<PathBuf> {
+        match self {
+            SourcePath::Unknown => None,
+            SourcePath::Rust(path) | SourcePath::Relative(path) | SourcePath::Absolute(path) => {
+                Some(path.clone())
+            }
+        }
+    }
+}
+
 impl Ord for SourcePath {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
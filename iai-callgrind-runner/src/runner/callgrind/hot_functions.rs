@@ -0,0 +1,99 @@
+use super::hashmap_parser::{CallgrindMap, Id, SourcePath, Value};
+use super::model::Costs;
+use crate::api::{self, EventKind};
+use crate::runner::summary::{CostsDiff, FunctionSummary, HotFunctionsSummary};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub limit: usize,
+    pub event_kind: EventKind,
+}
+
+pub struct HotFunctions {
+    pub config: Config,
+}
+
+impl Config {
+    /// Create a new `Config` from the benchmark configuration and the command-line `limit`
+    ///
+    /// The `limit` from the command-line takes precedence over the `limit` of the benchmark
+    /// configuration. Returns `None` if neither is present or the resulting `limit` is `0`.
+    pub fn new(config: Option<api::HotFunctionsConfig>, limit: Option<usize>) -> Option<Self> {
+        let config = match (config, limit) {
+            (None, None) => return None,
+            (config, limit) => {
+                let config = config.unwrap_or_default();
+                Self {
+                    limit: limit.or(config.limit).unwrap_or(10),
+                    event_kind: config.event_kind.unwrap_or(EventKind::Ir),
+                }
+            }
+        };
+
+        (config.limit > 0).then(|| config)
+    }
+}
+
+impl HotFunctions {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Create the [`HotFunctionsSummary`] from already parsed [`CallgrindMap`]s
+    pub fn summarize(
+        &self,
+        map: &CallgrindMap,
+        old_map: Option<&CallgrindMap>,
+    ) -> HotFunctionsSummary {
+        HotFunctionsSummary {
+            event_kind: self.config.event_kind,
+            inclusive: self.top(map, old_map, |value| &value.costs),
+            exclusive: self.top(map, old_map, |value| &value.self_costs),
+        }
+    }
+
+    fn top<F>(
+        &self,
+        map: &CallgrindMap,
+        old_map: Option<&CallgrindMap>,
+        get_costs: F,
+    ) -> Vec<FunctionSummary>
+    where
+        F: Fn(&Value) -> &Costs,
+    {
+        let event_kind = &self.config.event_kind;
+        let mut functions = map
+            .iter()
+            .filter_map(|(id, value)| {
+                get_costs(value)
+                    .cost_by_kind(event_kind)
+                    .filter(|cost| *cost > 0)
+                    .map(|cost| (id, cost))
+            })
+            .collect::<Vec<(&Id, u64)>>();
+
+        // Sort by cost in descending order and then by the function name to make the order stable
+        functions.sort_by(|(a_id, a_cost), (b_id, b_cost)| {
+            b_cost
+                .cmp(a_cost)
+                .then_with(|| a_id.func.cmp(&b_id.func))
+                .then_with(|| a_id.file.cmp(&b_id.file))
+        });
+
+        functions
+            .into_iter()
+            .take(self.config.limit)
+            .map(|(id, cost)| {
+                let old_cost = old_map
+                    .and_then(|old_map| old_map.get_key_value(id))
+                    .and_then(|(_, value)| get_costs(value).cost_by_kind(event_kind));
+                FunctionSummary {
+                    function: id.func.clone(),
+                    file: id.file.as_ref().and_then(SourcePath::to_path_buf),
+                    object: id.obj.as_ref().and_then(SourcePath::to_path_buf),
+                    costs: CostsDiff::new(Some(cost), old_cost),
+                }
+            })
+            .collect()
+    }
+}
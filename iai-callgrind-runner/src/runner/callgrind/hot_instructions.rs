@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use indexmap::IndexMap;
+use log::{debug, warn};
+
+use super::hashmap_parser::{CallgrindMap, SourcePath};
+use super::model::Costs;
+use crate::api::{self, EventKind};
+use crate::runner::tool::ToolOutputPath;
+use crate::util::format_pct;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub event_kind: EventKind,
+    pub functions: usize,
+    pub limit: usize,
+    pub objdump: PathBuf,
+}
+
+pub struct HotInstructions {
+    pub config: Config,
+}
+
+/// A function symbol disassembled by `objdump`
+///
+/// The `address` and the addresses of the `instructions` are the addresses in the object file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub address: u64,
+    pub instructions: IndexMap<u64, String>,
+}
+
+/// The instruction-level hotspot report of a single benchmark
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub benchmark: String,
+    pub event_kind: EventKind,
+    /// The sum of the exclusive costs of all functions
+    pub total: u64,
+    /// The most expensive functions sorted by their exclusive costs
+    pub functions: Vec<HotFunction>,
+}
+
+/// A function with its most expensive instructions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotFunction {
+    pub name: String,
+    pub obj: Option<PathBuf>,
+    /// The exclusive costs of the function
+    pub cost: u64,
+    /// The hottest instructions sorted by their costs
+    pub instructions: Vec<Instruction>,
+}
+
+/// An instruction at its runtime `address` and the disassembly if available
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: u64,
+    pub cost: u64,
+    pub disassembly: Option<String>,
+}
+
+/// The exclusive costs of a function and its instructions
+#[derive(Debug, Default)]
+struct FunctionCosts {
+    cost: u64,
+    instrs: IndexMap<u64, u64>,
+}
+
+impl From<api::HotInstructionsConfig> for Config {
+    fn from(value: api::HotInstructionsConfig) -> Self {
+        Self {
+            event_kind: value.event_kind.unwrap_or(EventKind::Ir),
+            functions: value.functions.unwrap_or(5),
+            limit: value.limit.unwrap_or(10),
+            objdump: value.objdump.unwrap_or_else(|| PathBuf::from("objdump")),
+        }
+    }
+}
+
+impl HotInstructions {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Create the hotspot [`Report`] of the parsed `map` and save it as text file next to the
+    /// callgrind output
+    ///
+    /// The callgrind output must have been created with `--dump-instr=yes`. Object files which
+    /// cannot be disassembled are reported with the instruction addresses only.
+    pub fn create(
+        &self,
+        callgrind_output_path: &ToolOutputPath,
+        map: &CallgrindMap,
+        project_root: &Path,
+        benchmark: String,
+    ) -> Result<PathBuf> {
+        let mut cache: HashMap<PathBuf, Option<Vec<Symbol>>> = HashMap::new();
+        let report = self.report(benchmark, map, |obj| {
+            let path = match obj {
+                SourcePath::Relative(path) => project_root.join(path),
+                SourcePath::Absolute(path) => path.clone(),
+                SourcePath::Rust(_) | SourcePath::Unknown => return None,
+            };
+            cache
+                .entry(path)
+                .or_insert_with_key(|path| match self.disassemble(path) {
+                    Ok(symbols) => Some(symbols),
+                    Err(error) => {
+                        warn!("{error}");
+                        None
+                    }
+                })
+                .clone()
+        })?;
+
+        let path = callgrind_output_path
+            .to_path()
+            .with_extension("hot_instructions.txt");
+        std::fs::write(&path, self.to_text(&report)).with_context(|| {
+            format!(
+                "Failed writing hot instructions report '{}'",
+                path.display()
+            )
+        })?;
+
+        Ok(path)
+    }
+
+    /// Disassemble the object file at `path` with `objdump`
+    pub fn disassemble(&self, path: &Path) -> Result<Vec<Symbol>> {
+        debug!(
+            "Disassembling '{}' with '{}'",
+            path.display(),
+            self.config.objdump.display()
+        );
+        let output = Command::new(&self.config.objdump)
+            .args(["-d", "-C", "--no-show-raw-insn"])
+            .arg(path)
+            .output()
+            .with_context(|| {
+                format!(
+                    "Failed to run '{}'. Showing instructions without disassembly",
+                    self.config.objdump.display()
+                )
+            })?;
+        if output.status.success() {
+            Ok(parse_objdump(&String::from_utf8_lossy(&output.stdout)))
+        } else {
+            Err(anyhow!(
+                "Failed disassembling '{}': {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    /// Collect the hottest instructions of the most expensive functions of the `map`
+    ///
+    /// The `disassemble` function is called with the object file of each reported function and
+    /// should return the disassembled symbols of the object file or `None` if not available.
+    ///
+    /// # Errors
+    ///
+    /// If the `EventKind` is not present in the `map`
+    pub fn report<F>(
+        &self,
+        benchmark: String,
+        map: &CallgrindMap,
+        mut disassemble: F,
+    ) -> Result<Report>
+    where
+        F: FnMut(&SourcePath) -> Option<Vec<Symbol>>,
+    {
+        let event_kind = self.config.event_kind;
+        let try_cost = |costs: &Costs| {
+            costs.cost_by_kind(&event_kind).ok_or_else(|| {
+                anyhow!(
+                    "Failed creating the hot instructions report: Missing event type \
+                     '{event_kind}'"
+                )
+            })
+        };
+
+        // The parts of a function in different files (inlined code) are merged
+        let mut functions: IndexMap<(&str, Option<&SourcePath>), FunctionCosts> = IndexMap::new();
+        let mut total = 0;
+        for (id, value) in map.iter() {
+            let cost = try_cost(&value.self_costs)?;
+            total += cost;
+
+            let function = functions
+                .entry((id.func.as_str(), id.obj.as_ref()))
+                .or_default();
+            function.cost += cost;
+            for (address, costs) in &value.instrs {
+                *function.instrs.entry(*address).or_default() += try_cost(costs)?;
+            }
+        }
+
+        functions.retain(|_, function| function.cost > 0);
+        functions.sort_by(|a_key, a, b_key, b| b.cost.cmp(&a.cost).then_with(|| a_key.cmp(b_key)));
+
+        let mut hot_functions = vec![];
+        for ((name, obj), FunctionCosts { cost, instrs }) in
+            functions.into_iter().take(self.config.functions)
+        {
+            let symbols = obj.and_then(&mut disassemble);
+            let (symbol, offset) = match symbols
+                .as_ref()
+                .and_then(|symbols| find_symbol(symbols, name, &instrs))
+            {
+                Some((symbol, offset)) => (Some(symbol), offset),
+                None => (None, 0),
+            };
+
+            let mut instructions = instrs
+                .into_iter()
+                .filter(|(_, cost)| *cost > 0)
+                .map(|(address, cost)| Instruction {
+                    address,
+                    cost,
+                    disassembly: symbol.and_then(|symbol| {
+                        symbol
+                            .instructions
+                            .get(&address.wrapping_sub(offset))
+                            .cloned()
+                    }),
+                })
+                .collect::<Vec<Instruction>>();
+            instructions.sort_by(|a, b| b.cost.cmp(&a.cost).then(a.address.cmp(&b.address)));
+            instructions.truncate(self.config.limit);
+
+            hot_functions.push(HotFunction {
+                name: name.to_owned(),
+                obj: obj.and_then(SourcePath::to_path_buf),
+                cost,
+                instructions,
+            });
+        }
+
+        Ok(Report {
+            benchmark,
+            event_kind,
+            total,
+            functions: hot_functions,
+        })
+    }
+
+    pub fn to_text(&self, report: &Report) -> String {
+        let mut result = String::new();
+
+        writeln!(result, "{}", "-".repeat(80)).unwrap();
+        writeln!(result, "-- Hot instructions: {}", report.benchmark).unwrap();
+        writeln!(result, "-- Event: {}", report.event_kind).unwrap();
+        writeln!(result, "{}", "-".repeat(80)).unwrap();
+        writeln!(result).unwrap();
+        writeln!(
+            result,
+            "{:>12} {:>8}  Total",
+            report.total,
+            format_pct(report.total, report.total)
+        )
+        .unwrap();
+
+        for function in &report.functions {
+            writeln!(result).unwrap();
+            writeln!(result, "{}", "-".repeat(80)).unwrap();
+            match &function.obj {
+                Some(obj) => {
+                    writeln!(result, "-- Function: {} [{}]", function.name, obj.display()).unwrap();
+                }
+                None => writeln!(result, "-- Function: {}", function.name).unwrap(),
+            }
+            writeln!(result, "{}", "-".repeat(80)).unwrap();
+            writeln!(result).unwrap();
+            writeln!(
+                result,
+                "{:>12} {:>8}  Total",
+                function.cost,
+                format_pct(function.cost, report.total)
+            )
+            .unwrap();
+            writeln!(result).unwrap();
+
+            if function.instructions.is_empty() {
+                writeln!(
+                    result,
+                    "<no instruction costs: callgrind needs to run with --dump-instr=yes>"
+                )
+                .unwrap();
+            }
+            for instruction in &function.instructions {
+                writeln!(
+                    result,
+                    "{:>12} {:>8}  {:#018x}  {}",
+                    instruction.cost,
+                    format_pct(instruction.cost, report.total),
+                    instruction.address,
+                    instruction
+                        .disassembly
+                        .as_deref()
+                        .unwrap_or("<disassembly not available>")
+                )
+                .unwrap();
+            }
+        }
+
+        result
+    }
+}
+
+/// Parse the output of `objdump -d -C --no-show-raw-insn` into the disassembled [`Symbol`]s
+///
+/// A symbol starts with a line like `0000000000001139 <main>:` followed by the instructions in
+/// lines like `    1139:\tpush   %rbp`.
+pub fn parse_objdump(output: &str) -> Vec<Symbol> {
+    let mut symbols: Vec<Symbol> = vec![];
+    for line in output.lines() {
+        if let Some(header) = line.strip_suffix(">:") {
+            if let Some((address, name)) = header.split_once(" <") {
+                if let Ok(address) = u64::from_str_radix(address.trim(), 16) {
+                    symbols.push(Symbol {
+                        name: name.to_owned(),
+                        address,
+                        instructions: IndexMap::new(),
+                    });
+                    continue;
+                }
+            }
+        }
+
+        if let (Some(symbol), Some((address, instruction))) =
+            (symbols.last_mut(), line.split_once(":\t"))
+        {
+            if let Ok(address) = u64::from_str_radix(address.trim(), 16) {
+                let instruction = instruction.split_whitespace().collect::<Vec<_>>();
+                if !instruction.is_empty() {
+                    symbol
+                        .instructions
+                        .insert(address, format_instruction(&instruction));
+                }
+            }
+        }
+    }
+    symbols
+}
+
+/// Find the symbol of the function `name` and the offset of the runtime addresses of the `instrs`
+/// to the addresses in the object file
+///
+/// The names of Rust symbols in the object file may have a hash suffix like `::h0123456789abcdef`
+/// which is not part of the function names in the callgrind output. The lowest address of the
+/// `instrs` is assumed to be the entry of the function and all other addresses must be
+/// instructions of the same symbol.
+fn find_symbol<'a>(
+    symbols: &'a [Symbol],
+    name: &str,
+    instrs: &IndexMap<u64, u64>,
+) -> Option<(&'a Symbol, u64)> {
+    let entry = instrs.keys().min()?;
+    symbols
+        .iter()
+        .filter(|symbol| {
+            symbol.name == name
+                || symbol.name.strip_prefix(name).map_or(false, |rest| {
+                    rest.len() == 19
+                        && rest.starts_with("::h")
+                        && rest[3..].chars().all(|c| c.is_ascii_hexdigit())
+                })
+        })
+        .find_map(|symbol| {
+            let offset = entry.wrapping_sub(symbol.address);
+            instrs
+                .keys()
+                .all(|address| {
+                    symbol
+                        .instructions
+                        .contains_key(&address.wrapping_sub(offset))
+                })
+                .then(|| (symbol, offset))
+        })
+}
+
+/// Format the mnemonic and operands of an instruction separated by a fixed width
+fn format_instruction(parts: &[&str]) -> String {
+    match parts.split_first() {
+        Some((mnemonic, [])) => (*mnemonic).to_owned(),
+        Some((mnemonic, operands)) => format!("{mnemonic:<6} {}", operands.join(" ")),
+        None => String::new(),
+    }
+}
@@ -1,10 +1,20 @@
+pub mod analysis;
+pub mod annotate;
 pub mod args;
+pub mod call_graph;
+pub mod dot;
+pub mod export;
 pub mod flamegraph;
 pub mod flamegraph_parser;
+pub mod function_diff;
 pub mod hashmap_parser;
+pub mod hot_functions;
+pub mod hot_instructions;
 pub mod model;
 pub mod parser;
+pub mod pprof;
 pub mod sentinel_parser;
+pub mod speedscope;
 pub mod summary_parser;
 
 use std::ffi::OsString;
@@ -185,6 +185,47 @@ impl Positions {
         }
     }
 
+    /// Update the positions with the positions of a cost line
+    ///
+    /// With `--compress-pos=yes` a position can be relative to the same position of the previous
+    /// cost line (`+2`, `-3`) or be the same (`*`). Absolute positions are decimal or hexadecimal
+    /// with a `0x` prefix.
+    ///
+    /// # Errors
+    ///
+    /// If a position could not be parsed
+    pub fn update_iter_str<I, T>(&mut self, iter: T) -> Result<()>
+    where
+        I: AsRef<str>,
+        T: IntoIterator<Item = I>,
+    {
+        for ((_, old), pos) in self.0.iter_mut().zip(iter) {
+            let pos = pos.as_ref();
+            let new = if pos == "*" {
+                Some(*old)
+            } else if let Some(diff) = pos.strip_prefix('+') {
+                diff.parse::<u64>()
+                    .ok()
+                    .and_then(|diff| old.checked_add(diff))
+            } else if let Some(diff) = pos.strip_prefix('-') {
+                diff.parse::<u64>()
+                    .ok()
+                    .and_then(|diff| old.checked_sub(diff))
+            } else if let Some(hex) = pos.strip_prefix("0x") {
+                u64::from_str_radix(hex, 16).ok()
+            } else {
+                pos.parse::<u64>().ok()
+            };
+            *old = new.ok_or_else(|| anyhow!("Invalid position: '{pos}'"))?;
+        }
+        Ok(())
+    }
+
+    /// Return the value of the [`PositionType`] if present
+    pub fn get_by_type(&self, position_type: PositionType) -> Option<u64> {
+        self.0.get(&position_type).copied()
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -192,6 +233,11 @@ impl Positions {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Return the index of the [`PositionType`] in a cost line if present
+    pub fn index_of(&self, position_type: PositionType) -> Option<usize> {
+        self.0.get_index_of(&position_type)
+    }
 }
 
 impl Default for Positions {
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fmt::Display;
 
 use anyhow::{anyhow, Result};
@@ -13,6 +14,18 @@ pub struct CallgrindProperties {
     pub positions_prototype: Positions,
 }
 
+/// Decode the compressed names of the callgrind output (`--compress-strings=yes`)
+///
+/// The first occurrence of a name defines an id with `(id) name` and all following occurrences
+/// only refer to this id with `(id)`. Object files (`ob`, `cob`), source files (`fl`, `fi`, `fe`,
+/// `cfi`, `cfl`) and functions (`fn`, `cfn`) have separate ids.
+#[derive(Debug, Default)]
+pub struct NameDecoder {
+    objs: HashMap<u64, String>,
+    files: HashMap<u64, String>,
+    fns: HashMap<u64, String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Sentinel(String);
 
@@ -23,6 +36,40 @@ pub trait Parser {
     where
         Self: std::marker::Sized;
 }
+impl NameDecoder {
+    /// Return the decoded name of the `value` of a name specification line `key=value`
+    ///
+    /// Uncompressed names like `main` or `(below main)` are returned as they are.
+    ///
+    /// # Errors
+    ///
+    /// If the `value` refers to an id which was not defined before
+    pub fn decode(&mut self, key: &str, value: &str) -> Result<String> {
+        let names = match key {
+            "ob" | "cob" => &mut self.objs,
+            "fl" | "fi" | "fe" | "cfi" | "cfl" => &mut self.files,
+            "fn" | "cfn" => &mut self.fns,
+            _ => return Ok(value.to_owned()),
+        };
+
+        let compressed = value
+            .strip_prefix('(')
+            .and_then(|rest| rest.split_once(')'))
+            .and_then(|(id, name)| id.parse::<u64>().ok().map(|id| (id, name.trim())));
+        match compressed {
+            Some((id, "")) => names
+                .get(&id)
+                .cloned()
+                .ok_or_else(|| anyhow!("Undefined compressed name: '{key}={value}'")),
+            Some((id, name)) => {
+                names.insert(id, name.to_owned());
+                Ok(name.to_owned())
+            }
+            None => Ok(value.to_owned()),
+        }
+    }
+}
+
 impl Sentinel {
     pub fn new<T>(value: T) -> Self
     where
@@ -74,6 +121,14 @@ impl Display for Sentinel {
     }
 }
 
+/// Return true if the `line` is a cost line
+///
+/// Cost lines start with an absolute position or, with `--compress-pos=yes`, with a relative
+/// position (`+2`, `-3`, `*`).
+pub fn is_cost_line(line: &str) -> bool {
+    line.starts_with(|c: char| c.is_ascii_digit() || c == '+' || c == '-' || c == '*')
+}
+
 pub fn parse_header(iter: &mut impl Iterator<Item = String>) -> Result<CallgrindProperties> {
     if !iter
         .by_ref()
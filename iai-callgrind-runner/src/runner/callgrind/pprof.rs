@@ -0,0 +1,270 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use indexmap::{IndexMap, IndexSet};
+
+use super::call_graph::{CallGraph, Frame, RunningRound};
+use super::hashmap_parser::{CallgrindMap, Id, SourcePath};
+use crate::api::{self, EventKind};
+use crate::runner::tool::ToolOutputPath;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    pub event_kinds: Option<Vec<EventKind>>,
+}
+
+pub struct Pprof {
+    pub config: Config,
+}
+
+/// A minimal protocol buffers encoder for the messages of the pprof `profile.proto`
+#[derive(Debug, Default)]
+struct Encoder(Vec<u8>);
+
+/// The tables of the pprof profile which are referenced by index or id
+#[derive(Debug, Default)]
+struct Tables<'map> {
+    strings: IndexSet<String>,
+    mappings: IndexSet<&'map SourcePath>,
+    functions: IndexSet<&'map Id>,
+    locations: IndexSet<(&'map Id, u64)>,
+}
+
+impl From<api::PprofConfig> for Config {
+    fn from(value: api::PprofConfig) -> Self {
+        Self {
+            event_kinds: value.event_kinds,
+        }
+    }
+}
+
+impl Pprof {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Return the [`EventKind`]s of the sample types
+    ///
+    /// These are the configured `EventKind`s or else all `EventKind`s recorded in the `map`. The
+    /// derived `EventKind`s are not recorded but added to the `map` on demand, so they are left
+    /// out.
+    pub fn event_kinds(&self, map: &CallgrindMap) -> Vec<EventKind> {
+        self.config.event_kinds.clone().unwrap_or_else(|| {
+            map.iter()
+                .next()
+                .map(|(_, value)| {
+                    value
+                        .costs
+                        .iter()
+                        .map(|(kind, _)| *kind)
+                        .filter(|kind| !kind.is_derived())
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    /// Save the `map` in the pprof protobuf format next to the callgrind output
+    pub fn save(
+        &self,
+        callgrind_output_path: &ToolOutputPath,
+        map: &CallgrindMap,
+    ) -> Result<PathBuf> {
+        let path = callgrind_output_path.to_path().with_extension("pb");
+        std::fs::write(&path, self.encode(map)?)
+            .with_context(|| format!("Failed writing pprof file '{}'", path.display()))?;
+        Ok(path)
+    }
+
+    /// Encode the `map` as pprof profile
+    ///
+    /// The call stacks of the samples are reconstructed from the [`CallGraph`]. The value of a
+    /// sample are the costs of the sample types (the [`EventKind`]s). The locations of the samples
+    /// are the lines of the function with the costs and the call sites in the calling functions.
+    ///
+    /// # Errors
+    ///
+    /// If one of the `EventKind`s is not present in the `map`
+    pub fn encode(&self, map: &CallgrindMap) -> Result<Vec<u8>> {
+        let event_kinds = self.event_kinds(map);
+        let mut tables = Tables::default();
+        tables.strings.insert(String::new());
+
+        let mut samples: IndexMap<Vec<u64>, Vec<f64>> = IndexMap::new();
+        if !map.is_empty() {
+            let call_graph = CallGraph::new(map, &event_kinds)?;
+            call_graph.walk(None, |frames, costs| {
+                // The first location is the leaf
+                let locations = frames
+                    .iter()
+                    .rev()
+                    .map(|frame| tables.location_id(frame))
+                    .collect::<Vec<u64>>();
+                let values = samples
+                    .entry(locations)
+                    .or_insert_with(|| vec![0f64; costs.len()]);
+                for (value, cost) in values.iter_mut().zip(costs.iter()) {
+                    *value += cost;
+                }
+            });
+        }
+
+        let mut encoder = Encoder::default();
+        for event_kind in &event_kinds {
+            let type_index = tables.string_index(&event_kind.to_string());
+            let unit_index = tables.string_index("count");
+            encoder.message(1, |value_type| {
+                value_type.int64(1, type_index);
+                value_type.int64(2, unit_index);
+            });
+        }
+
+        let mut rounding = vec![RunningRound::default(); event_kinds.len()];
+        for (locations, values) in &samples {
+            let values = values
+                .iter()
+                .zip(rounding.iter_mut())
+                .map(|(value, rounding)| rounding.round(*value))
+                .collect::<Vec<u64>>();
+            if values.iter().all(|value| *value == 0) {
+                continue;
+            }
+
+            encoder.message(2, |sample| {
+                sample.packed(1, locations.iter().copied());
+                sample.packed(2, values.iter().copied());
+            });
+        }
+
+        // The functions and mappings are collected while encoding the locations
+        for (index, (id, line)) in tables.locations.clone().iter().enumerate() {
+            let function_id = tables.function_id(id);
+            let mapping_id = id.obj.as_ref().map_or(0, |obj| tables.mapping_id(obj));
+
+            encoder.message(4, |location| {
+                location.uint64(1, index as u64 + 1);
+                location.uint64(2, mapping_id);
+                location.message(4, |location_line| {
+                    location_line.uint64(1, function_id);
+                    #[allow(clippy::cast_possible_wrap)]
+                    location_line.int64(2, *line as i64);
+                });
+            });
+        }
+
+        for (index, obj) in tables.mappings.clone().iter().enumerate() {
+            let filename = tables.string_index(&to_string(obj));
+            encoder.message(3, |mapping| {
+                mapping.uint64(1, index as u64 + 1);
+                mapping.int64(5, filename);
+                mapping.uint64(7, 1);
+                mapping.uint64(8, 1);
+                mapping.uint64(9, 1);
+            });
+        }
+
+        for (index, id) in tables.functions.clone().iter().enumerate() {
+            let name = tables.string_index(&id.func);
+            let filename =
+                tables.string_index(&id.file.as_ref().map(to_string).unwrap_or_default());
+            encoder.message(5, |function| {
+                function.uint64(1, index as u64 + 1);
+                function.int64(2, name);
+                function.int64(3, name);
+                function.int64(4, filename);
+            });
+        }
+
+        for string in &tables.strings {
+            encoder.bytes(6, string.as_bytes());
+        }
+
+        Ok(encoder.0)
+    }
+}
+
+impl<'map> Tables<'map> {
+    fn string_index(&mut self, string: &str) -> i64 {
+        let index = match self.strings.get_index_of(string) {
+            Some(index) => index,
+            None => self.strings.insert_full(string.to_owned()).0,
+        };
+        #[allow(clippy::cast_possible_wrap)]
+        let index = index as i64;
+        index
+    }
+
+    fn mapping_id(&mut self, obj: &'map SourcePath) -> u64 {
+        self.mappings.insert_full(obj).0 as u64 + 1
+    }
+
+    fn function_id(&mut self, id: &'map Id) -> u64 {
+        self.functions.insert_full(id).0 as u64 + 1
+    }
+
+    fn location_id(&mut self, frame: &Frame<'map>) -> u64 {
+        self.locations.insert_full((frame.id, frame.line)).0 as u64 + 1
+    }
+}
+
+impl Encoder {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            #[allow(clippy::cast_possible_truncation)]
+            self.0.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7_u32;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        self.0.push(value as u8);
+    }
+
+    fn key(&mut self, field: u64, wire_type: u64) {
+        self.varint(field << 3 | wire_type);
+    }
+
+    /// Encode a `uint64` field omitting the default value `0`
+    fn uint64(&mut self, field: u64, value: u64) {
+        if value != 0 {
+            self.key(field, 0);
+            self.varint(value);
+        }
+    }
+
+    /// Encode an `int64` field omitting the default value `0`
+    fn int64(&mut self, field: u64, value: i64) {
+        #[allow(clippy::cast_sign_loss)]
+        self.uint64(field, value as u64);
+    }
+
+    fn bytes(&mut self, field: u64, bytes: &[u8]) {
+        self.key(field, 2);
+        self.varint(bytes.len() as u64);
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn packed<T>(&mut self, field: u64, values: T)
+    where
+        T: IntoIterator<Item = u64>,
+    {
+        let mut packed = Encoder::default();
+        for value in values {
+            packed.varint(value);
+        }
+        self.bytes(field, &packed.0);
+    }
+
+    fn message<F>(&mut self, field: u64, encode: F)
+    where
+        F: FnOnce(&mut Encoder),
+    {
+        let mut message = Encoder::default();
+        encode(&mut message);
+        self.bytes(field, &message.0);
+    }
+}
+
+fn to_string(path: &SourcePath) -> String {
+    path.to_path_buf()
+        .map(|path| path.display().to_string())
+        .unwrap_or_default()
+}
@@ -2,9 +2,8 @@ use anyhow::Result;
 use log::{debug, trace};
 
 use super::model::Costs;
-use super::parser::{Parser, Sentinel};
+use super::parser::{is_cost_line, parse_header, NameDecoder, Parser, Sentinel};
 use crate::error::Error;
-use crate::runner::callgrind::parser::parse_header;
 use crate::runner::tool::ToolOutputPath;
 
 pub struct SentinelParser {
@@ -38,6 +37,7 @@ impl Parser for SentinelParser {
         let mut found = false;
         let mut costs = properties.costs_prototype;
         let mut start_record = false;
+        let mut names = NameDecoder::default();
 
         for line in iter.filter(|p| !p.starts_with('#')) {
             let line = line.trim();
@@ -45,9 +45,21 @@ impl Parser for SentinelParser {
                 start_record = false;
                 continue;
             }
+
+            // The names of all lines have to be decoded to know the ids of compressed names
+            let func = match line.split_once('=') {
+                Some((key, value)) => {
+                    let name = names.decode(key, value).map_err(|error| {
+                        Error::ParseError((output_path.to_path(), error.to_string()))
+                    })?;
+                    (key == "fn").then(|| name)
+                }
+                None => None,
+            };
+
             if !start_record {
-                if let Some(func) = line.strip_prefix("fn=") {
-                    if self.sentinel.matches(func) {
+                if let Some(func) = func {
+                    if self.sentinel.matches(&func) {
                         {
                             trace!("Found line with sentinel: '{}'", line);
                             start_record = true;
@@ -59,7 +71,7 @@ impl Parser for SentinelParser {
             }
 
             // we check if it is a line with counters and summarize them
-            if line.starts_with(|c: char| c.is_ascii_digit()) {
+            if is_cost_line(line) {
                 // From the documentation of the callgrind format:
                 // > If a cost line specifies less event counts than given in the "events" line, the
                 // > rest is assumed to be zero.
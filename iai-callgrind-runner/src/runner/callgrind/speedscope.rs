@@ -0,0 +1,208 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use indexmap::{IndexMap, IndexSet};
+use serde::Serialize;
+
+use super::call_graph::{CallGraph, FunctionKey, RunningRound};
+use super::hashmap_parser::{CallgrindMap, SourcePath};
+use crate::api::{self, EventKind};
+use crate::runner::tool::ToolOutputPath;
+
+const SCHEMA: &str = "https://www.speedscope.app/file-format-schema.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    pub event_kinds: Option<Vec<EventKind>>,
+}
+
+pub struct Speedscope {
+    pub config: Config,
+}
+
+/// The speedscope file format
+///
+/// See <https://github.com/jlfwong/speedscope/wiki/Importing-from-custom-sources>
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct File {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub name: String,
+    pub exporter: String,
+    pub active_profile_index: usize,
+    pub shared: Shared,
+    pub profiles: Vec<Profile>,
+}
+
+/// The frames shared by all profiles
+#[derive(Debug, Serialize)]
+pub struct Shared {
+    pub frames: Vec<Frame>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Frame {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+/// A sampled profile of a single [`EventKind`]
+///
+/// The `samples` are call stacks of indices into the shared frames from the root to the leaf and
+/// the `weights` are the costs of these stacks.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub name: String,
+    pub unit: &'static str,
+    pub start_value: u64,
+    pub end_value: u64,
+    pub samples: Vec<Vec<usize>>,
+    pub weights: Vec<u64>,
+}
+
+impl From<api::SpeedscopeConfig> for Config {
+    fn from(value: api::SpeedscopeConfig) -> Self {
+        Self {
+            event_kinds: value.event_kinds,
+        }
+    }
+}
+
+impl Speedscope {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Return the [`EventKind`]s of the profiles
+    ///
+    /// These are the configured `EventKind`s or else all `EventKind`s recorded in the `map`. The
+    /// derived `EventKind`s are not recorded but added to the `map` on demand, so they are left
+    /// out.
+    pub fn event_kinds(&self, map: &CallgrindMap) -> Vec<EventKind> {
+        self.config.event_kinds.clone().unwrap_or_else(|| {
+            map.iter()
+                .next()
+                .map(|(_, value)| {
+                    value
+                        .costs
+                        .iter()
+                        .map(|(kind, _)| *kind)
+                        .filter(|kind| !kind.is_derived())
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    /// Save the `map` in the speedscope json format next to the callgrind output
+    pub fn save(
+        &self,
+        callgrind_output_path: &ToolOutputPath,
+        map: &CallgrindMap,
+    ) -> Result<PathBuf> {
+        let path = callgrind_output_path
+            .to_path()
+            .with_extension("speedscope.json");
+        let name = callgrind_output_path
+            .to_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let file = std::fs::File::create(&path)
+            .with_context(|| format!("Failed creating speedscope file '{}'", path.display()))?;
+        serde_json::to_writer(file, &self.create(name, map)?)
+            .with_context(|| format!("Failed writing speedscope file '{}'", path.display()))?;
+        Ok(path)
+    }
+
+    /// Create the speedscope [`File`] with a [`Profile`] for each [`EventKind`]
+    ///
+    /// The call stacks are reconstructed from the [`CallGraph`] at the level of functions, so the
+    /// costs of all lines of a function are merged into a single frame.
+    ///
+    /// # Errors
+    ///
+    /// If one of the `EventKind`s is not present in the `map`
+    pub fn create(&self, name: String, map: &CallgrindMap) -> Result<File> {
+        let event_kinds = self.event_kinds(map);
+
+        let mut frames: IndexSet<FunctionKey> = IndexSet::new();
+        let mut stacks: IndexMap<Vec<usize>, Vec<f64>> = IndexMap::new();
+        let call_graph = if map.is_empty() {
+            None
+        } else {
+            let call_graph = CallGraph::new(map, &event_kinds)?;
+            call_graph.walk(None, |stack, costs| {
+                // The stacks of the different lines of a function are merged
+                let indices = stack
+                    .iter()
+                    .map(|frame| frames.insert_full(frame.key).0)
+                    .collect::<Vec<usize>>();
+                let values = stacks
+                    .entry(indices)
+                    .or_insert_with(|| vec![0f64; costs.len()]);
+                for (value, cost) in values.iter_mut().zip(costs.iter()) {
+                    *value += cost;
+                }
+            });
+            Some(call_graph)
+        };
+
+        let profiles = event_kinds
+            .iter()
+            .enumerate()
+            .map(|(index, event_kind)| {
+                let mut profile = Profile {
+                    kind: "sampled",
+                    name: event_kind.to_string(),
+                    unit: "none",
+                    start_value: 0,
+                    end_value: 0,
+                    samples: vec![],
+                    weights: vec![],
+                };
+                let mut rounding = RunningRound::default();
+                for (stack, values) in &stacks {
+                    let weight = rounding.round(values[index]);
+                    if weight > 0 {
+                        profile.samples.push(stack.clone());
+                        profile.weights.push(weight);
+                        profile.end_value += weight;
+                    }
+                }
+                profile
+            })
+            .collect();
+
+        let frames = frames
+            .iter()
+            .map(|key| {
+                let id = call_graph
+                    .as_ref()
+                    .and_then(|call_graph| call_graph.id(key));
+                Frame {
+                    name: key.0.to_owned(),
+                    file: id
+                        .and_then(|id| id.file.as_ref())
+                        .and_then(SourcePath::to_path_buf)
+                        .map(|path| path.display().to_string()),
+                }
+            })
+            .collect();
+
+        Ok(File {
+            schema: SCHEMA,
+            name,
+            exporter: format!("iai-callgrind-runner@{}", env!("CARGO_PKG_VERSION")),
+            active_profile_index: 0,
+            shared: Shared { frames },
+            profiles,
+        })
+    }
+}
@@ -0,0 +1,170 @@
+//! The `cargo bench` invocation which runs all benchmark files
+//!
+//! The `main!` macro of each benchmark passes the process id of its parent process, usually cargo,
+//! in [`envs::IAI_CALLGRIND_CARGO_PID`] to the runner. Process ids are reused, especially in
+//! containers where cargo often gets the same process id in each run, so the id of a [`CargoRun`]
+//! also contains the start time of the cargo process.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use log::debug;
+
+use super::envs;
+use super::summary::to_millis;
+
+/// The tolerance in milliseconds of the start time of cargo, which is exact to a second with `ps`
+const START_TIME_TOLERANCE: u64 = 2000;
+
+/// The clock ticks per second of the start time in `/proc/<pid>/stat` (`USER_HZ`)
+const CLOCK_TICKS: f64 = 100f64;
+
+/// The `cargo bench` invocation shared by all benchmark files of a benchmark run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CargoRun {
+    /// The unique id of the run consisting of the process id and the start time of cargo
+    pub id: String,
+    /// The approximate start time of cargo
+    pub start_time: SystemTime,
+}
+
+impl CargoRun {
+    /// Detect the `CargoRun` from the process id in [`envs::IAI_CALLGRIND_CARGO_PID`]
+    ///
+    /// Returns `None` if the process id is not present or the start time of the process could not
+    /// be determined.
+    pub fn detect() -> Option<Self> {
+        let pid = std::env::var(envs::IAI_CALLGRIND_CARGO_PID)
+            .ok()
+            .and_then(|pid| pid.parse::<u32>().ok())?;
+        let run = Self::from_proc(pid).or_else(|| Self::from_ps(pid));
+        if run.is_none() {
+            debug!("Unable to determine the start time of the cargo process with pid '{pid}'");
+        }
+        run
+    }
+
+    /// Return the earliest start time of a benchmark file of this run in milliseconds since the
+    /// unix epoch
+    pub fn earliest_start_time(&self) -> u64 {
+        to_millis(self.start_time).saturating_sub(START_TIME_TOLERANCE)
+    }
+
+    /// Detect the `CargoRun` of the process `pid` from the `/proc` filesystem on linux
+    #[allow(clippy::cast_precision_loss)]
+    fn from_proc(pid: u32) -> Option<Self> {
+        let stat =
+            std::fs::read_to_string(Path::new("/proc").join(pid.to_string()).join("stat")).ok()?;
+        let uptime = std::fs::read_to_string("/proc/uptime").ok()?;
+        let (start_ticks, uptime) = parse_proc_stat(&stat, &uptime)?;
+
+        let elapsed = (uptime - start_ticks as f64 / CLOCK_TICKS).max(0f64);
+        Some(Self {
+            id: format!("{pid}-{start_ticks}"),
+            start_time: SystemTime::now() - Duration::from_secs_f64(elapsed),
+        })
+    }
+
+    /// Detect the `CargoRun` of the process `pid` with `ps`
+    fn from_ps(pid: u32) -> Option<Self> {
+        let output = Command::new("ps")
+            .args(["-o", "etime=", "-o", "lstart=", "-p", &pid.to_string()])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+        let (elapsed, start) = parse_ps(&String::from_utf8_lossy(&output.stdout))?;
+
+        Some(Self {
+            id: format!("{pid}-{start}"),
+            start_time: SystemTime::now() - Duration::from_secs(elapsed),
+        })
+    }
+}
+
+/// Parse the start time in clock ticks from `/proc/<pid>/stat` and the uptime in seconds
+///
+/// The command name in the `stat` file is in parentheses and may contain spaces, so the fields
+/// are counted from the last `)`. The start time is the 22nd field.
+fn parse_proc_stat(stat: &str, uptime: &str) -> Option<(u64, f64)> {
+    let start_ticks = stat
+        .rsplit_once(')')
+        .and_then(|(_, fields)| fields.split_whitespace().nth(19))
+        .and_then(|ticks| ticks.parse::<u64>().ok())?;
+    let uptime = uptime
+        .split_whitespace()
+        .next()
+        .and_then(|uptime| uptime.parse::<f64>().ok())?;
+    Some((start_ticks, uptime))
+}
+
+/// Parse the output of `ps -o etime= -o lstart=` into the elapsed seconds and the start time
+///
+/// The elapsed time has the format `[[dd-]hh:]mm:ss`. Spaces in the start time are replaced by
+/// `_`.
+fn parse_ps(output: &str) -> Option<(u64, String)> {
+    let mut fields = output.split_whitespace();
+    let etime = fields.next()?;
+    let start = fields.collect::<Vec<_>>().join("_");
+    if start.is_empty() {
+        return None;
+    }
+
+    let (days, time) = match etime.split_once('-') {
+        Some((days, time)) => (days.parse::<u64>().ok()?, time),
+        None => (0, etime),
+    };
+    let mut elapsed = days * 24 * 60 * 60;
+    let mut factor = 1;
+    for value in time.rsplit(':') {
+        elapsed += value.parse::<u64>().ok()? * factor;
+        factor *= 60;
+    }
+    Some((elapsed, start))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::simple(
+        "1234 (cargo) S 1 1234 1234 0 -1 4194304 100 0 0 0 1 2 0 0 20 0 1 0 5000 10000 100",
+        "120.50 100.00",
+        Some((5000, 120.5f64))
+    )]
+    #[case::name_with_spaces_and_parentheses(
+        "1234 (my (car) go) S 1 1234 1234 0 -1 4194304 100 0 0 0 1 2 0 0 20 0 1 0 42 10000 100",
+        "7.00 1.00",
+        Some((42, 7f64))
+    )]
+    #[case::too_short("1234 (cargo) S 1 1234", "7.00 1.00", None)]
+    #[case::invalid_uptime(
+        "1234 (cargo) S 1 1234 1234 0 -1 4194304 100 0 0 0 1 2 0 0 20 0 1 0 42 10000 100",
+        "",
+        None
+    )]
+    fn test_parse_proc_stat(
+        #[case] stat: &str,
+        #[case] uptime: &str,
+        #[case] expected: Option<(u64, f64)>,
+    ) {
+        assert_eq!(parse_proc_stat(stat, uptime), expected);
+    }
+
+    #[rstest]
+    #[case::seconds("   00:05 Sat Oct 18 10:00:00 2026\n", Some(5))]
+    #[case::minutes("12:05 Sat Oct 18 10:00:00 2026", Some(725))]
+    #[case::hours("01:00:01 Sat Oct 18 10:00:00 2026", Some(3601))]
+    #[case::days("2-00:00:10 Sat Oct 18 10:00:00 2026", Some(172_810))]
+    #[case::no_start_time("00:05", None)]
+    #[case::invalid("abc Sat Oct 18 10:00:00 2026", None)]
+    fn test_parse_ps(#[case] output: &str, #[case] expected: Option<u64>) {
+        assert_eq!(
+            parse_ps(output),
+            expected.map(|elapsed| (elapsed, "Sat_Oct_18_10:00:00_2026".to_owned()))
+        );
+    }
+}
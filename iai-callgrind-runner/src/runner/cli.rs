@@ -0,0 +1,639 @@
+//! The subcommands of the `iai-callgrind-runner` if invoked directly on the command line
+//!
+//! Usually, the runner is invoked by the `main!` macro of a benchmark with the positional
+//! arguments of the benchmark run. If the first argument is one of the [`SUBCOMMANDS`], the runner
+//! is instead used as standalone command line tool.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use log::debug;
+
+use super::args::{parse_event_kinds, parse_regression_config, EventKinds};
+use super::callgrind::flamegraph::{Config as FlamegraphConfig, Flamegraph};
+use super::callgrind::parser::{Parser as _, Sentinel};
+use super::callgrind::sentinel_parser::SentinelParser;
+use super::callgrind::summary_parser::SummaryParser;
+use super::callgrind::Regression;
+use super::envs;
+use super::git::{self, GitRevision, Worktree};
+use super::history::chart::LineChart;
+use super::history::trend::{format_trends, Trend};
+use super::history::{History, DIR_NAME as HISTORY_DIR_NAME};
+use super::print::{Formatter, Header, VerticalFormat};
+use super::report::DEFAULT_EVENT_KINDS;
+use super::summary::{CostsSummary, FlamegraphSummary};
+use super::tool::ToolOutputPath;
+use crate::api::{EventKind, FlamegraphKind, RegressionConfig};
+use crate::error::Error;
+
+/// The names of all subcommands
+pub const SUBCOMMANDS: [&str; 4] = ["history", "compare-rev", "compare", "flamegraph"];
+
+#[derive(Parser, Debug)]
+#[clap(
+    name = "iai-callgrind-runner",
+    version,
+    about = "High-precision and consistent benchmarking framework/harness for Rust",
+    long_about = None,
+)]
+pub struct Cli {
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Show the trends of the benchmarks recorded with `--save-history`
+    ///
+    /// For each benchmark and event kind, the costs of the last runs are printed and a svg line
+    /// chart is saved in the charts directory.
+    History(HistoryArgs),
+    /// Compare the benchmarks of the current working tree against another git revision
+    ///
+    /// The revision is checked out in a temporary git worktree and its benchmarks are run with
+    /// `--save-baseline-commit`. Then, the benchmarks of the current working tree are run with
+    /// `--baseline-commit` against this baseline. Both runs share the target directory.
+    CompareRev(CompareRevArgs),
+    /// Compare the costs of two existing callgrind output files
+    ///
+    /// The costs are printed like in a benchmark run. If limits are given with `--regression`,
+    /// this command fails if the performance has regressed. The file names have to be in the
+    /// format of valgrind like `callgrind.out.<pid>` or of iai-callgrind like
+    /// `callgrind.<name>.out`.
+    Compare(CompareArgs),
+    /// Create flamegraphs of an existing callgrind output file
+    ///
+    /// A regular flamegraph is created for each event kind and if an old callgrind output file is
+    /// given also a differential flamegraph. The svg files are stored next to the callgrind output
+    /// file. The file names have to be in the same format as for the `compare` subcommand.
+    Flamegraph(FlamegraphArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    /// The directory with the history files (default: target/iai)
+    ///
+    /// All history files in this directory and its subdirectories are loaded.
+    #[clap(long = "target-dir")]
+    pub target_dir: Option<PathBuf>,
+
+    /// The number of the last runs of each benchmark to show (at least `1`)
+    #[clap(
+        long = "last",
+        default_value = "10",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub last: usize,
+
+    /// Show only benchmarks with the module path and id containing this string
+    #[clap(long = "filter")]
+    pub filter: Option<String>,
+
+    /// The `,` separated list of `EventKinds` to show (default: Ir,EstimatedCycles)
+    #[clap(long = "event-kinds", value_parser = parse_event_kinds)]
+    pub event_kinds: Option<EventKinds>,
+
+    /// The directory in which the svg charts are saved (default: <TARGET_DIR>/history-charts)
+    #[clap(long = "charts-dir")]
+    pub charts_dir: Option<PathBuf>,
+
+    /// Don't save any svg charts
+    #[clap(long = "no-charts")]
+    pub no_charts: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CompareRevArgs {
+    /// The git revision like `main`, a tag, a commit hash or `merge-base(main)`
+    #[clap(value_parser = GitRevision::parse)]
+    pub revision: GitRevision,
+
+    /// The package with the benchmarks (see `cargo bench --package`)
+    #[clap(long = "package", short = 'p')]
+    pub package: Option<String>,
+
+    /// Run only the benchmark with this name (see `cargo bench --bench`)
+    ///
+    /// This option can be specified multiple times.
+    #[clap(long = "bench")]
+    pub benches: Vec<String>,
+
+    /// The arguments passed to the benchmark runs like `-- --regression=Ir=5`
+    ///
+    /// The regression and baseline options only apply to the run of the current working tree.
+    #[clap(last = true)]
+    pub bench_args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CompareArgs {
+    /// The new callgrind output file
+    pub new: PathBuf,
+
+    /// The old callgrind output file to compare against
+    pub old: PathBuf,
+
+    /// Compare only the inclusive costs of the first function starting with this name
+    ///
+    /// Without a sentinel, the total costs of the callgrind output files are compared.
+    ///
+    /// Examples: --sentinel=main, --sentinel=fibonacci
+    #[clap(long = "sentinel")]
+    pub sentinel: Option<String>,
+
+    /// Fail if the performance has regressed by more than these limits
+    ///
+    /// The format is the same as for the `--regression` option of a benchmark run.
+    ///
+    /// Examples: --regression='ir=0.0' or --regression='ir=0, EstimatedCycles=10'
+    #[clap(long = "regression", value_parser = parse_regression_config)]
+    pub regression: Option<RegressionConfig>,
+}
+
+#[derive(Args, Debug)]
+pub struct FlamegraphArgs {
+    /// The callgrind output file
+    pub path: PathBuf,
+
+    /// The old callgrind output file for the differential flamegraphs
+    #[clap(long = "old")]
+    pub old: Option<PathBuf>,
+
+    /// The `,` separated list of `EventKinds` with a flamegraph each (default: Ir)
+    #[clap(long = "event-kind", value_parser = parse_event_kinds)]
+    pub event_kinds: Option<EventKinds>,
+
+    /// Create the flamegraphs only of the first function starting with this name
+    ///
+    /// Without a sentinel, the flamegraphs show all functions of the callgrind output files.
+    #[clap(long = "sentinel")]
+    pub sentinel: Option<String>,
+
+    /// The title of the flamegraphs (default: the file name of the callgrind output file)
+    #[clap(long = "title")]
+    pub title: Option<String>,
+}
+
+/// Return true if the `arg` is the name of a subcommand
+pub fn is_subcommand(arg: &OsString) -> bool {
+    arg.to_str().map_or(false, |arg| SUBCOMMANDS.contains(&arg))
+}
+
+/// Parse the command line `args` including the binary name and run the subcommand
+pub fn run(args: Vec<OsString>) -> Result<()> {
+    let cli = Cli::parse_from(args);
+    match cli.command {
+        Command::History(args) => run_history(&args),
+        Command::CompareRev(args) => run_compare_rev(&args),
+        Command::Compare(args) => run_compare(&args),
+        Command::Flamegraph(args) => run_flamegraph(&args),
+    }
+}
+
+fn run_history(args: &HistoryArgs) -> Result<()> {
+    let target_dir = match &args.target_dir {
+        Some(target_dir) => target_dir.clone(),
+        None => default_target_dir()?,
+    };
+    let entries = History::new(&target_dir).load()?;
+    let event_kinds = args
+        .event_kinds
+        .clone()
+        .unwrap_or_else(|| DEFAULT_EVENT_KINDS.to_vec());
+    let trends = Trend::from_entries(&entries, &event_kinds, args.last, args.filter.as_deref());
+    if trends.is_empty() {
+        println!(
+            "No history found in '{}'. Run the benchmarks with --save-history to record it.",
+            target_dir.display()
+        );
+        return Ok(());
+    }
+
+    print!("{}", format_trends(&trends));
+    if !args.no_charts {
+        let charts_dir = args
+            .charts_dir
+            .clone()
+            .unwrap_or_else(|| target_dir.join(format!("{HISTORY_DIR_NAME}-charts")));
+        for trend in &trends {
+            LineChart::new(trend).save(&charts_dir)?;
+        }
+        println!();
+        println!(
+            "Saved {} charts in '{}'",
+            trends.len(),
+            charts_dir.display()
+        );
+    }
+    Ok(())
+}
+
+fn run_compare_rev(args: &CompareRevArgs) -> Result<()> {
+    let current_dir =
+        std::env::current_dir().with_context(|| "Failed to determine the current directory")?;
+    let commit = args.revision.resolve(&current_dir)?;
+    let target_dir = cargo_target_dir()?;
+
+    // The worktree is outside of the current cargo workspace, so cargo doesn't mistake the
+    // packages of the worktree as members of the current workspace. The directory is unique, so
+    // concurrent invocations don't share a worktree.
+    let worktree_dir = tempfile::Builder::new()
+        .prefix("iai-callgrind-compare-rev-")
+        .tempdir()
+        .with_context(|| "Failed to create a temporary directory for the git worktree")?;
+    let worktree = Worktree::add(&current_dir, &worktree_dir.path().join(&commit), &commit)?;
+    let toplevel = git::toplevel(&current_dir)?;
+    let prefix = current_dir
+        .canonicalize()
+        .ok()
+        .and_then(|dir| {
+            toplevel
+                .canonicalize()
+                .ok()
+                .and_then(|toplevel| dir.strip_prefix(toplevel).ok().map(Path::to_path_buf))
+        })
+        .unwrap_or_default();
+
+    println!(
+        "Running the benchmarks of '{}' ({commit}) to create the baseline",
+        args.revision
+    );
+    // The baseline run must not fail because of a regression against a stale `*.old` output, so
+    // the regression checks and baselines apply to the second run only.
+    let mut command = cargo_bench(
+        args,
+        &baseline_bench_args(&args.bench_args),
+        &worktree.path.join(prefix),
+        &target_dir,
+    );
+    command
+        .env(envs::IAI_CALLGRIND_SAVE_BASELINE_COMMIT, "yes")
+        .env_remove(envs::IAI_CALLGRIND_BASELINE_COMMIT)
+        .env_remove(envs::IAI_CALLGRIND_REGRESSION)
+        .env_remove(envs::IAI_CALLGRIND_REGRESSION_FAIL_FAST);
+    run_cargo_bench(command)?;
+    drop(worktree);
+    drop(worktree_dir);
+
+    println!(
+        "Running the benchmarks of the current working tree against '{}' ({commit})",
+        args.revision
+    );
+    let mut command = cargo_bench(args, &args.bench_args, &current_dir, &target_dir);
+    command.env(envs::IAI_CALLGRIND_BASELINE_COMMIT, &commit);
+    run_cargo_bench(command)
+}
+
+fn run_compare(args: &CompareArgs) -> Result<()> {
+    let sentinel = args.sentinel.as_ref().map(Sentinel::new);
+    let costs_summary = compare(&args.new, &args.old, sentinel.as_ref())?;
+
+    Header::new(
+        format!("{} vs {}", args.new.display(), args.old.display()),
+        None,
+        None,
+    )
+    .print();
+    print!("{}", VerticalFormat::default().format(&costs_summary)?);
+
+    if let Some(config) = &args.regression {
+        let regressions = Regression::from(config.clone()).check_and_print(&costs_summary);
+        if !regressions.is_empty() {
+            return Err(Error::RegressionError(false).into());
+        }
+    }
+    Ok(())
+}
+
+/// Compare the costs of the callgrind output files `new` and `old`
+///
+/// If a `sentinel` is given, only the inclusive costs of the first function matching the sentinel
+/// are compared, otherwise the total costs.
+pub fn compare(new: &Path, old: &Path, sentinel: Option<&Sentinel>) -> Result<CostsSummary> {
+    let new = ToolOutputPath::from_existing(new)?;
+    let old = ToolOutputPath::from_existing(old)?;
+    let (new_costs, old_costs) = if let Some(sentinel) = sentinel {
+        let parser = SentinelParser::new(sentinel);
+        (parser.parse(&new)?, parser.parse(&old)?)
+    } else {
+        (SummaryParser.parse(&new)?, SummaryParser.parse(&old)?)
+    };
+    Ok(CostsSummary::new(&new_costs, Some(&old_costs)))
+}
+
+fn run_flamegraph(args: &FlamegraphArgs) -> Result<()> {
+    let project_root =
+        std::env::current_dir().with_context(|| "Failed to determine the current directory")?;
+    let summaries = flamegraph(args, &project_root)?;
+    if summaries.is_empty() {
+        return Err(anyhow!(
+            "No flamegraphs created from '{}'",
+            args.path.display()
+        ));
+    }
+    for summary in summaries {
+        for path in [summary.regular_path, summary.diff_path].iter().flatten() {
+            println!("Created flamegraph '{}'", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Create the flamegraphs of the callgrind output files in the `args`
+///
+/// Paths of the functions in the flamegraphs are shown relative to the `project_root`.
+pub fn flamegraph(args: &FlamegraphArgs, project_root: &Path) -> Result<Vec<FlamegraphSummary>> {
+    let output_path = ToolOutputPath::from_existing(&args.path)?;
+    let old_output_path = args
+        .old
+        .as_ref()
+        .map(ToolOutputPath::from_existing)
+        .transpose()?;
+    let sentinel = args.sentinel.as_ref().map(Sentinel::new);
+
+    let file_name = |path: &Path| {
+        path.file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+    };
+    let config = FlamegraphConfig {
+        kind: FlamegraphKind::All,
+        negate_differential: false,
+        normalize_differential: false,
+        event_kinds: args
+            .event_kinds
+            .clone()
+            .unwrap_or_else(|| vec![EventKind::Ir]),
+        direction: inferno::flamegraph::Direction::Inverted,
+        title: args.title.clone(),
+        subtitle: args
+            .old
+            .as_ref()
+            .map(|old| format!("compared with {}", file_name(old))),
+        min_width: 0.1f64,
+        max_depth: None,
+        folded: false,
+    };
+    Flamegraph::new(file_name(&args.path), config).create_with_old(
+        &output_path,
+        old_output_path.as_ref(),
+        sentinel.as_ref(),
+        project_root,
+    )
+}
+
+/// Return the `bench_args` without the regression and baseline options for the baseline run
+///
+/// Options with a value can be given as `--option=value` or `--option value`.
+fn baseline_bench_args(bench_args: &[String]) -> Vec<String> {
+    const VALUE_OPTIONS: [&str; 2] = ["--regression", "--baseline-commit"];
+    const FLAG_OPTIONS: [&str; 2] = ["--regression-fail-fast", "--save-baseline-commit"];
+
+    let mut result = vec![];
+    let mut iter = bench_args.iter();
+    while let Some(arg) = iter.next() {
+        let name = arg.split_once('=').map_or(arg.as_str(), |(name, _)| name);
+        if VALUE_OPTIONS.contains(&name) {
+            debug!("Ignoring '{name}' in the baseline run");
+            if name == arg {
+                iter.next();
+            }
+        } else if FLAG_OPTIONS.contains(&name) {
+            debug!("Ignoring '{name}' in the baseline run");
+        } else {
+            result.push(arg.clone());
+        }
+    }
+    result
+}
+
+/// Create the `cargo bench` command to run in `dir` with the shared `target_dir`
+///
+/// The baseline options are passed to the `iai-callgrind-runner` as environment variables, so
+/// other benchmark harnesses don't choke on them.
+fn cargo_bench(
+    args: &CompareRevArgs,
+    bench_args: &[String],
+    dir: &Path,
+    target_dir: &Path,
+) -> std::process::Command {
+    let cargo = std::env::var_os(envs::CARGO).unwrap_or_else(|| OsString::from("cargo"));
+    let mut command = std::process::Command::new(cargo);
+    command
+        .arg("bench")
+        .current_dir(dir)
+        .env(envs::CARGO_TARGET_DIR, target_dir);
+    if let Some(package) = &args.package {
+        command.args(["--package", package]);
+    }
+    for bench in &args.benches {
+        command.args(["--bench", bench]);
+    }
+    if !bench_args.is_empty() {
+        command.arg("--").args(bench_args);
+    }
+    command
+}
+
+fn run_cargo_bench(mut command: std::process::Command) -> Result<()> {
+    debug!("Running {command:?}");
+    let status = command
+        .status()
+        .with_context(|| "Failed to run cargo bench")?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("cargo bench failed with {status}"))
+    }
+}
+
+/// Return the target directory of the cargo workspace
+fn cargo_target_dir() -> Result<PathBuf> {
+    if let Some(target_dir) = std::env::var_os(envs::CARGO_TARGET_DIR) {
+        return Ok(PathBuf::from(target_dir));
+    }
+    let meta = cargo_metadata::MetadataCommand::new()
+        .no_deps()
+        .exec()
+        .with_context(|| "Failed to query the metadata of the cargo workspace")?;
+    Ok(meta.target_directory.into_std_path_buf())
+}
+
+/// Return the `iai` directory in the target directory of the cargo workspace
+fn default_target_dir() -> Result<PathBuf> {
+    cargo_target_dir().map(|target_dir| target_dir.join("iai"))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Command {
+        Cli::try_parse_from(std::iter::once("iai-callgrind-runner").chain(args.iter().copied()))
+            .unwrap()
+            .command
+    }
+
+    fn parse_history(args: &[&str]) -> HistoryArgs {
+        match parse(args) {
+            Command::History(args) => args,
+            _ => panic!("Expected the history subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_history_defaults() {
+        let args = parse_history(&["history"]);
+        assert_eq!(args.target_dir, None);
+        assert_eq!(args.last, 10);
+        assert_eq!(args.filter, None);
+        assert_eq!(args.event_kinds, None);
+        assert_eq!(args.charts_dir, None);
+        assert!(!args.no_charts);
+    }
+
+    #[rstest]
+    #[case::single(&["history", "--event-kinds=Ir"], vec![EventKind::Ir])]
+    #[case::case_insensitive(&["history", "--event-kinds=estimatedcycles"], vec![EventKind::EstimatedCycles])]
+    #[case::comma_separated(&["history", "--event-kinds=ir,Dr"], vec![EventKind::Ir, EventKind::Dr])]
+    #[case::with_whitespace(
+        &["history", "--event-kinds", "Ir, L1hits, TotalRW"],
+        vec![EventKind::Ir, EventKind::L1hits, EventKind::TotalRW]
+    )]
+    fn test_history_event_kinds(#[case] args: &[&str], #[case] expected: Vec<EventKind>) {
+        assert_eq!(parse_history(args).event_kinds, Some(expected));
+    }
+
+    #[test]
+    fn test_history_event_kinds_when_unknown_then_error() {
+        assert!(
+            Cli::try_parse_from(["iai-callgrind-runner", "history", "--event-kinds=Ir,Wrong"])
+                .is_err()
+        );
+    }
+
+    #[rstest]
+    #[case::one(&["history", "--last=1"], 1)]
+    #[case::many(&["history", "--last", "20"], 20)]
+    fn test_history_last(#[case] args: &[&str], #[case] expected: usize) {
+        assert_eq!(parse_history(args).last, expected);
+    }
+
+    #[test]
+    fn test_history_last_when_zero_then_error() {
+        assert!(Cli::try_parse_from(["iai-callgrind-runner", "history", "--last=0"]).is_err());
+    }
+
+    #[rstest]
+    #[case::revision(&["compare-rev", "main"], GitRevision::Rev("main".to_owned()), None, &[], &[])]
+    #[case::merge_base(
+        &["compare-rev", "merge-base(main)"],
+        GitRevision::MergeBase("main".to_owned()),
+        None,
+        &[],
+        &[]
+    )]
+    #[case::all(
+        &["compare-rev", "-p", "my-package", "--bench", "a", "--bench", "b", "HEAD~1", "--", "--regression=Ir=5"],
+        GitRevision::Rev("HEAD~1".to_owned()),
+        Some("my-package"),
+        &["a", "b"],
+        &["--regression=Ir=5"]
+    )]
+    fn test_compare_rev(
+        #[case] args: &[&str],
+        #[case] revision: GitRevision,
+        #[case] package: Option<&str>,
+        #[case] benches: &[&str],
+        #[case] bench_args: &[&str],
+    ) {
+        match parse(args) {
+            Command::CompareRev(args) => {
+                assert_eq!(args.revision, revision);
+                assert_eq!(args.package.as_deref(), package);
+                assert_eq!(args.benches, benches);
+                assert_eq!(args.bench_args, bench_args);
+            }
+            _ => panic!("Expected the compare-rev subcommand"),
+        }
+    }
+
+    #[rstest]
+    #[case::files_only(&["compare", "new.out", "old.out"], None, None)]
+    #[case::all(
+        &["compare", "--sentinel=main", "--regression=Ir=5", "new.out", "old.out"],
+        Some("main"),
+        Some(RegressionConfig { limits: vec![(EventKind::Ir, 5f64)], fail_fast: None })
+    )]
+    fn test_compare(
+        #[case] args: &[&str],
+        #[case] sentinel: Option<&str>,
+        #[case] regression: Option<RegressionConfig>,
+    ) {
+        match parse(args) {
+            Command::Compare(args) => {
+                assert_eq!(args.new, PathBuf::from("new.out"));
+                assert_eq!(args.old, PathBuf::from("old.out"));
+                assert_eq!(args.sentinel.as_deref(), sentinel);
+                assert_eq!(args.regression, regression);
+            }
+            _ => panic!("Expected the compare subcommand"),
+        }
+    }
+
+    #[rstest]
+    #[case::file_only(&["flamegraph", "callgrind.out.1"], None, None)]
+    #[case::all(
+        &["flamegraph", "--old=callgrind.out.2", "--event-kind=ir,Dr", "--sentinel=main", "callgrind.out.1"],
+        Some("callgrind.out.2"),
+        Some(vec![EventKind::Ir, EventKind::Dr])
+    )]
+    fn test_flamegraph(
+        #[case] args: &[&str],
+        #[case] old: Option<&str>,
+        #[case] event_kinds: Option<Vec<EventKind>>,
+    ) {
+        let cli = Cli::try_parse_from(
+            std::iter::once("iai-callgrind-runner").chain(args.iter().copied()),
+        )
+        .unwrap();
+        match cli.command {
+            Command::Flamegraph(args) => {
+                assert_eq!(args.path, PathBuf::from("callgrind.out.1"));
+                assert_eq!(args.old, old.map(PathBuf::from));
+                assert_eq!(args.event_kinds, event_kinds);
+            }
+            _ => panic!("Expected the flamegraph subcommand"),
+        }
+    }
+
+    #[rstest]
+    #[case::empty(&[], &[])]
+    #[case::other(&["--html-report", "--save-history"], &["--html-report", "--save-history"])]
+    #[case::regression_with_equals(&["--regression=Ir=5", "--html-report"], &["--html-report"])]
+    #[case::regression_separate(&["--regression", "Ir=5", "--html-report"], &["--html-report"])]
+    #[case::fail_fast(&["--regression-fail-fast", "--regression-fail-fast=yes"], &[])]
+    #[case::baselines(
+        &["--save-baseline-commit", "--baseline-commit", "main", "--baseline-commit=HEAD~1"],
+        &[]
+    )]
+    fn test_baseline_bench_args(#[case] args: &[&str], #[case] expected: &[&str]) {
+        let args = args.iter().map(|arg| (*arg).to_owned()).collect::<Vec<_>>();
+        assert_eq!(baseline_bench_args(&args), expected);
+    }
+
+    #[rstest]
+    #[case::history("history", true)]
+    #[case::compare_rev("compare-rev", true)]
+    #[case::compare("compare", true)]
+    #[case::flamegraph("flamegraph", true)]
+    #[case::benchmark_argument("--save-history", false)]
+    #[case::benchmark_kind("--lib-bench", false)]
+    fn test_is_subcommand(#[case] arg: &str, #[case] expected: bool) {
+        assert_eq!(is_subcommand(&OsString::from(arg)), expected);
+    }
+}
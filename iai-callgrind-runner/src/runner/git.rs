@@ -0,0 +1,289 @@
+//! Baselines of the valgrind tool outputs stored per git commit and branch
+//!
+//! With `--save-baseline-commit`, the callgrind output of each benchmark and the output and log
+//! files of the other valgrind tools like DHAT or Memcheck are copied into the [`DIR_NAME`]
+//! directory of the output directory of the benchmark below `commits/<hash>` and if on a branch
+//! also below `branches/<branch>`. With `--baseline-commit=<rev>`, the stored output of the
+//! revision replaces the `*.old` output, so all comparisons are against this baseline instead of
+//! the output of the previous benchmark run. The old flamegraphs are created from the `*.old`
+//! callgrind output, so they show the baseline, too.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+
+use super::args::CommandLineArgs;
+use super::tool::ToolOutputPath;
+
+/// The name of the directory with the baselines in the output directory of a benchmark
+pub const DIR_NAME: &str = "baselines";
+
+/// A git revision specified with `--baseline-commit`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitRevision {
+    /// Any revision understood by `git rev-parse` like a branch, a tag or a commit hash
+    Rev(String),
+    /// The merge base of `HEAD` and the revision specified as `merge-base(<rev>)`
+    MergeBase(String),
+}
+
+/// The baselines to save and the baseline to compare against
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitBaselines {
+    /// The directories relative to the [`DIR_NAME`] directory in which the new output is saved
+    pub save: Vec<PathBuf>,
+    /// The baseline which replaces the `*.old` output if present
+    pub compare: Option<GitBaseline>,
+}
+
+/// A stored baseline of a [`GitRevision`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitBaseline {
+    /// The revision as specified on the command line
+    pub revision: String,
+    /// The directories relative to the [`DIR_NAME`] directory in the order of preference
+    pub dirs: Vec<PathBuf>,
+}
+
+/// A temporary git worktree which is removed when dropped
+#[derive(Debug)]
+pub struct Worktree {
+    /// The directory of the repository to which the worktree belongs
+    pub repository: PathBuf,
+    /// The directory of the worktree
+    pub path: PathBuf,
+}
+
+impl GitRevision {
+    /// Parse the `value` of `--baseline-commit`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let revision = if let Some(rev) = value
+            .strip_prefix("merge-base(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            let rev = rev.trim();
+            if rev.is_empty() {
+                return Err(format!(
+                    "Invalid baseline commit: '{value}': Expected merge-base(<rev>)"
+                ));
+            }
+            Self::MergeBase(rev.to_owned())
+        } else if value.is_empty() {
+            return Err("Invalid baseline commit: The revision must not be empty".to_owned());
+        } else {
+            Self::Rev(value.to_owned())
+        };
+        Ok(revision)
+    }
+
+    /// Resolve this revision to the hash of a commit in the repository in `dir`
+    pub fn resolve(&self, dir: &Path) -> Result<String> {
+        match self {
+            Self::Rev(rev) => git(
+                dir,
+                &["rev-parse", "--verify", &format!("{rev}^{{commit}}")],
+            ),
+            Self::MergeBase(rev) => git(dir, &["merge-base", "HEAD", rev]),
+        }
+        .with_context(|| format!("Failed to resolve the baseline commit '{self}'"))
+    }
+}
+
+impl Display for GitRevision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rev(rev) => f.write_str(rev),
+            Self::MergeBase(rev) => write!(f, "merge-base({rev})"),
+        }
+    }
+}
+
+impl GitBaselines {
+    /// Create the `GitBaselines` from the command line `args` for the repository in `dir`
+    ///
+    /// The git commands are only run if `--save-baseline-commit` or `--baseline-commit` are
+    /// present.
+    pub fn new(args: &CommandLineArgs, dir: &Path) -> Result<Self> {
+        let mut save = vec![];
+        if args.save_baseline_commit.unwrap_or_default() {
+            let commit = head_commit(dir).ok_or_else(|| {
+                anyhow!(
+                    "Failed to save the baseline commit: Could not determine the git commit of \
+                     '{}'",
+                    dir.display()
+                )
+            })?;
+            save.push(commit_dir(&commit));
+            if let Some(branch) = current_branch(dir) {
+                save.push(branch_dir(&branch));
+            }
+            debug!("Saving the baselines in: {save:?}");
+        }
+
+        let compare = args
+            .baseline_commit
+            .as_ref()
+            .map(|revision| -> Result<GitBaseline> {
+                let mut dirs = vec![commit_dir(&revision.resolve(dir)?)];
+                if let GitRevision::Rev(rev) = revision {
+                    dirs.push(branch_dir(rev));
+                }
+                debug!("Comparing against the baselines of '{revision}' in: {dirs:?}");
+                Ok(GitBaseline {
+                    revision: revision.to_string(),
+                    dirs,
+                })
+            })
+            .transpose()?;
+
+        Ok(Self { save, compare })
+    }
+
+    /// Replace the `*.old` output of `output_path` with the stored baseline if requested
+    ///
+    /// If there is no stored baseline, the `*.old` output is removed with a warning, so there is
+    /// nothing to compare against.
+    pub fn load(&self, output_path: &ToolOutputPath) -> Result<()> {
+        if let Some(compare) = &self.compare {
+            let found = compare
+                .dirs
+                .iter()
+                .find(|dir| output_path.to_baseline_output(dir).exists());
+            if let Some(dir) = found {
+                output_path.load_baseline(dir)?;
+            } else {
+                output_path.remove_old()?;
+                warn!(
+                    "No baseline of '{}' found for '{}'. Use --save-baseline-commit on this \
+                     revision first.",
+                    compare.revision, output_path
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the output of `output_path` in all baseline directories if requested
+    pub fn save(&self, output_path: &ToolOutputPath) -> Result<()> {
+        for dir in &self.save {
+            output_path.save_baseline(dir)?;
+        }
+        Ok(())
+    }
+}
+
+impl Worktree {
+    /// Add a worktree at `path` with the detached `commit` to the repository in `dir`
+    ///
+    /// Registered worktrees whose directory doesn't exist anymore, for example because an
+    /// interrupted run left them behind, are pruned first. A stale worktree at `path` is removed.
+    pub fn add(dir: &Path, path: &Path, commit: &str) -> Result<Self> {
+        git(dir, &["worktree", "prune"]).ok();
+        if path.exists() {
+            debug!("Removing stale worktree '{}'", path.display());
+            git(
+                dir,
+                &["worktree", "remove", "--force", &path.to_string_lossy()],
+            )
+            .ok();
+            if path.exists() {
+                std::fs::remove_dir_all(path)
+                    .with_context(|| format!("Failed removing '{}'", path.display()))?;
+            }
+            git(dir, &["worktree", "prune"]).ok();
+        }
+
+        debug!("Adding worktree '{}' of commit '{commit}'", path.display());
+        git(
+            dir,
+            &[
+                "worktree",
+                "add",
+                "--quiet",
+                "--detach",
+                &path.to_string_lossy(),
+                commit,
+            ],
+        )
+        .with_context(|| format!("Failed to add a git worktree at '{}'", path.display()))?;
+        Ok(Self {
+            repository: dir.to_owned(),
+            path: path.to_owned(),
+        })
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        debug!("Removing worktree '{}'", self.path.display());
+        if let Err(error) = git(
+            &self.repository,
+            &[
+                "worktree",
+                "remove",
+                "--force",
+                &self.path.to_string_lossy(),
+            ],
+        ) {
+            warn!(
+                "Failed to remove the git worktree '{}': {error}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Return the hash of the current git commit of the repository in `dir` if available
+pub fn head_commit(dir: &Path) -> Option<String> {
+    git(dir, &["rev-parse", "HEAD"])
+        .ok()
+        .filter(|commit| !commit.is_empty())
+}
+
+/// Return the name of the current git branch of the repository in `dir` if on a branch
+pub fn current_branch(dir: &Path) -> Option<String> {
+    git(dir, &["symbolic-ref", "--short", "-q", "HEAD"])
+        .ok()
+        .filter(|branch| !branch.is_empty())
+}
+
+/// Return the top-level directory of the working tree of the repository in `dir`
+pub fn toplevel(dir: &Path) -> Result<PathBuf> {
+    git(dir, &["rev-parse", "--show-toplevel"]).map(PathBuf::from)
+}
+
+/// Return the directory of the baseline of a `commit` relative to the [`DIR_NAME`] directory
+pub fn commit_dir(commit: &str) -> PathBuf {
+    Path::new("commits").join(commit)
+}
+
+/// Return the directory of the baseline of a `branch` relative to the [`DIR_NAME`] directory
+///
+/// Branch names with a `/` like `feature/name` are stored in subdirectories.
+pub fn branch_dir(branch: &str) -> PathBuf {
+    let mut path = PathBuf::from("branches");
+    path.extend(branch.split('/').filter(|segment| !segment.is_empty()));
+    path
+}
+
+/// Run git with the `args` in the repository in `dir` and return the trimmed stdout
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .with_context(|| "Failed to run git")?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    } else {
+        Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::debug;
+
+use super::format_timestamp;
+use super::trend::Trend;
+use crate::util::escape_xml;
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 320.0;
+const MARGIN_LEFT: f64 = 90.0;
+const MARGIN_RIGHT: f64 = 30.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 40.0;
+
+/// A simple svg line chart of a [`Trend`]
+///
+/// The x-axis are the runs in chronological order with the same distance between two runs and the
+/// y-axis are the costs between the minimum and the maximum costs. Hovering over a point shows the
+/// time, the git commit and the costs of the run.
+pub struct LineChart<'a, 'b> {
+    trend: &'b Trend<'a>,
+}
+
+impl<'a, 'b> LineChart<'a, 'b> {
+    pub fn new(trend: &'b Trend<'a>) -> Self {
+        Self { trend }
+    }
+
+    /// Save the chart in the directory `dir` and return the path of the svg file
+    ///
+    /// The file name is the title of the benchmark with all characters other than alphanumeric
+    /// characters, `-` and `.` replaced by `_` followed by the event kind.
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed creating directory '{}'", dir.display()))?;
+        let path = dir.join(self.file_name());
+        debug!("Creating history chart '{}'", path.display());
+        std::fs::write(&path, self.create())
+            .with_context(|| format!("Failed writing history chart '{}'", path.display()))?;
+        Ok(path)
+    }
+
+    /// Return the file name of the svg file
+    pub fn file_name(&self) -> String {
+        let name = self
+            .trend
+            .title
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        format!("{name}.{}.svg", self.trend.event_kind)
+    }
+
+    /// Create the svg document
+    #[allow(clippy::cast_precision_loss, clippy::too_many_lines)]
+    pub fn create(&self) -> String {
+        let points = &self.trend.points;
+        let min = points.iter().map(|(_, cost)| *cost).min().unwrap_or(0);
+        let max = points.iter().map(|(_, cost)| *cost).max().unwrap_or(0);
+        let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+        let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+        let bottom = MARGIN_TOP + plot_height;
+
+        let x = |index: usize| {
+            if points.len() > 1 {
+                MARGIN_LEFT + index as f64 * plot_width / (points.len() - 1) as f64
+            } else {
+                MARGIN_LEFT + plot_width / 2f64
+            }
+        };
+        let y = |cost: u64| {
+            if max > min {
+                bottom - (cost - min) as f64 * plot_height / (max - min) as f64
+            } else {
+                MARGIN_TOP + plot_height / 2f64
+            }
+        };
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" \
+             viewBox=\"0 0 {WIDTH} {HEIGHT}\">"
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<style>text {{ font-family: sans-serif; font-size: 12px; }}</style>"
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"20\" text-anchor=\"middle\">{} ({})</text>",
+            WIDTH / 2f64,
+            escape_xml(&self.trend.title),
+            self.trend.event_kind
+        )
+        .unwrap();
+
+        // The axes with the labels of the minimum and maximum costs and the first and last run
+        writeln!(
+            svg,
+            "<polyline fill=\"none\" stroke=\"#888\" points=\"{MARGIN_LEFT:.1},{MARGIN_TOP:.1} \
+             {MARGIN_LEFT:.1},{bottom:.1} {:.1},{bottom:.1}\"/>",
+            WIDTH - MARGIN_RIGHT
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{max}</text>",
+            MARGIN_LEFT - 5f64,
+            y(max) + 4f64
+        )
+        .unwrap();
+        if max > min {
+            writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{min}</text>",
+                MARGIN_LEFT - 5f64,
+                y(min) + 4f64
+            )
+            .unwrap();
+        }
+        if let (Some((first, _)), Some((last, _))) = (points.first(), points.last()) {
+            writeln!(
+                svg,
+                "<text x=\"{MARGIN_LEFT:.1}\" y=\"{:.1}\" text-anchor=\"start\">{}</text>",
+                bottom + 20f64,
+                format_timestamp(first.timestamp)
+            )
+            .unwrap();
+            if points.len() > 1 {
+                writeln!(
+                    svg,
+                    "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+                    WIDTH - MARGIN_RIGHT,
+                    bottom + 20f64,
+                    format_timestamp(last.timestamp)
+                )
+                .unwrap();
+            }
+        }
+
+        let line = points
+            .iter()
+            .enumerate()
+            .map(|(index, (_, cost))| format!("{:.1},{:.1}", x(index), y(*cost)))
+            .collect::<Vec<String>>()
+            .join(" ");
+        writeln!(
+            svg,
+            "<polyline fill=\"none\" stroke=\"#1f77b4\" stroke-width=\"2\" points=\"{line}\"/>"
+        )
+        .unwrap();
+        for (index, (entry, cost)) in points.iter().enumerate() {
+            writeln!(
+                svg,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"#1f77b4\"><title>{} {}: \
+                 {cost}</title></circle>",
+                x(index),
+                y(*cost),
+                format_timestamp(entry.timestamp),
+                entry.git_commit.as_deref().unwrap_or("unknown commit")
+            )
+            .unwrap();
+        }
+        writeln!(svg, "</svg>").unwrap();
+        svg
+    }
+}
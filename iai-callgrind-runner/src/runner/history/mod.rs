@@ -0,0 +1,238 @@
+//! The local history of the costs of all benchmark runs
+//!
+//! With `--save-history`, the costs of each benchmark are appended to a json lines file per
+//! benchmark file in the [`DIR_NAME`] directory of the target directory. Each line is a
+//! [`HistoryEntry`] with the time of the benchmark run, the git commit and the [`Fingerprint`] of
+//! the environment. The history is never truncated, so the trends of the benchmarks can be shown
+//! over any number of benchmark runs.
+
+pub mod chart;
+pub mod trend;
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use glob::glob;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use super::git::head_commit;
+use super::meta::Metadata;
+use super::summary::{to_millis, BenchmarkSummary, CostsSummary};
+
+/// The version of the format of a [`HistoryEntry`]
+pub const VERSION: &str = "1";
+
+/// The name of the directory with the history files in the target directory
+pub const DIR_NAME: &str = "history";
+
+/// The environment in which a benchmark was run
+///
+/// The costs of benchmark runs in different environments are usually not comparable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// The architecture like `x86_64`
+    pub arch: String,
+    /// The operating system like `linux`
+    pub os: String,
+    /// The version of the `iai-callgrind-runner`
+    pub runner_version: String,
+    /// The output of `valgrind --version` if available
+    pub valgrind_version: Option<String>,
+}
+
+/// The costs of a single run of a benchmark
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// The version of this format
+    pub version: String,
+    /// The time of the benchmark run in milliseconds since the unix epoch
+    pub timestamp: u64,
+    /// The git commit of the project at the time of the benchmark run if available
+    pub git_commit: Option<String>,
+    /// The environment of the benchmark run
+    pub fingerprint: Fingerprint,
+    /// The rust path in the form `bench_file::group::bench`
+    pub module_path: String,
+    /// The user provided id of the benchmark
+    pub id: Option<String>,
+    /// The costs of the first callgrind run of the benchmark
+    pub costs: CostsSummary,
+}
+
+/// The history files of all benchmark files stored in a directory
+pub struct History {
+    dir: PathBuf,
+}
+
+impl Fingerprint {
+    /// Create the `Fingerprint` of the current environment
+    pub fn new(meta: &Metadata) -> Self {
+        let valgrind_version = Command::new(&meta.valgrind.bin)
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned());
+        Self {
+            arch: meta.arch.clone(),
+            os: std::env::consts::OS.to_owned(),
+            runner_version: env!("CARGO_PKG_VERSION").to_owned(),
+            valgrind_version,
+        }
+    }
+}
+
+impl HistoryEntry {
+    /// Create a new `HistoryEntry` from the `summary` of a benchmark
+    ///
+    /// Returns `None` if there are no costs, for example because the benchmark could not be run
+    /// successfully.
+    pub fn new(
+        summary: &BenchmarkSummary,
+        timestamp: SystemTime,
+        git_commit: Option<String>,
+        fingerprint: Fingerprint,
+    ) -> Option<Self> {
+        let run = summary.callgrind_summary.as_ref()?.summaries.first()?;
+        Some(Self {
+            version: VERSION.to_owned(),
+            timestamp: to_millis(timestamp),
+            git_commit,
+            fingerprint,
+            module_path: summary.module_path.clone(),
+            id: summary.id.clone(),
+            costs: run.events.clone(),
+        })
+    }
+
+    /// Return the title of the benchmark consisting of the module path and the id
+    pub fn title(&self) -> String {
+        match &self.id {
+            Some(id) => format!("{} {id}", self.module_path),
+            None => self.module_path.clone(),
+        }
+    }
+}
+
+impl History {
+    pub fn new<T>(dir: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self { dir: dir.into() }
+    }
+
+    /// Append the `entries` to the history file of the benchmark file `module`
+    pub fn append(&self, module: &str, entries: &[HistoryEntry]) -> Result<()> {
+        let path = self
+            .dir
+            .join(module.split("::").collect::<PathBuf>())
+            .with_extension("jsonl");
+        debug!("Appending to history file '{}'", path.display());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed creating directory '{}'", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed opening history file '{}'", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        for entry in entries {
+            serde_json::to_writer(&mut writer, entry)
+                .with_context(|| format!("Failed writing history file '{}'", path.display()))?;
+            writeln!(writer)
+                .with_context(|| format!("Failed writing history file '{}'", path.display()))?;
+        }
+        writer
+            .flush()
+            .with_context(|| format!("Failed writing history file '{}'", path.display()))
+    }
+
+    /// Load the entries of all history files in the directory and its subdirectories
+    ///
+    /// The entries are sorted by their timestamp. Lines which can't be read, for example because
+    /// they were written by an incompatible version, are skipped with a warning.
+    pub fn load(&self) -> Result<Vec<HistoryEntry>> {
+        let pattern = self.dir.join("**").join("*.jsonl");
+        let mut paths = glob(&pattern.to_string_lossy())
+            .with_context(|| format!("Invalid glob pattern '{}'", pattern.display()))?
+            .filter_map(Result::ok)
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+
+        let mut entries = vec![];
+        for path in paths {
+            let file = File::open(&path)
+                .with_context(|| format!("Failed opening history file '{}'", path.display()))?;
+            for (index, line) in BufReader::new(file).lines().enumerate() {
+                let line = line
+                    .with_context(|| format!("Failed reading history file '{}'", path.display()))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str(&line) {
+                    Ok(entry) => entries.push(entry),
+                    Err(error) => warn!(
+                        "Skipping line {} of history file '{}': {error}",
+                        index + 1,
+                        path.display()
+                    ),
+                }
+            }
+        }
+        entries.sort_by_key(|entry: &HistoryEntry| entry.timestamp);
+        Ok(entries)
+    }
+}
+
+/// Append the costs of the `summaries` of the benchmark file `module` to the history
+///
+/// Nothing is done if the history wasn't requested with `--save-history`.
+pub(crate) fn save(meta: &Metadata, module: &str, summaries: &[BenchmarkSummary]) -> Result<()> {
+    if !meta.args.save_history.unwrap_or_default() {
+        return Ok(());
+    }
+
+    let timestamp = SystemTime::now();
+    let git_commit = head_commit(&meta.project_root);
+    let fingerprint = Fingerprint::new(meta);
+    let entries = summaries
+        .iter()
+        .filter_map(|summary| {
+            HistoryEntry::new(summary, timestamp, git_commit.clone(), fingerprint.clone())
+        })
+        .collect::<Vec<HistoryEntry>>();
+    History::new(meta.target_dir.join(DIR_NAME)).append(module, &entries)
+}
+
+/// Format the `millis` since the unix epoch as `YYYY-MM-DD HH:MM:SS` in UTC
+pub fn format_timestamp(millis: u64) -> String {
+    let secs = millis / 1000;
+    let days = i64::try_from(secs / 86_400).unwrap_or(i64::MAX);
+    let secs_of_day = secs % 86_400;
+
+    // The conversion of days since the epoch to the civil date by Howard Hinnant
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
@@ -0,0 +1,127 @@
+use std::fmt::Write;
+
+use indexmap::IndexMap;
+
+use super::{format_timestamp, HistoryEntry};
+use crate::api::EventKind;
+use crate::util::{percentage_diff, to_string_signed_short};
+
+/// The costs of an [`EventKind`] of a single benchmark over its last runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trend<'a> {
+    /// The title of the benchmark consisting of the module path and the id
+    pub title: String,
+    /// The [`EventKind`] of the costs
+    pub event_kind: EventKind,
+    /// The [`HistoryEntry`] and the new costs of each run in chronological order
+    pub points: Vec<(&'a HistoryEntry, u64)>,
+}
+
+impl<'a> Trend<'a> {
+    /// Create the trends of the `event_kinds` of all benchmarks in the history `entries`
+    ///
+    /// The `entries` have to be sorted by their timestamp. Only the `last` runs of each benchmark
+    /// are included and if present only benchmarks with a title containing the `filter`. The
+    /// trends are in the order of the first appearance of the benchmark in the `entries` and then
+    /// in the order of the `event_kinds`. Event kinds without any costs are left out, so there are
+    /// no trends at all if `last` is `0`.
+    pub fn from_entries(
+        entries: &'a [HistoryEntry],
+        event_kinds: &[EventKind],
+        last: usize,
+        filter: Option<&str>,
+    ) -> Vec<Self> {
+        let mut benchmarks: IndexMap<String, Vec<&HistoryEntry>> = IndexMap::new();
+        for entry in entries {
+            let title = entry.title();
+            if filter.map_or(true, |filter| title.contains(filter)) {
+                benchmarks.entry(title).or_default().push(entry);
+            }
+        }
+
+        let mut trends = vec![];
+        for (title, entries) in benchmarks {
+            for event_kind in event_kinds {
+                let points = entries
+                    .iter()
+                    .filter_map(|entry| {
+                        entry
+                            .costs
+                            .diff_by_kind(event_kind)
+                            .and_then(|diff| diff.new)
+                            .map(|cost| (*entry, cost))
+                    })
+                    .collect::<Vec<(&HistoryEntry, u64)>>();
+                let skip = points.len().saturating_sub(last);
+                if skip == points.len() {
+                    continue;
+                }
+                trends.push(Trend {
+                    title: title.clone(),
+                    event_kind: *event_kind,
+                    points: points[skip..].to_vec(),
+                });
+            }
+        }
+        trends
+    }
+
+    /// Format this trend with a summary line and a line per run
+    pub fn format(&self) -> String {
+        let mut output = String::new();
+        let costs = self.points.iter().map(|(_, cost)| *cost);
+        let (first, last) = (self.points[0].1, self.points[self.points.len() - 1].1);
+        writeln!(
+            output,
+            "  {}: {} runs, {first} -> {last} ({}%), min: {}, max: {}",
+            self.event_kind,
+            self.points.len(),
+            to_string_signed_short(percentage_diff(last, first)),
+            costs.clone().min().unwrap_or_default(),
+            costs.max().unwrap_or_default(),
+        )
+        .unwrap();
+
+        let mut previous = None;
+        for (entry, cost) in &self.points {
+            let commit = entry
+                .git_commit
+                .as_deref()
+                .map_or("-------", |commit| commit.get(..7).unwrap_or(commit));
+            write!(
+                output,
+                "    {} {commit:<7} {cost:>15}",
+                format_timestamp(entry.timestamp)
+            )
+            .unwrap();
+            if let Some(previous) = previous {
+                write!(
+                    output,
+                    " ({}%)",
+                    to_string_signed_short(percentage_diff(*cost, previous))
+                )
+                .unwrap();
+            }
+            writeln!(output).unwrap();
+            previous = Some(*cost);
+        }
+        output
+    }
+}
+
+/// Format the `trends` grouped by the benchmark
+pub fn format_trends(trends: &[Trend]) -> String {
+    let mut output = String::new();
+    let mut title = None;
+    for trend in trends {
+        if title != Some(&trend.title) {
+            if title.is_some() {
+                writeln!(output).unwrap();
+            }
+            writeln!(output, "{}", trend.title).unwrap();
+            title = Some(&trend.title);
+        }
+        output.push_str(&trend.format());
+    }
+    output
+}
@@ -1,18 +1,20 @@
 use std::ffi::OsString;
-use std::io::stdout;
+use std::io::{stderr, stdout};
 
 use anyhow::Result;
 
+use super::annotations::{AnnotationsFormat, GithubAnnotations};
+use super::callgrind::analysis::Config as AnalysisConfig;
 use super::callgrind::args::Args;
-use super::callgrind::flamegraph::{Config as FlamegraphConfig, Flamegraph};
 use super::callgrind::parser::{Parser, Sentinel};
 use super::callgrind::sentinel_parser::SentinelParser;
-use super::callgrind::{CallgrindCommand, Regression};
+use super::callgrind::CallgrindCommand;
+use super::message::{Message, MessageFormat};
 use super::meta::Metadata;
 use super::print::{Formatter, Header, VerticalFormat};
 use super::tool::{RunOptions, ToolConfigs};
-use super::{Config, Error};
-use crate::api::{self, LibraryBenchmark};
+use super::{history, report, Config, Error};
+use crate::api::LibraryBenchmark;
 use crate::runner::print::tool_summary_header;
 use crate::runner::summary::{
     BenchmarkKind, BenchmarkSummary, CallgrindSummary, CostsSummary, SummaryOutput,
@@ -41,8 +43,7 @@ struct LibBench {
     args: Option<String>,
     options: RunOptions,
     callgrind_args: Args,
-    flamegraph: Option<FlamegraphConfig>,
-    regression: Option<Regression>,
+    analysis: AnalysisConfig,
     tools: ToolConfigs,
 }
 
@@ -60,7 +61,6 @@ impl Groups {
     ) -> Result<Self> {
         let global_config = benchmark.config;
         let mut groups = vec![];
-        let meta_callgrind_args = meta.args.callgrind_args.clone().unwrap_or_default();
 
         for library_benchmark_group in benchmark.groups {
             let module_path = if let Some(group_id) = &library_benchmark_group.id {
@@ -79,15 +79,25 @@ impl Groups {
                 for (index, library_benchmark_bench) in
                     library_benchmark_benches.benches.into_iter().enumerate()
                 {
-                    let config = global_config.clone().update_from_all([
-                        library_benchmark_group.config.as_ref(),
-                        library_benchmark_benches.config.as_ref(),
-                        library_benchmark_bench.config.as_ref(),
-                    ]);
+                    let bench_path = format!("{}::{}", group.module, library_benchmark_bench.bench);
+                    let config = meta
+                        .project_config
+                        .library_benchmark_config(&bench_path)
+                        .update_from_all([
+                            Some(&global_config),
+                            library_benchmark_group.config.as_ref(),
+                            library_benchmark_benches.config.as_ref(),
+                            library_benchmark_bench.config.as_ref(),
+                        ]);
                     let envs = config.resolve_envs();
-                    let callgrind_args =
-                        Args::from_raw_args(&[&config.raw_callgrind_args, &meta_callgrind_args])?;
-                    let flamegraph = config.flamegraph.map(Into::into);
+                    let analysis = AnalysisConfig::new(&config, &bench_path, meta);
+                    let mut callgrind_args = Args::from_raw_args(&[
+                        &config.raw_callgrind_args,
+                        &meta.args.callgrind_args(&bench_path),
+                    ])?;
+                    if analysis.hot_instructions.is_some() {
+                        callgrind_args.set_dump_instr(true);
+                    }
                     let lib_bench = LibBench {
                         bench_index,
                         index,
@@ -103,9 +113,7 @@ impl Groups {
                             ..Default::default()
                         },
                         callgrind_args,
-                        flamegraph,
-                        regression: api::update_option(&config.regression, &meta.regression_config)
-                            .map(Into::into),
+                        analysis,
                         tools: ToolConfigs(config.tools.0.into_iter().map(Into::into).collect()),
                     };
                     group.benches.push(lib_bench);
@@ -117,15 +125,45 @@ impl Groups {
         Ok(Self(groups))
     }
 
-    fn run(&self, config: &Config) -> Result<()> {
-        let mut is_regressed = false;
+    /// Run all benchmarks collecting their [`BenchmarkSummary`] in `summaries`
+    ///
+    /// A benchmark which fails with an error is recorded in the `summaries` before the error is
+    /// returned.
+    fn run_benches(
+        &self,
+        is_regressed: &mut bool,
+        summaries: &mut Vec<BenchmarkSummary>,
+        config: &Config,
+    ) -> Result<()> {
         for group in &self.0 {
             for bench in &group.benches {
-                let summary = bench.run(config, group)?;
+                let summary = match bench.run(config, group) {
+                    Ok(summary) => summary,
+                    Err(error) => {
+                        let summary = bench.create_summary(config, group, None).with_error(&error);
+                        print_messages(config, &summary)?;
+                        summaries.push(summary);
+                        return Err(error);
+                    }
+                };
                 summary.save()?;
-                summary.check_regression(&mut is_regressed)?;
+                print_messages(config, &summary)?;
+                let result = summary.check_regression(is_regressed);
+                summaries.push(summary);
+                result?;
             }
         }
+        Ok(())
+    }
+
+    fn run(&self, config: &Config) -> Result<()> {
+        let mut is_regressed = false;
+        let mut summaries = vec![];
+        let result = self.run_benches(&mut is_regressed, &mut summaries, config);
+
+        history::save(&config.meta, &config.module, &summaries)?;
+        report::create(&config.meta, &config.module, summaries)?;
+        result?;
 
         if is_regressed {
             Err(Error::RegressionError(false).into())
@@ -136,6 +174,26 @@ impl Groups {
 }
 
 impl LibBench {
+    /// Create the [`BenchmarkSummary`] of this benchmark without any results
+    fn create_summary(
+        &self,
+        config: &Config,
+        group: &Group,
+        summary_output: Option<SummaryOutput>,
+    ) -> BenchmarkSummary {
+        BenchmarkSummary::new(
+            BenchmarkKind::LibraryBenchmark,
+            config.meta.project_root.clone(),
+            config.package_dir.clone(),
+            config.bench_file.clone(),
+            config.bench_bin.clone(),
+            &[&group.module, &self.function],
+            self.id.clone(),
+            self.args.clone(),
+            summary_output,
+        )
+    }
+
     #[allow(clippy::too_many_lines)]
     fn run(&self, config: &Config, group: &Group) -> Result<BenchmarkSummary> {
         let callgrind_command = CallgrindCommand::new(&config.meta);
@@ -177,6 +235,8 @@ impl LibBench {
             )
         };
 
+        config.meta.git_baselines.load(&output_path)?;
+
         let log_path = output_path.to_log_output();
         log_path.init();
 
@@ -186,27 +246,22 @@ impl LibBench {
             output
         });
 
-        let mut benchmark_summary = BenchmarkSummary::new(
-            BenchmarkKind::LibraryBenchmark,
-            config.meta.project_root.clone(),
-            config.package_dir.clone(),
-            config.bench_file.clone(),
-            config.bench_bin.clone(),
-            &[&group.module, &self.function],
-            self.id.clone(),
-            self.args.clone(),
-            summary_output,
-        );
+        let mut benchmark_summary = self.create_summary(config, group, summary_output);
 
+        let is_human = config.meta.args.message_format == MessageFormat::Human;
         let header = Header::from_segments(
             [&group.module, &self.function],
             self.id.clone(),
             self.args.clone(),
         );
 
-        header.print();
-        if self.tools.has_tools_enabled() {
-            println!("{}", tool_summary_header(ValgrindTool::Callgrind));
+        if is_human {
+            header.print();
+            if self.tools.has_tools_enabled() {
+                println!("{}", tool_summary_header(ValgrindTool::Callgrind));
+            }
+        } else {
+            header.to_message().print()?;
         }
 
         let mut options = self.options.clone();
@@ -223,6 +278,7 @@ impl LibBench {
         )?;
 
         let new_costs = SentinelParser::new(&sentinel).parse(&output_path)?;
+        config.meta.git_baselines.save(&output_path)?;
 
         let old_output = output_path.to_old_output();
         #[allow(clippy::if_then_some_else_none)]
@@ -233,21 +289,26 @@ impl LibBench {
         };
 
         let costs_summary = CostsSummary::new(&new_costs, old_costs.as_ref());
-        let string = VerticalFormat::default().format(&costs_summary)?;
-        print!("{string}");
+        if is_human {
+            print!("{}", VerticalFormat::default().format(&costs_summary)?);
+        }
 
-        output.dump_log(log::Level::Info);
-        log_path.dump_log(log::Level::Info, &mut stdout())?;
+        let reports = self.analysis.create_reports(
+            &output_path,
+            Some(&sentinel),
+            &config.meta.project_root,
+            &header.to_title(),
+            is_human,
+        )?;
 
-        let (regressions, fail_fast) = if let Some(regression) = &self.regression {
-            (
-                regression.check_and_print(&costs_summary),
-                regression.fail_fast,
-            )
+        output.dump_log(log::Level::Info);
+        if is_human {
+            log_path.dump_log(log::Level::Info, &mut stdout())?;
         } else {
-            (vec![], false)
-        };
+            log_path.dump_log(log::Level::Info, &mut stderr())?;
+        }
 
+        let (regressions, fail_fast) = self.analysis.check_regressions(&costs_summary, is_human);
         let callgrind_summary = benchmark_summary
             .callgrind_summary
             .insert(CallgrindSummary::new(
@@ -255,7 +316,6 @@ impl LibBench {
                 vec![log_path.to_path()],
                 vec![output_path.to_path()],
             ));
-
         callgrind_summary.add_summary(
             &config.bench_bin,
             &args,
@@ -263,15 +323,7 @@ impl LibBench {
             costs_summary,
             regressions,
         );
-
-        if let Some(flamegraph_config) = self.flamegraph.clone() {
-            callgrind_summary.flamegraphs = Flamegraph::new(header.to_title(), flamegraph_config)
-                .create(
-                &output_path,
-                Some(&sentinel),
-                &config.meta.project_root,
-            )?;
-        }
+        reports.store(callgrind_summary);
 
         benchmark_summary.tool_summaries = self.tools.run(
             &config.meta,
@@ -298,6 +350,18 @@ impl Runner {
     }
 }
 
+/// Print the json messages with `--message-format=json` and the annotations of the finished
+/// benchmark
+fn print_messages(config: &Config, summary: &BenchmarkSummary) -> Result<()> {
+    if config.meta.args.message_format == MessageFormat::Json {
+        Message::print_summary(summary)?;
+    }
+    if let Some(AnnotationsFormat::Github) = config.meta.annotations {
+        GithubAnnotations::print(summary);
+    }
+    Ok(())
+}
+
 pub fn run(library_benchmark: LibraryBenchmark, config: Config) -> Result<()> {
     Runner::generate(library_benchmark, config)?.run()
 }
@@ -0,0 +1,116 @@
+use std::io::{stdout, Write};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::summary::{BenchmarkSummary, CallgrindRegressionSummary, CostsSummary, ToolSummary};
+
+/// The format of the output on stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MessageFormat {
+    /// The usual human readable (and colored) output
+    Human,
+    /// Newline delimited json messages
+    Json,
+}
+
+/// A machine readable message printed to stdout with `--message-format=json`
+///
+/// Each message is a json object on a single line. Like the messages of `cargo
+/// --message-format=json`, the kind of the message is stored in the `reason` field.
+#[derive(Debug, Serialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+pub enum Message<'a> {
+    /// The benchmark is about to be run
+    BenchmarkStarted {
+        module_path: &'a str,
+        id: Option<&'a str>,
+        details: Option<&'a str>,
+    },
+    /// The costs of a callgrind run of the benchmark
+    Costs {
+        module_path: &'a str,
+        id: Option<&'a str>,
+        command: &'a str,
+        costs: &'a CostsSummary,
+    },
+    /// A performance regression of a callgrind run of the benchmark
+    Regression {
+        module_path: &'a str,
+        id: Option<&'a str>,
+        command: &'a str,
+        regression: &'a CallgrindRegressionSummary,
+    },
+    /// The summary of a valgrind tool other than callgrind
+    ToolSummary {
+        module_path: &'a str,
+        id: Option<&'a str>,
+        summary: &'a ToolSummary,
+    },
+    /// The benchmark has finished with the complete summary
+    BenchmarkFinished { summary: &'a BenchmarkSummary },
+    /// The benchmark could not be run successfully
+    Error {
+        module_path: &'a str,
+        id: Option<&'a str>,
+        message: &'a str,
+    },
+}
+
+impl<'a> Message<'a> {
+    /// Print the messages of a finished benchmark with the `summary`
+    ///
+    /// These are the `costs` and `regression` messages of each callgrind run, the `tool-summary`
+    /// messages and the final `benchmark-finished` message or an `error` message if the benchmark
+    /// could not be run successfully.
+    pub fn print_summary(summary: &'a BenchmarkSummary) -> Result<()> {
+        let module_path = summary.module_path.as_str();
+        let id = summary.id.as_deref();
+        if let Some(error) = &summary.error {
+            return Message::Error {
+                module_path,
+                id,
+                message: error,
+            }
+            .print();
+        }
+
+        if let Some(callgrind) = &summary.callgrind_summary {
+            for run in &callgrind.summaries {
+                Message::Costs {
+                    module_path,
+                    id,
+                    command: &run.command,
+                    costs: &run.events,
+                }
+                .print()?;
+                for regression in &run.regressions {
+                    Message::Regression {
+                        module_path,
+                        id,
+                        command: &run.command,
+                        regression,
+                    }
+                    .print()?;
+                }
+            }
+        }
+        for tool in &summary.tool_summaries {
+            Message::ToolSummary {
+                module_path,
+                id,
+                summary: tool,
+            }
+            .print()?;
+        }
+        Message::BenchmarkFinished { summary }.print()
+    }
+
+    /// Print this message as json on a single line to stdout
+    pub fn print(&self) -> Result<()> {
+        let stdout = stdout();
+        let mut stdout = stdout.lock();
+        serde_json::to_writer(&mut stdout, self).with_context(|| "Failed to print json message")?;
+        writeln!(stdout).with_context(|| "Failed to print json message")
+    }
+}
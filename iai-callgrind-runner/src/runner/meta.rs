@@ -1,13 +1,17 @@
 use std::ffi::OsString;
 use std::path::PathBuf;
 use std::process::Command;
+use std::time::SystemTime;
 
 use anyhow::Result;
 use clap::Parser;
 use log::debug;
 
+use super::annotations::AnnotationsFormat;
 use super::args::CommandLineArgs;
-use crate::api::RegressionConfig;
+use super::cargo_run::CargoRun;
+use super::git::GitBaselines;
+use super::project_config::ProjectConfig;
 use crate::runner::envs;
 use crate::util::resolve_binary_path;
 
@@ -26,12 +30,18 @@ pub struct Metadata {
     pub target_dir: PathBuf,
     pub valgrind: Cmd,
     pub valgrind_wrapper: Option<Cmd>,
-    pub regression_config: Option<RegressionConfig>,
+    pub annotations: Option<AnnotationsFormat>,
+    pub git_baselines: GitBaselines,
+    pub project_config: ProjectConfig,
     pub args: CommandLineArgs,
+    pub command_line_args: Vec<String>,
+    pub start_time: SystemTime,
+    pub cargo_run: Option<CargoRun>,
 }
 
 impl Metadata {
     pub fn new(raw_command_line_args: &[String]) -> Result<Self> {
+        let start_time = SystemTime::now();
         let args = CommandLineArgs::parse_from(raw_command_line_args);
 
         let arch = std::env::consts::ARCH.to_owned();
@@ -51,6 +61,14 @@ impl Metadata {
 
         debug!("Detected target directory: '{}'", target_dir.display());
 
+        let git_baselines = GitBaselines::new(&args, &project_root)?;
+        let project_config = ProjectConfig::load(
+            std::env::var_os(envs::CARGO_MANIFEST_DIR)
+                .map(PathBuf::from)
+                .as_deref(),
+            &project_root,
+        )?;
+
         // Invoke Valgrind, disabling ASLR if possible because ASLR could noise up the results a bit
         let valgrind_path = resolve_binary_path("valgrind")?;
         let valgrind_wrapper = if args.allow_aslr.unwrap_or_default() {
@@ -107,8 +125,13 @@ impl Metadata {
             },
             valgrind_wrapper,
             project_root,
-            regression_config: Into::<Option<RegressionConfig>>::into(&args),
+            annotations: AnnotationsFormat::resolve(args.annotations, args.message_format),
+            git_baselines,
+            project_config,
             args,
+            command_line_args: raw_command_line_args.to_vec(),
+            start_time,
+            cargo_run: CargoRun::detect(),
         })
     }
 }
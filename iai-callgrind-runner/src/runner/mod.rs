@@ -1,13 +1,22 @@
+pub mod annotations;
 mod args;
 mod bin_bench;
 pub mod callgrind;
+mod cargo_run;
+pub mod cli;
 pub mod dhat;
+pub mod git;
+pub mod history;
 mod lib_bench;
+pub mod message;
 mod meta;
 mod print;
+pub mod project_config;
+pub mod report;
 pub mod summary;
 pub mod tool;
 
+use std::ffi::OsString;
 use std::io::{stdin, Read};
 use std::path::PathBuf;
 
@@ -21,12 +30,21 @@ use crate::runner::summary::BenchmarkKind;
 pub use crate::util::{write_all_to_stderr, write_all_to_stdout};
 
 pub mod envs {
+    pub const IAI_CALLGRIND_BASELINE_COMMIT: &str = "IAI_CALLGRIND_BASELINE_COMMIT";
+    pub const IAI_CALLGRIND_CARGO_PID: &str = "IAI_CALLGRIND_CARGO_PID";
     pub const IAI_CALLGRIND_COLOR: &str = "IAI_CALLGRIND_COLOR";
     pub const IAI_CALLGRIND_LOG: &str = "IAI_CALLGRIND_LOG";
+    pub const IAI_CALLGRIND_REGRESSION: &str = "IAI_CALLGRIND_REGRESSION";
+    pub const IAI_CALLGRIND_REGRESSION_FAIL_FAST: &str = "IAI_CALLGRIND_REGRESSION_FAIL_FAST";
+    pub const IAI_CALLGRIND_SAVE_BASELINE_COMMIT: &str = "IAI_CALLGRIND_SAVE_BASELINE_COMMIT";
 
+    pub const CARGO: &str = "CARGO";
+    pub const CARGO_MANIFEST_DIR: &str = "CARGO_MANIFEST_DIR";
     pub const CARGO_PKG_NAME: &str = "CARGO_PKG_NAME";
     pub const CARGO_TARGET_DIR: &str = "CARGO_TARGET_DIR";
     pub const CARGO_TERM_COLOR: &str = "CARGO_TERM_COLOR";
+
+    pub const GITHUB_ACTIONS: &str = "GITHUB_ACTIONS";
 }
 
 #[derive(Debug)]
@@ -89,7 +107,11 @@ where
 }
 
 pub fn run() -> Result<()> {
-    let mut args_iter = std::env::args_os();
+    let args = std::env::args_os().collect::<Vec<OsString>>();
+    if args.get(1).map_or(false, cli::is_subcommand) {
+        return cli::run(args);
+    }
+    let mut args_iter = args.into_iter();
 
     let runner = PathBuf::from(args_iter.next().unwrap());
     debug!("Runner executable: '{}'", runner.display());
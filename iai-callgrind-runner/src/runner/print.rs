@@ -1,9 +1,13 @@
+use std::cmp::Ordering;
 use std::fmt::{Display, Write};
 
 use anyhow::Result;
 use colored::{ColoredString, Colorize};
 
-use super::summary::CostsSummary;
+use super::message::Message;
+use super::summary::{
+    CostsSummary, FunctionDiffKind, FunctionDiffReport, FunctionSummary, HotFunctionsSummary,
+};
 use super::tool::ValgrindTool;
 use crate::api::EventKind;
 use crate::util::{to_string_signed_short, truncate_str_utf8};
@@ -74,6 +78,15 @@ impl Header {
         println!("{self}");
     }
 
+    /// Return the `benchmark-started` [`Message`] of this header
+    pub fn to_message(&self) -> Message<'_> {
+        Message::BenchmarkStarted {
+            module_path: &self.module_path,
+            id: self.id.as_deref(),
+            details: self.description.as_deref(),
+        }
+    }
+
     pub fn to_title(&self) -> String {
         let mut output = String::new();
         write!(&mut output, "{}", self.module_path).unwrap();
@@ -224,6 +237,126 @@ impl Formatter for VerticalFormat {
     }
 }
 
+/// Format the [`HotFunctionsSummary`] as two tables with the inclusive and exclusive costs
+pub fn format_hot_functions(summary: &HotFunctionsSummary) -> Result<String> {
+    let mut result = String::new();
+
+    for (kind, functions) in [
+        ("inclusive", &summary.inclusive),
+        ("exclusive", &summary.exclusive),
+    ] {
+        if functions.is_empty() {
+            continue;
+        }
+
+        writeln!(
+            result,
+            "  {} {}",
+            format!("Hot functions ({kind}):").bold(),
+            summary.event_kind.to_string().bright_black()
+        )?;
+        for function in functions {
+            write_function_summary(&mut result, function)?;
+        }
+    }
+
+    Ok(result)
+}
+
+fn write_function_summary(result: &mut String, function: &FunctionSummary) -> Result<()> {
+    let not_available = "N/A";
+    let unknown = "*********";
+    let no_change = "No change";
+
+    let diff = &function.costs;
+    let name = &function.function;
+    match (diff.new, diff.old) {
+        (None, Some(old_cost)) => writeln!(
+            result,
+            "    {:>15}|{old_cost:<15} ({:^9}) {name}",
+            not_available.bold(),
+            unknown.bright_black()
+        )?,
+        (Some(new_cost), None) => writeln!(
+            result,
+            "    {:>15}|{not_available:<15} ({:^9}) {name}",
+            new_cost.to_string().bold(),
+            unknown.bright_black()
+        )?,
+        (Some(new_cost), Some(old_cost)) if new_cost == old_cost => writeln!(
+            result,
+            "    {:>15}|{old_cost:<15} ({:^9}) {name}",
+            new_cost.to_string().bold(),
+            no_change.bright_black()
+        )?,
+        (Some(new_cost), Some(old_cost)) => {
+            let pct = diff.diff_pct.expect(
+                "If there are new costs and old costs there should be a difference in percent",
+            );
+            writeln!(
+                result,
+                "    {:>15}|{old_cost:<15} ({:^9}) {name}",
+                new_cost.to_string().bold(),
+                VerticalFormat::format_float(pct, "%"),
+            )?;
+        }
+        (None, None) => {}
+    }
+
+    Ok(())
+}
+
+/// Format the [`FunctionDiffReport`] showing at most `limit` functions for each `EventKind`
+pub fn format_function_diff(report: &FunctionDiffReport, limit: usize) -> Result<String> {
+    let mut result = String::new();
+
+    for section in &report.sections {
+        if section.functions.is_empty() {
+            continue;
+        }
+
+        writeln!(
+            result,
+            "  {} {}",
+            "Function diff:".bold(),
+            section.event_kind.to_string().bright_black()
+        )?;
+        for function in section.functions.iter().take(limit) {
+            let kind = match function.kind {
+                FunctionDiffKind::Appeared => "Appeared".bright_red(),
+                FunctionDiffKind::Disappeared => "Disappeared".bright_green(),
+                FunctionDiffKind::Changed => "Changed".bright_black(),
+            };
+            let delta = function.inclusive.delta();
+            let delta_string = match delta.cmp(&0) {
+                Ordering::Greater => format!("{delta:+}").bright_red().bold(),
+                Ordering::Less => format!("{delta:+}").bright_green().bold(),
+                Ordering::Equal => format!("{delta:+}").bold(),
+            };
+            let pct_string = function.inclusive.diff_pct.map_or_else(
+                || "*********".bright_black(),
+                |pct| VerticalFormat::format_float(pct, "%"),
+            );
+            writeln!(
+                result,
+                "    {kind:<11} {delta_string:>15} ({pct_string:^9}) {}",
+                function.function
+            )?;
+        }
+
+        let remaining = section.functions.len().saturating_sub(limit);
+        if remaining > 0 {
+            writeln!(
+                result,
+                "    {}",
+                format!("... and {remaining} more function(s)").bright_black()
+            )?;
+        }
+    }
+
+    Ok(result)
+}
+
 pub fn tool_summary_header(tool: ValgrindTool) -> String {
     let id = tool.id();
     format!(
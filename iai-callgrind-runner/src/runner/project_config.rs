@@ -0,0 +1,283 @@
+//! The project configuration file `iai-callgrind.toml`
+//!
+//! The configuration file provides the defaults for all benchmarks of a package or workspace. The
+//! `[default]` table applies to all benchmarks and the `[benchmarks."<pattern>"]` tables to all
+//! benchmarks with a module path matching the glob pattern. The configuration in the benchmark
+//! code always has a higher priority than the configuration file.
+//!
+//! ```toml
+//! [default]
+//! callgrind_args = ["--toggle-collect=my_lib::*"]
+//! envs = ["HOME", "RUST_LOG=off"]
+//! regression = { limits = { Ir = 5.0 }, fail_fast = false }
+//! flamegraph = { kind = "All" }
+//!
+//! [benchmarks."parser::*"]
+//! callgrind_args = ["--branch-sim=yes"]
+//! tools = [{ kind = "DHAT" }]
+//! ```
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use indexmap::IndexMap;
+use log::debug;
+use serde::{Deserialize, Deserializer};
+
+use crate::api::{
+    BinaryBenchmarkConfig, EventKind, FlamegraphConfig, LibraryBenchmarkConfig, RawArgs,
+    RegressionConfig, Tool, Tools, ValgrindTool,
+};
+
+/// The name of the configuration file in the package or workspace root directory
+pub const FILE_NAME: &str = "iai-callgrind.toml";
+
+/// A glob pattern matching the module path of a benchmark
+///
+/// The only special characters are `*`, `?` and `[...]` as described in [`glob::Pattern`]. A `*`
+/// also matches the `::` separator. The pattern matches if the whole module path or any suffix of
+/// it starting at a `::` separator matches, so `parser::*` matches `my_bench::parser::bench_json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModulePathPattern(glob::Pattern);
+
+/// The loaded project configuration file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectConfig {
+    /// The path of the configuration file if present
+    pub path: Option<PathBuf>,
+    /// The `[default]` table
+    pub default: FileConfig,
+    /// The `[benchmarks."<pattern>"]` tables in the order of the configuration file
+    pub benchmarks: Vec<(ModulePathPattern, FileConfig)>,
+}
+
+/// The configuration of a table in the configuration file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    /// Additional arguments for callgrind
+    pub callgrind_args: Vec<String>,
+    /// If true, clear the environment variables before running the benchmark
+    pub env_clear: Option<bool>,
+    /// Environment variables as `KEY=VALUE` or `KEY` to pass the variable through
+    pub envs: Vec<String>,
+    /// The regression limits as a table of event kinds and percentages
+    pub regression: Option<FileRegressionConfig>,
+    /// The flamegraph configuration with the same fields as `FlamegraphConfig`
+    pub flamegraph: Option<FlamegraphConfig>,
+    /// Additional valgrind tools
+    pub tools: Vec<FileTool>,
+}
+
+/// The `regression` configuration in the configuration file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileRegressionConfig {
+    /// The limits in percent as in `limits = { Ir = 5.0 }`. The event kinds are case-insensitive.
+    #[serde(deserialize_with = "deserialize_limits")]
+    pub limits: Vec<(EventKind, f64)>,
+    /// If true, stop the benchmarks after the first regression
+    pub fail_fast: Option<bool>,
+}
+
+/// A valgrind tool in the configuration file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileTool {
+    /// The tool like `DHAT` or `Memcheck`
+    pub kind: ValgrindTool,
+    /// If false, disable the tool
+    pub enable: Option<bool>,
+    /// The arguments for the tool
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// If true, show the log output of the tool
+    pub show_log: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawProjectConfig {
+    default: FileConfig,
+    benchmarks: IndexMap<String, FileConfig>,
+}
+
+impl ModulePathPattern {
+    /// Create a new `ModulePathPattern` from a glob `pattern`
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err("Invalid module path pattern: The pattern must not be empty".to_owned());
+        }
+        glob::Pattern::new(pattern)
+            .map(Self)
+            .map_err(|error| format!("Invalid module path pattern: '{pattern}': {error}"))
+    }
+
+    /// Return true if this pattern matches the `module_path`
+    pub fn matches(&self, module_path: &str) -> bool {
+        self.0.matches(module_path)
+            || module_path
+                .match_indices("::")
+                .any(|(index, _)| self.0.matches(&module_path[index + 2..]))
+    }
+
+    /// Return the pattern as string
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl ProjectConfig {
+    /// Load the first configuration file found in the `package_dir` or the `project_root`
+    ///
+    /// If there is no configuration file, the returned `ProjectConfig` is empty.
+    pub fn load(package_dir: Option<&Path>, project_root: &Path) -> Result<Self> {
+        let path = package_dir
+            .into_iter()
+            .chain(std::iter::once(project_root))
+            .map(|dir| dir.join(FILE_NAME))
+            .find(|path| path.is_file());
+
+        if let Some(path) = path {
+            debug!(
+                "Loading the project configuration file '{}'",
+                path.display()
+            );
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read '{}'", path.display()))?;
+            let mut config = Self::from_toml(&content)
+                .with_context(|| format!("Failed to parse '{}'", path.display()))?;
+            config.path = Some(path);
+            Ok(config)
+        } else {
+            debug!("No project configuration file found");
+            Ok(Self::default())
+        }
+    }
+
+    /// Parse the `content` of a configuration file
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let raw: RawProjectConfig =
+            basic_toml::from_str(content).map_err(|error| anyhow!(error))?;
+        let benchmarks = raw
+            .benchmarks
+            .into_iter()
+            .map(|(pattern, config)| {
+                ModulePathPattern::new(&pattern)
+                    .map(|pattern| (pattern, config))
+                    .map_err(|error| anyhow!(error))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            path: None,
+            default: raw.default,
+            benchmarks,
+        })
+    }
+
+    /// Return all configurations which apply to the benchmark with the `module_path`
+    ///
+    /// The `[default]` table comes first followed by the matching `[benchmarks]` tables in the
+    /// order of the configuration file, so later configurations have a higher priority.
+    pub fn matching<'a>(&'a self, module_path: &'a str) -> impl Iterator<Item = &'a FileConfig> {
+        std::iter::once(&self.default).chain(
+            self.benchmarks
+                .iter()
+                .filter(move |(pattern, _)| pattern.matches(module_path))
+                .map(|(_, config)| config),
+        )
+    }
+
+    /// Return the merged [`LibraryBenchmarkConfig`] for the benchmark with the `module_path`
+    pub fn library_benchmark_config(&self, module_path: &str) -> LibraryBenchmarkConfig {
+        let configs = self
+            .matching(module_path)
+            .map(FileConfig::to_library_benchmark_config)
+            .collect::<Vec<_>>();
+        LibraryBenchmarkConfig::default().update_from_all(configs.iter().map(Some))
+    }
+
+    /// Return the merged [`BinaryBenchmarkConfig`] for the benchmark with the `module_path`
+    pub fn binary_benchmark_config(&self, module_path: &str) -> BinaryBenchmarkConfig {
+        let configs = self
+            .matching(module_path)
+            .map(FileConfig::to_binary_benchmark_config)
+            .collect::<Vec<_>>();
+        BinaryBenchmarkConfig::default().update_from_all(configs.iter().map(Some))
+    }
+}
+
+impl FileConfig {
+    /// Convert this configuration into a [`LibraryBenchmarkConfig`]
+    pub fn to_library_benchmark_config(&self) -> LibraryBenchmarkConfig {
+        LibraryBenchmarkConfig {
+            env_clear: self.env_clear,
+            raw_callgrind_args: RawArgs::from_iter(&self.callgrind_args),
+            envs: self.resolve_envs(),
+            flamegraph: self.flamegraph.clone(),
+            regression: self.regression.clone().map(Into::into),
+            tools: self.resolve_tools(),
+            ..Default::default()
+        }
+    }
+
+    /// Convert this configuration into a [`BinaryBenchmarkConfig`]
+    pub fn to_binary_benchmark_config(&self) -> BinaryBenchmarkConfig {
+        BinaryBenchmarkConfig {
+            env_clear: self.env_clear,
+            raw_callgrind_args: RawArgs::from_iter(&self.callgrind_args),
+            envs: self.resolve_envs(),
+            flamegraph: self.flamegraph.clone(),
+            regression: self.regression.clone().map(Into::into),
+            tools: self.resolve_tools(),
+            ..Default::default()
+        }
+    }
+
+    fn resolve_envs(&self) -> Vec<(OsString, Option<OsString>)> {
+        self.envs
+            .iter()
+            .map(|env| match env.split_once('=') {
+                Some((key, value)) => (OsString::from(key), Some(OsString::from(value))),
+                None => (OsString::from(env), None),
+            })
+            .collect()
+    }
+
+    fn resolve_tools(&self) -> Tools {
+        let mut tools = Tools::default();
+        tools.update_all(self.tools.iter().map(|tool| Tool {
+            kind: tool.kind.clone(),
+            enable: tool.enable,
+            raw_args: RawArgs::from_iter(&tool.args),
+            outfile_modifier: None,
+            show_log: tool.show_log,
+        }));
+        tools
+    }
+}
+
+impl From<FileRegressionConfig> for RegressionConfig {
+    fn from(value: FileRegressionConfig) -> Self {
+        Self {
+            limits: value.limits,
+            fail_fast: value.fail_fast,
+        }
+    }
+}
+
+fn deserialize_limits<'de, D>(deserializer: D) -> Result<Vec<(EventKind, f64)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    IndexMap::<String, f64>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, pct)| {
+            EventKind::from_str_ignore_case(&key)
+                .map(|event_kind| (event_kind, pct))
+                .ok_or_else(|| serde::de::Error::custom(format!("Unknown event kind: '{key}'")))
+        })
+        .collect()
+}
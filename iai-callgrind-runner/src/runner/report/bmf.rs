@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use log::debug;
+use serde::Serialize;
+
+use super::title;
+use crate::runner::summary::BenchmarkSummary;
+
+/// A single measurement of a benchmark in the Bencher Metric Format
+#[derive(Debug, Serialize)]
+struct Metric {
+    value: u64,
+}
+
+/// The Bencher Metric Format (BMF) json report of all benchmarks of a benchmark run
+///
+/// The json object maps the name of each benchmark, which is the module path and the id, to its
+/// measures. Each [`EventKind`] of the first callgrind run of a benchmark is a measure with the new
+/// costs as value. Benchmarks without costs, for example because they could not be run
+/// successfully, are not included.
+///
+/// [`EventKind`]: crate::api::EventKind
+pub struct BmfReport;
+
+impl BmfReport {
+    /// Save the BMF json report of the `summaries` to the file at `path`
+    pub fn save(path: &Path, summaries: &[BenchmarkSummary]) -> Result<()> {
+        debug!("Creating bmf report '{}'", path.display());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed creating directory '{}'", parent.display()))?;
+        }
+        std::fs::write(path, Self::create(summaries)?)
+            .with_context(|| format!("Failed writing bmf report '{}'", path.display()))
+    }
+
+    /// Create the BMF json document from the `summaries`
+    pub fn create(summaries: &[BenchmarkSummary]) -> Result<String> {
+        let mut benchmarks: IndexMap<String, IndexMap<String, Metric>> = IndexMap::new();
+        for summary in summaries {
+            let run = match summary
+                .callgrind_summary
+                .as_ref()
+                .and_then(|callgrind| callgrind.summaries.first())
+            {
+                Some(run) => run,
+                None => continue,
+            };
+            let measures = run
+                .events
+                .iter()
+                .filter_map(|(event_kind, diff)| {
+                    diff.new
+                        .map(|value| (event_kind.to_string(), Metric { value }))
+                })
+                .collect::<IndexMap<String, Metric>>();
+            if !measures.is_empty() {
+                benchmarks.insert(title(summary), measures);
+            }
+        }
+
+        let mut json = serde_json::to_string_pretty(&benchmarks)
+            .with_context(|| "Failed to serialize bmf report")?;
+        json.push('\n');
+        Ok(json)
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::debug;
+
+use crate::runner::summary::BenchmarkSummary;
+
+/// The columns of the csv table
+const COLUMNS: [&str; 9] = [
+    "module_path",
+    "id",
+    "details",
+    "event",
+    "new",
+    "old",
+    "diff_pct",
+    "factor",
+    "regressed",
+];
+
+/// A flat table of the costs of the benchmarks with one row per benchmark and [`EventKind`]
+///
+/// The costs are the events of the first callgrind run of a benchmark. Missing values are empty.
+/// The table can be written with a `,` (csv) or tab (tsv) as delimiter.
+///
+/// [`EventKind`]: crate::api::EventKind
+pub struct CsvReport {
+    delimiter: char,
+}
+
+impl CsvReport {
+    /// Create a new `CsvReport` with `,` as delimiter
+    pub fn csv() -> Self {
+        Self { delimiter: ',' }
+    }
+
+    /// Create a new `CsvReport` with a tab as delimiter
+    pub fn tsv() -> Self {
+        Self { delimiter: '\t' }
+    }
+
+    /// Save the table of the `summaries` to the file at `path`
+    pub fn save(&self, path: &Path, summaries: &[BenchmarkSummary]) -> Result<()> {
+        debug!("Creating csv report '{}'", path.display());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed creating directory '{}'", parent.display()))?;
+        }
+        std::fs::write(path, self.create(summaries))
+            .with_context(|| format!("Failed writing csv report '{}'", path.display()))
+    }
+
+    /// Create the table from the `summaries` including the header
+    pub fn create(&self, summaries: &[BenchmarkSummary]) -> String {
+        let mut table = String::new();
+        self.write_row(&mut table, &COLUMNS.map(ToOwned::to_owned));
+        for summary in summaries {
+            let run = match summary
+                .callgrind_summary
+                .as_ref()
+                .and_then(|callgrind| callgrind.summaries.first())
+            {
+                Some(run) => run,
+                None => continue,
+            };
+            for (event_kind, diff) in &run.events {
+                let is_regressed = run
+                    .regressions
+                    .iter()
+                    .any(|regression| regression.event_kind == *event_kind);
+                self.write_row(
+                    &mut table,
+                    &[
+                        summary.module_path.clone(),
+                        summary.id.clone().unwrap_or_default(),
+                        summary.details.clone().unwrap_or_default(),
+                        event_kind.to_string(),
+                        to_string_or_empty(diff.new),
+                        to_string_or_empty(diff.old),
+                        to_string_or_empty(diff.diff_pct),
+                        to_string_or_empty(diff.factor),
+                        is_regressed.to_string(),
+                    ],
+                );
+            }
+        }
+        table
+    }
+
+    fn write_row(&self, table: &mut String, fields: &[String]) {
+        let row = fields
+            .iter()
+            .map(|field| self.escape(field))
+            .collect::<Vec<String>>()
+            .join(&self.delimiter.to_string());
+        writeln!(table, "{row}").unwrap();
+    }
+
+    /// Quote the `field` if it contains the delimiter, a quote or a line break
+    fn escape(&self, field: &str) -> String {
+        if field.contains([self.delimiter, '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_owned()
+        }
+    }
+}
+
+fn to_string_or_empty<T>(value: Option<T>) -> String
+where
+    T: ToString,
+{
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
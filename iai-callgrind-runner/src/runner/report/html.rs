@@ -0,0 +1,364 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::debug;
+
+use super::{is_regressed, title};
+use crate::api::EventKind;
+use crate::runner::summary::{
+    BenchmarkSummary, CallgrindSummary, CostsDiff, CostsSummary, ToolSummary,
+};
+use crate::util::{escape_html, format_cost, to_string_signed_short};
+
+/// The name of the aggregated html report
+pub const REPORT_FILE_NAME: &str = "report.html";
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: right; }
+th { background: #eee; }
+th.sortable { cursor: pointer; }
+td.name, th.name { text-align: left; }
+tr.regressed td { background: #fdd; }
+td.worse { color: #c00; }
+td.better { color: #080; }
+ul.regressions li, p.error { color: #c00; }";
+
+const SCRIPT: &str = "document.querySelectorAll('th.sortable').forEach(function (th) {
+  th.addEventListener('click', function () {
+    var table = th.closest('table');
+    var body = table.tBodies[0];
+    var index = Array.prototype.indexOf.call(th.parentNode.children, th);
+    var ascending = th.dataset.order !== 'asc';
+    th.dataset.order = ascending ? 'asc' : 'desc';
+    var value = function (row) {
+      var cell = row.children[index];
+      return cell.dataset.value !== undefined ? parseFloat(cell.dataset.value) : cell.textContent;
+    };
+    Array.from(body.rows).sort(function (a, b) {
+      var x = value(a), y = value(b);
+      var order = x < y ? -1 : x > y ? 1 : 0;
+      return ascending ? order : -order;
+    }).forEach(function (row) { body.appendChild(row); });
+  });
+});";
+
+/// The aggregated html report of the benchmarks of a `cargo bench` run in the `target_dir`
+///
+/// The index shows the configured [`EventKind`]s of each benchmark. The report contains only the
+/// given summaries, which are the summaries of the current run loaded with
+/// [`ReportData::load`](super::ReportData::load), so it doesn't show benchmarks of previous runs
+/// even if cargo had the same process id.
+pub struct HtmlReport {
+    target_dir: PathBuf,
+    event_kinds: Vec<EventKind>,
+}
+
+impl HtmlReport {
+    pub fn new<T>(target_dir: T, event_kinds: Vec<EventKind>) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self {
+            target_dir: target_dir.into(),
+            event_kinds,
+        }
+    }
+
+    /// Save the html report of the `summaries` in the `target_dir`
+    ///
+    /// Returns the path to the html report.
+    pub fn save(&self, summaries: &[BenchmarkSummary]) -> Result<PathBuf> {
+        let path = self.target_dir.join(REPORT_FILE_NAME);
+        debug!("Creating html report '{}'", path.display());
+        std::fs::write(&path, self.create(summaries))
+            .with_context(|| format!("Failed writing html report '{}'", path.display()))?;
+        Ok(path)
+    }
+
+    /// Create the static html page from the `summaries`
+    ///
+    /// The page starts with a sortable index of all benchmarks followed by the details of each
+    /// benchmark. Links to files in the `target_dir` are relative to the report.
+    pub fn create(&self, summaries: &[BenchmarkSummary]) -> String {
+        let regressed = summaries
+            .iter()
+            .filter(|summary| is_regressed(summary))
+            .count();
+
+        let mut html = String::new();
+        writeln!(html, "<!DOCTYPE html>").unwrap();
+        writeln!(html, "<html lang=\"en\">").unwrap();
+        writeln!(html, "<head>").unwrap();
+        writeln!(html, "<meta charset=\"utf-8\">").unwrap();
+        writeln!(html, "<title>Iai-Callgrind Report</title>").unwrap();
+        writeln!(html, "<style>\n{STYLE}\n</style>").unwrap();
+        writeln!(html, "</head>").unwrap();
+        writeln!(html, "<body>").unwrap();
+        writeln!(html, "<h1>Iai-Callgrind Report</h1>").unwrap();
+        writeln!(
+            html,
+            "<p>{} benchmarks, {regressed} regressed</p>",
+            summaries.len()
+        )
+        .unwrap();
+
+        writeln!(html, "<table id=\"index\">").unwrap();
+        write!(
+            html,
+            "<thead><tr><th class=\"sortable name\">Benchmark</th>"
+        )
+        .unwrap();
+        for event_kind in &self.event_kinds {
+            write!(
+                html,
+                "<th class=\"sortable\">{event_kind}</th><th class=\"sortable\">Change</th>"
+            )
+            .unwrap();
+        }
+        writeln!(html, "<th class=\"sortable\">Regressions</th></tr></thead>").unwrap();
+        writeln!(html, "<tbody>").unwrap();
+        for (index, summary) in summaries.iter().enumerate() {
+            let class = if is_regressed(summary) {
+                " class=\"regressed\""
+            } else {
+                ""
+            };
+            write!(
+                html,
+                "<tr{class}><td class=\"name\"><a href=\"#b{index}\">{}</a></td>",
+                escape_html(&title(summary))
+            )
+            .unwrap();
+            let events = summary
+                .callgrind_summary
+                .as_ref()
+                .and_then(|callgrind| callgrind.summaries.first())
+                .map(|run| &run.events);
+            for event_kind in &self.event_kinds {
+                match events.and_then(|events| events.diff_by_kind(event_kind)) {
+                    Some(diff) => write!(
+                        html,
+                        "<td data-value=\"{}\">{}</td>{}",
+                        diff.new.unwrap_or(0),
+                        format_cost(diff.new),
+                        html_change(diff)
+                    )
+                    .unwrap(),
+                    None => write!(
+                        html,
+                        "<td data-value=\"-1\"></td><td data-value=\"-Infinity\"></td>"
+                    )
+                    .unwrap(),
+                }
+            }
+            let regressions = summary.callgrind_summary.as_ref().map_or(0, |callgrind| {
+                callgrind
+                    .summaries
+                    .iter()
+                    .map(|run| run.regressions.len())
+                    .sum()
+            });
+            writeln!(
+                html,
+                "<td data-value=\"{regressions}\">{regressions}</td></tr>"
+            )
+            .unwrap();
+        }
+        writeln!(html, "</tbody>").unwrap();
+        writeln!(html, "</table>").unwrap();
+
+        for (index, summary) in summaries.iter().enumerate() {
+            self.write_benchmark(&mut html, index, summary);
+        }
+
+        writeln!(html, "<script>\n{SCRIPT}\n</script>").unwrap();
+        writeln!(html, "</body>").unwrap();
+        writeln!(html, "</html>").unwrap();
+        html
+    }
+
+    fn write_benchmark(&self, html: &mut String, index: usize, summary: &BenchmarkSummary) {
+        writeln!(html, "<section id=\"b{index}\">").unwrap();
+        writeln!(html, "<h2>{}</h2>", escape_html(&title(summary))).unwrap();
+        if let Some(details) = &summary.details {
+            writeln!(html, "<p>{}</p>", escape_html(details)).unwrap();
+        }
+        if let Some(error) = &summary.error {
+            writeln!(html, "<p class=\"error\">Error: {}</p>", escape_html(error)).unwrap();
+        }
+
+        if let Some(callgrind) = &summary.callgrind_summary {
+            for run in &callgrind.summaries {
+                writeln!(html, "<p><code>{}</code></p>", escape_html(&run.command)).unwrap();
+                write_costs(html, &run.events);
+                if !run.regressions.is_empty() {
+                    writeln!(html, "<ul class=\"regressions\">").unwrap();
+                    for regression in &run.regressions {
+                        writeln!(
+                            html,
+                            "<li>Performance has regressed: {} ({} -> {}): {}% exceeds limit of \
+                             {}%</li>",
+                            regression.event_kind,
+                            regression.old,
+                            regression.new,
+                            to_string_signed_short(regression.diff_pct),
+                            to_string_signed_short(regression.limit)
+                        )
+                        .unwrap();
+                    }
+                    writeln!(html, "</ul>").unwrap();
+                }
+            }
+            self.write_callgrind_links(html, callgrind);
+        }
+
+        for tool in &summary.tool_summaries {
+            self.write_tool(html, tool);
+        }
+        writeln!(html, "</section>").unwrap();
+    }
+
+    fn write_callgrind_links(&self, html: &mut String, callgrind: &CallgrindSummary) {
+        let mut links = vec![];
+        for flamegraph in &callgrind.flamegraphs {
+            for (name, path) in [
+                ("flamegraph", &flamegraph.regular_path),
+                ("old flamegraph", &flamegraph.old_path),
+                ("differential flamegraph", &flamegraph.diff_path),
+            ] {
+                if let Some(path) = path {
+                    links.push((format!("{} {name}", flamegraph.event_kind), path.as_path()));
+                }
+            }
+        }
+        if let Some(annotate) = &callgrind.annotate {
+            links.push(("annotation".to_owned(), annotate.html_path.as_path()));
+        }
+        if let Some(function_diff) = &callgrind.function_diff {
+            links.push((
+                "function diff".to_owned(),
+                function_diff.markdown_path.as_path(),
+            ));
+        }
+        if let Some(path) = &callgrind.hot_instructions {
+            links.push(("hot instructions".to_owned(), path.as_path()));
+        }
+        for export in &callgrind.exports {
+            links.push((format!("{:?} export", export.format), export.path.as_path()));
+        }
+        for path in &callgrind.log_paths {
+            links.push(("callgrind log".to_owned(), path.as_path()));
+        }
+        for path in &callgrind.out_paths {
+            links.push(("callgrind output".to_owned(), path.as_path()));
+        }
+        self.write_links(html, &links);
+    }
+
+    fn write_tool(&self, html: &mut String, tool: &ToolSummary) {
+        writeln!(html, "<h3>{}</h3>", tool.tool.id()).unwrap();
+        for run in &tool.summaries {
+            writeln!(html, "<p><code>{}</code></p>", escape_html(&run.command)).unwrap();
+            if !run.summary.is_empty() {
+                writeln!(html, "<table>").unwrap();
+                for (key, value) in &run.summary {
+                    writeln!(
+                        html,
+                        "<tr><td class=\"name\">{}</td><td>{}</td></tr>",
+                        escape_html(key),
+                        escape_html(value)
+                    )
+                    .unwrap();
+                }
+                writeln!(html, "</table>").unwrap();
+            }
+        }
+
+        let mut links = vec![];
+        for path in &tool.log_paths {
+            links.push((format!("{} log", tool.tool.id()), path.as_path()));
+        }
+        for path in &tool.out_paths {
+            links.push((format!("{} output", tool.tool.id()), path.as_path()));
+        }
+        self.write_links(html, &links);
+    }
+
+    fn write_links(&self, html: &mut String, links: &[(String, &Path)]) {
+        if links.is_empty() {
+            return;
+        }
+        writeln!(html, "<ul>").unwrap();
+        for (name, path) in links {
+            writeln!(
+                html,
+                "<li><a href=\"{}\">{}</a></li>",
+                escape_html(&self.to_link(path)),
+                escape_html(name)
+            )
+            .unwrap();
+        }
+        writeln!(html, "</ul>").unwrap();
+    }
+
+    /// Return the link to the `path` relative to the report if it is within the `target_dir`
+    fn to_link(&self, path: &Path) -> String {
+        match path.strip_prefix(&self.target_dir) {
+            Ok(relative) => relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            Err(_) => format!("file://{}", path.display()),
+        }
+    }
+}
+
+fn write_costs(html: &mut String, costs: &CostsSummary) {
+    writeln!(html, "<table>").unwrap();
+    writeln!(
+        html,
+        "<thead><tr><th class=\"sortable name\">Event</th><th class=\"sortable\">New</th><th \
+         class=\"sortable\">Old</th><th class=\"sortable\">Change</th></tr></thead>"
+    )
+    .unwrap();
+    writeln!(html, "<tbody>").unwrap();
+    for (event_kind, diff) in costs {
+        writeln!(
+            html,
+            "<tr><td class=\"name\">{event_kind}</td><td data-value=\"{}\">{}</td><td \
+             data-value=\"{}\">{}</td>{}</tr>",
+            diff.new.unwrap_or(0),
+            format_cost(diff.new),
+            diff.old.unwrap_or(0),
+            format_cost(diff.old),
+            html_change(diff)
+        )
+        .unwrap();
+    }
+    writeln!(html, "</tbody>").unwrap();
+    writeln!(html, "</table>").unwrap();
+}
+
+/// Return the table cell with the change in percent colored by its direction
+fn html_change(diff: &CostsDiff) -> String {
+    match diff.diff_pct {
+        Some(pct) if pct.is_finite() => {
+            let class = if pct > 0f64 {
+                " class=\"worse\""
+            } else if pct < 0f64 {
+                " class=\"better\""
+            } else {
+                ""
+            };
+            format!(
+                "<td data-value=\"{pct}\"{class}>{}%</td>",
+                to_string_signed_short(pct)
+            )
+        }
+        Some(_) => "<td data-value=\"Infinity\" class=\"worse\">new</td>".to_owned(),
+        None => "<td data-value=\"-Infinity\"></td>".to_owned(),
+    }
+}
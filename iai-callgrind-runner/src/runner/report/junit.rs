@@ -0,0 +1,165 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::debug;
+
+use super::{group_by_module, is_regressed, name};
+use crate::runner::summary::{BenchmarkSummary, CallgrindRegressionSummary};
+use crate::util::{escape_xml, to_string_signed_short};
+
+/// The `JUnit` XML report of all benchmarks of a benchmark run
+///
+/// Each benchmark is a `testcase` and the benchmarks are grouped in a `testsuite` per group.
+/// Performance regressions are reported as `failure`, benchmarks which could not be run
+/// successfully as `error`. The events of the first callgrind run of a benchmark are stored in the
+/// `properties` of the `testcase`.
+pub struct JunitReport;
+
+impl JunitReport {
+    /// Save the `JUnit` XML report of the `summaries` to the file at `path`
+    pub fn save(path: &Path, summaries: &[BenchmarkSummary]) -> Result<()> {
+        debug!("Creating junit report '{}'", path.display());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed creating directory '{}'", parent.display()))?;
+        }
+        std::fs::write(path, Self::create(summaries))
+            .with_context(|| format!("Failed writing junit report '{}'", path.display()))
+    }
+
+    /// Create the `JUnit` XML document from the `summaries`
+    pub fn create(summaries: &[BenchmarkSummary]) -> String {
+        let mut xml = String::new();
+        writeln!(xml, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+        writeln!(
+            xml,
+            "<testsuites name=\"iai-callgrind\" tests=\"{}\" failures=\"{}\" errors=\"{}\">",
+            summaries.len(),
+            count_failures(summaries.iter()),
+            count_errors(summaries.iter())
+        )
+        .unwrap();
+        for (group, summaries) in group_by_module(summaries) {
+            writeln!(
+                xml,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">",
+                escape_xml(group),
+                summaries.len(),
+                count_failures(summaries.iter().copied()),
+                count_errors(summaries.iter().copied())
+            )
+            .unwrap();
+            for summary in summaries {
+                write_testcase(&mut xml, group, summary);
+            }
+            writeln!(xml, "  </testsuite>").unwrap();
+        }
+        writeln!(xml, "</testsuites>").unwrap();
+        xml
+    }
+}
+
+fn write_testcase(xml: &mut String, group: &str, summary: &BenchmarkSummary) {
+    writeln!(
+        xml,
+        "    <testcase name=\"{}\" classname=\"{}\">",
+        escape_xml(&name(summary)),
+        escape_xml(group)
+    )
+    .unwrap();
+
+    if let Some(run) = summary
+        .callgrind_summary
+        .as_ref()
+        .and_then(|callgrind| callgrind.summaries.first())
+    {
+        writeln!(xml, "      <properties>").unwrap();
+        for (event_kind, diff) in &run.events {
+            for (key, value) in [
+                (
+                    event_kind.to_string(),
+                    diff.new.map(|cost| cost.to_string()),
+                ),
+                (
+                    format!("{event_kind}.old"),
+                    diff.old.map(|cost| cost.to_string()),
+                ),
+                (
+                    format!("{event_kind}.diff_pct"),
+                    diff.diff_pct
+                        .filter(|pct| pct.is_finite())
+                        .map(|pct| pct.to_string()),
+                ),
+            ] {
+                if let Some(value) = value {
+                    writeln!(xml, "        <property name=\"{key}\" value=\"{value}\"/>").unwrap();
+                }
+            }
+        }
+        writeln!(xml, "      </properties>").unwrap();
+    }
+
+    let regressions = regressions(summary);
+    if !regressions.is_empty() {
+        let messages = regressions
+            .iter()
+            .map(|regression| {
+                format!(
+                    "Performance has regressed: {} ({} -> {}): {}% exceeds limit of {}%",
+                    regression.event_kind,
+                    regression.old,
+                    regression.new,
+                    to_string_signed_short(regression.diff_pct),
+                    to_string_signed_short(regression.limit)
+                )
+            })
+            .collect::<Vec<String>>();
+        writeln!(
+            xml,
+            "      <failure message=\"{}\" type=\"regression\">{}</failure>",
+            escape_xml(&messages.join("; ")),
+            escape_xml(&messages.join("\n"))
+        )
+        .unwrap();
+    }
+    if let Some(error) = &summary.error {
+        let message = error.lines().next().unwrap_or_default();
+        writeln!(
+            xml,
+            "      <error message=\"{}\" type=\"error\">{}</error>",
+            escape_xml(message),
+            escape_xml(error)
+        )
+        .unwrap();
+    }
+    writeln!(xml, "    </testcase>").unwrap();
+}
+
+fn regressions(summary: &BenchmarkSummary) -> Vec<&CallgrindRegressionSummary> {
+    summary
+        .callgrind_summary
+        .as_ref()
+        .map(|callgrind| {
+            callgrind
+                .summaries
+                .iter()
+                .flat_map(|run| &run.regressions)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn count_failures<'a, I>(summaries: I) -> usize
+where
+    I: Iterator<Item = &'a BenchmarkSummary>,
+{
+    summaries.filter(|summary| is_regressed(summary)).count()
+}
+
+fn count_errors<'a, I>(summaries: I) -> usize
+where
+    I: Iterator<Item = &'a BenchmarkSummary>,
+{
+    summaries.filter(|summary| summary.error.is_some()).count()
+}
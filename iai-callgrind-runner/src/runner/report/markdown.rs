@@ -0,0 +1,226 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::debug;
+
+use super::{group_by_module, is_regressed, name, title};
+use crate::api::EventKind;
+use crate::runner::summary::{
+    BenchmarkSummary, CallgrindRegressionSummary, CostsDiff, CostsSummary, ToolSummary,
+};
+use crate::util::{format_cost, to_string_signed_short};
+
+/// The markdown report of all benchmarks of a benchmark run
+///
+/// The document is meant to be posted as is, for example as comment of a pull request. There's a
+/// table for each group of benchmarks with the configured [`EventKind`]s, followed by the
+/// collapsible details of each benchmark of this group. Regressions are highlighted in bold.
+pub struct MarkdownReport {
+    event_kinds: Vec<EventKind>,
+}
+
+impl MarkdownReport {
+    pub fn new(event_kinds: Vec<EventKind>) -> Self {
+        Self { event_kinds }
+    }
+
+    /// Save the markdown report of the `summaries` to the file at `path`
+    pub fn save(&self, path: &Path, summaries: &[BenchmarkSummary]) -> Result<()> {
+        debug!("Creating markdown report '{}'", path.display());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed creating directory '{}'", parent.display()))?;
+        }
+        std::fs::write(path, self.create(summaries))
+            .with_context(|| format!("Failed writing markdown report '{}'", path.display()))
+    }
+
+    /// Create the markdown document from the `summaries`
+    pub fn create(&self, summaries: &[BenchmarkSummary]) -> String {
+        let regressed = summaries
+            .iter()
+            .filter(|summary| is_regressed(summary))
+            .count();
+
+        let mut md = String::new();
+        writeln!(md, "## Iai-Callgrind").unwrap();
+        writeln!(md).unwrap();
+        if regressed > 0 {
+            writeln!(
+                md,
+                "{} benchmarks, **{regressed} regressed**",
+                summaries.len()
+            )
+            .unwrap();
+        } else {
+            writeln!(md, "{} benchmarks, 0 regressed", summaries.len()).unwrap();
+        }
+
+        for (group, summaries) in group_by_module(summaries) {
+            writeln!(md).unwrap();
+            writeln!(md, "### `{group}`").unwrap();
+            writeln!(md).unwrap();
+            self.write_group_table(&mut md, &summaries);
+            for summary in summaries {
+                writeln!(md).unwrap();
+                write_benchmark(&mut md, summary);
+            }
+        }
+        md
+    }
+
+    fn write_group_table(&self, md: &mut String, summaries: &[&BenchmarkSummary]) {
+        write!(md, "| Benchmark |").unwrap();
+        for event_kind in &self.event_kinds {
+            write!(md, " {event_kind} | {event_kind} (old) | Diff |").unwrap();
+        }
+        writeln!(md).unwrap();
+        write!(md, "|:----------|").unwrap();
+        for _ in &self.event_kinds {
+            write!(md, "--:|--:|--:|").unwrap();
+        }
+        writeln!(md).unwrap();
+
+        for summary in summaries {
+            if is_regressed(summary) {
+                write!(md, "| **{}** |", escape_markdown(&name(summary))).unwrap();
+            } else {
+                write!(md, "| {} |", escape_markdown(&name(summary))).unwrap();
+            }
+
+            let run = summary
+                .callgrind_summary
+                .as_ref()
+                .and_then(|callgrind| callgrind.summaries.first());
+            for event_kind in &self.event_kinds {
+                match run.and_then(|run| run.events.diff_by_kind(event_kind)) {
+                    Some(diff) => write!(
+                        md,
+                        " {} | {} | {} |",
+                        format_cost(diff.new),
+                        format_cost(diff.old),
+                        format_change(
+                            diff,
+                            run.map_or(false, |run| is_regressed_by(&run.regressions, *event_kind))
+                        )
+                    )
+                    .unwrap(),
+                    None => write!(md, " N/A | N/A | N/A |").unwrap(),
+                }
+            }
+            writeln!(md).unwrap();
+        }
+    }
+}
+
+fn write_benchmark(md: &mut String, summary: &BenchmarkSummary) {
+    writeln!(md, "<details>").unwrap();
+    writeln!(
+        md,
+        "<summary>{}</summary>",
+        escape_markdown(&title(summary))
+    )
+    .unwrap();
+    writeln!(md).unwrap();
+    if let Some(details) = &summary.details {
+        writeln!(md, "{}", escape_markdown(details)).unwrap();
+        writeln!(md).unwrap();
+    }
+    if let Some(error) = &summary.error {
+        writeln!(md, "**Error: {}**", escape_markdown(error)).unwrap();
+        writeln!(md).unwrap();
+    }
+
+    if let Some(callgrind) = &summary.callgrind_summary {
+        for run in &callgrind.summaries {
+            writeln!(md, "`{}`", run.command.trim()).unwrap();
+            writeln!(md).unwrap();
+            write_costs(md, &run.events, &run.regressions);
+            writeln!(md).unwrap();
+            for regression in &run.regressions {
+                writeln!(
+                    md,
+                    "- **Performance has regressed: {} ({} -> {}): {}% exceeds limit of {}%**",
+                    regression.event_kind,
+                    regression.old,
+                    regression.new,
+                    to_string_signed_short(regression.diff_pct),
+                    to_string_signed_short(regression.limit)
+                )
+                .unwrap();
+            }
+            if !run.regressions.is_empty() {
+                writeln!(md).unwrap();
+            }
+        }
+    }
+
+    for tool in &summary.tool_summaries {
+        write_tool(md, tool);
+    }
+    writeln!(md, "</details>").unwrap();
+}
+
+fn write_costs(md: &mut String, costs: &CostsSummary, regressions: &[CallgrindRegressionSummary]) {
+    writeln!(md, "| Event | New | Old | Diff | Factor |").unwrap();
+    writeln!(md, "|:------|--:|--:|--:|--:|").unwrap();
+    for (event_kind, diff) in costs {
+        let factor = match diff.factor {
+            Some(factor) if factor.is_finite() => format!("{}x", to_string_signed_short(factor)),
+            _ => "N/A".to_owned(),
+        };
+        writeln!(
+            md,
+            "| {event_kind} | {} | {} | {} | {factor} |",
+            format_cost(diff.new),
+            format_cost(diff.old),
+            format_change(diff, is_regressed_by(regressions, *event_kind))
+        )
+        .unwrap();
+    }
+}
+
+fn write_tool(md: &mut String, tool: &ToolSummary) {
+    writeln!(md, "**{}**", tool.tool.id()).unwrap();
+    writeln!(md).unwrap();
+    for run in &tool.summaries {
+        writeln!(md, "`{}`", run.command.trim()).unwrap();
+        writeln!(md).unwrap();
+        for (key, value) in &run.summary {
+            writeln!(md, "- {}: {}", escape_markdown(key), escape_markdown(value)).unwrap();
+        }
+        if !run.summary.is_empty() {
+            writeln!(md).unwrap();
+        }
+    }
+}
+
+fn is_regressed_by(regressions: &[CallgrindRegressionSummary], event_kind: EventKind) -> bool {
+    regressions
+        .iter()
+        .any(|regression| regression.event_kind == event_kind)
+}
+
+/// Return the change in percent, in bold if it is a regression
+fn format_change(diff: &CostsDiff, is_regressed: bool) -> String {
+    let change = match diff.diff_pct {
+        Some(pct) if pct.is_finite() => format!("{}%", to_string_signed_short(pct)),
+        Some(_) => "new".to_owned(),
+        None => "N/A".to_owned(),
+    };
+    if is_regressed {
+        format!("**{change}**")
+    } else {
+        change
+    }
+}
+
+/// Escape the characters which would otherwise be interpreted as html or break a table
+fn escape_markdown(string: &str) -> String {
+    string
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('|', "\\|")
+}
@@ -0,0 +1,266 @@
+//! The reports covering all benchmarks of a benchmark run
+//!
+//! Each benchmark file runs in its own process. To be able to create a report of all benchmark
+//! files, the [`RunSummary`] of each benchmark file is stored in the [`DATA_FILE_NAME`] file in the
+//! directory of the benchmark file. All benchmark files of a `cargo bench` run share the same run
+//! id, which consists of the process id and the start time of cargo. The reports are then created
+//! from the summaries with the run id of the current run after each benchmark file has finished,
+//! so they cover all benchmarks of the `cargo bench` run which have finished so far. Summaries of
+//! previous runs are ignored.
+
+pub mod bmf;
+pub mod csv;
+pub mod html;
+pub mod junit;
+pub mod markdown;
+pub mod openmetrics;
+
+use std::fs::File;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use glob::glob;
+use indexmap::IndexMap;
+use log::{debug, warn};
+
+use self::bmf::BmfReport;
+use self::csv::CsvReport;
+use self::html::HtmlReport;
+use self::junit::JunitReport;
+use self::markdown::MarkdownReport;
+use self::openmetrics::OpenMetricsReport;
+use super::meta::Metadata;
+use super::summary::{BenchmarkSummary, CallgrindSummary, ExportFormat, RunSummary};
+use crate::api::EventKind;
+
+/// The name of the file with the summaries of a single benchmark file
+pub const DATA_FILE_NAME: &str = "report.json";
+
+/// The [`EventKind`]s shown in the reports if not specified otherwise
+pub const DEFAULT_EVENT_KINDS: [EventKind; 2] = [EventKind::Ir, EventKind::EstimatedCycles];
+
+/// The format of a report specified with `--report`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    /// A json document in the Bencher Metric Format (BMF)
+    Bmf,
+    /// A csv table with one row per benchmark and `EventKind`
+    Csv,
+    /// A `JUnit` XML document for test dashboards of CI systems
+    Junit,
+    /// A markdown document suitable for pull request comments
+    Markdown,
+    /// An `OpenMetrics` text file suitable for Prometheus
+    #[clap(name = "openmetrics")]
+    OpenMetrics,
+    /// A tab separated table with one row per benchmark and `EventKind`
+    Tsv,
+}
+
+/// A report in the [`ReportFormat`] which should be saved to the `path`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportOutput {
+    pub format: ReportFormat,
+    pub path: PathBuf,
+}
+
+/// An export specified with `--export`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportOutput {
+    /// Export the callgrind output of each benchmark in the [`ExportFormat`]
+    Callgrind(ExportFormat),
+    /// Export the summaries of all benchmarks as `OpenMetrics` text file to the path
+    ///
+    /// This is the same as a [`ReportOutput`] with [`ReportFormat::OpenMetrics`]
+    OpenMetrics(PathBuf),
+}
+
+/// The [`RunSummary`] of all benchmark files stored in the `target_dir`
+pub struct ReportData {
+    target_dir: PathBuf,
+}
+
+impl ReportData {
+    pub fn new<T>(target_dir: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self {
+            target_dir: target_dir.into(),
+        }
+    }
+
+    /// Save the [`RunSummary`] of the benchmark file `module`
+    ///
+    /// The summary of a previous run of this benchmark file is replaced.
+    pub fn save(&self, module: &str, summary: &RunSummary) -> Result<()> {
+        let dir = self
+            .target_dir
+            .join(module.split("::").collect::<PathBuf>());
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed creating directory '{}'", dir.display()))?;
+        let path = dir.join(DATA_FILE_NAME);
+        let file =
+            File::create(&path).with_context(|| format!("Failed creating '{}'", path.display()))?;
+        serde_json::to_writer(file, summary)
+            .with_context(|| format!("Failed writing '{}'", path.display()))
+    }
+
+    /// Load the [`RunSummary`] of all benchmark files in the `target_dir` of the run with `run_id`
+    ///
+    /// Summaries of other runs and summaries which started before `not_before` in milliseconds
+    /// since the unix epoch are skipped. Files which can't be read, for example because they were
+    /// created by an incompatible version, are skipped with a warning.
+    pub fn load(&self, run_id: &str, not_before: u64) -> Result<Vec<RunSummary>> {
+        let pattern = self.target_dir.join("**").join(DATA_FILE_NAME);
+        let mut paths = glob(&pattern.to_string_lossy())
+            .with_context(|| format!("Invalid glob pattern '{}'", pattern.display()))?
+            .filter_map(Result::ok)
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+
+        let mut summaries = vec![];
+        for path in paths {
+            let result: Result<RunSummary> = File::open(&path)
+                .map_err(anyhow::Error::new)
+                .and_then(|file| serde_json::from_reader(file).map_err(anyhow::Error::new));
+            match result {
+                Ok(summary) if summary.is_same_run(run_id) && summary.start_time >= not_before => {
+                    summaries.push(summary);
+                }
+                Ok(_) => debug!("Skipping '{}' of another run in report", path.display()),
+                Err(error) => warn!("Skipping '{}' in report: {error}", path.display()),
+            }
+        }
+        Ok(summaries)
+    }
+}
+
+/// Create the reports and the summary of the whole benchmark run requested on the command line
+///
+/// The `summaries` of the benchmark file `module` are saved first, so the reports include the
+/// summaries of all benchmark files of the current `cargo bench` run in the `target_dir`. If the
+/// run can't be detected, the reports cover only the benchmark file `module`. Nothing is done if no
+/// report was requested.
+pub(crate) fn create(
+    meta: &Metadata,
+    module: &str,
+    summaries: Vec<BenchmarkSummary>,
+) -> Result<()> {
+    let html_report = meta.args.html_report.unwrap_or_default();
+    let reports = meta
+        .args
+        .report
+        .iter()
+        .cloned()
+        .chain(meta.args.junit.clone().map(|path| ReportOutput {
+            format: ReportFormat::Junit,
+            path,
+        }))
+        .chain(meta.args.export.iter().filter_map(|export| match export {
+            ExportOutput::Callgrind(_) => None,
+            ExportOutput::OpenMetrics(path) => Some(ReportOutput {
+                format: ReportFormat::OpenMetrics,
+                path: path.clone(),
+            }),
+        }))
+        .collect::<Vec<ReportOutput>>();
+    if !html_report && reports.is_empty() && meta.args.save_summary_to.is_none() {
+        return Ok(());
+    }
+
+    let data = ReportData::new(&meta.target_dir);
+    let current = RunSummary::new(
+        meta.cargo_run.as_ref().map(|run| run.id.clone()),
+        meta.command_line_args.clone(),
+        meta.start_time,
+        SystemTime::now(),
+        summaries,
+    );
+    data.save(module, &current)?;
+    let run = if let Some(cargo_run) = &meta.cargo_run {
+        let mut run = RunSummary::new(
+            Some(cargo_run.id.clone()),
+            meta.command_line_args.clone(),
+            meta.start_time,
+            meta.start_time,
+            vec![],
+        );
+        for summary in data.load(&cargo_run.id, cargo_run.earliest_start_time())? {
+            run.merge(summary);
+        }
+        run
+    } else {
+        current
+    };
+    if let Some(path) = &meta.args.save_summary_to {
+        run.save(path)?;
+    }
+    let summaries = &run.benchmarks;
+
+    let event_kinds = meta
+        .args
+        .report_event_kinds
+        .clone()
+        .unwrap_or_else(|| DEFAULT_EVENT_KINDS.to_vec());
+    if html_report {
+        HtmlReport::new(&meta.target_dir, event_kinds.clone()).save(summaries)?;
+    }
+    for report in &reports {
+        match report.format {
+            ReportFormat::Bmf => BmfReport::save(&report.path, summaries)?,
+            ReportFormat::Csv => CsvReport::csv().save(&report.path, summaries)?,
+            ReportFormat::Junit => JunitReport::save(&report.path, summaries)?,
+            ReportFormat::Markdown => {
+                MarkdownReport::new(event_kinds.clone()).save(&report.path, summaries)?;
+            }
+            ReportFormat::OpenMetrics => OpenMetricsReport::save(&report.path, summaries)?,
+            ReportFormat::Tsv => CsvReport::tsv().save(&report.path, summaries)?,
+        }
+    }
+    Ok(())
+}
+
+/// Return the title of the benchmark consisting of the module path and the id
+pub fn title(summary: &BenchmarkSummary) -> String {
+    match &summary.id {
+        Some(id) => format!("{} {id}", summary.module_path),
+        None => summary.module_path.clone(),
+    }
+}
+
+/// Return the name of the benchmark within its group consisting of the function name and the id
+pub fn name(summary: &BenchmarkSummary) -> String {
+    let name = summary
+        .module_path
+        .rsplit_once("::")
+        .map_or(summary.module_path.as_str(), |(_, name)| name);
+    match &summary.id {
+        Some(id) => format!("{name} {id}"),
+        None => name.to_owned(),
+    }
+}
+
+/// Group the `summaries` by their module path without the function name
+///
+/// The groups and the summaries within a group keep the order of the `summaries`.
+pub fn group_by_module(summaries: &[BenchmarkSummary]) -> IndexMap<&str, Vec<&BenchmarkSummary>> {
+    let mut groups: IndexMap<&str, Vec<&BenchmarkSummary>> = IndexMap::new();
+    for summary in summaries {
+        let group = summary
+            .module_path
+            .rsplit_once("::")
+            .map_or(summary.module_path.as_str(), |(group, _)| group);
+        groups.entry(group).or_default().push(summary);
+    }
+    groups
+}
+
+/// Return true if any performance regression check of the benchmark has failed
+pub fn is_regressed(summary: &BenchmarkSummary) -> bool {
+    summary
+        .callgrind_summary
+        .as_ref()
+        .map_or(false, CallgrindSummary::is_regressed)
+}
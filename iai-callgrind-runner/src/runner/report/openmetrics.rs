@@ -0,0 +1,117 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::debug;
+
+use super::is_regressed;
+use crate::runner::summary::BenchmarkSummary;
+
+/// The `OpenMetrics` text file of all benchmarks of a benchmark run
+///
+/// The file can be scraped by Prometheus, for example with the textfile collector of the node
+/// exporter. The costs of each [`EventKind`] of the first callgrind run of a benchmark are the
+/// `iai_callgrind_events` gauge with the `baseline` label `new` and `old`. The
+/// `iai_callgrind_regressed` gauge is `1` if the performance of the benchmark has regressed and `0`
+/// otherwise. Benchmarks which could not be run successfully are left out of this gauge and are
+/// `1` in the `iai_callgrind_error` gauge instead.
+///
+/// [`EventKind`]: crate::api::EventKind
+pub struct OpenMetricsReport;
+
+impl OpenMetricsReport {
+    /// Save the `OpenMetrics` text of the `summaries` to the file at `path`
+    pub fn save(path: &Path, summaries: &[BenchmarkSummary]) -> Result<()> {
+        debug!("Creating openmetrics export '{}'", path.display());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed creating directory '{}'", parent.display()))?;
+        }
+        std::fs::write(path, Self::create(summaries))
+            .with_context(|| format!("Failed writing openmetrics export '{}'", path.display()))
+    }
+
+    /// Create the `OpenMetrics` text from the `summaries`
+    pub fn create(summaries: &[BenchmarkSummary]) -> String {
+        let mut text = String::new();
+        writeln!(text, "# TYPE iai_callgrind_events gauge").unwrap();
+        writeln!(
+            text,
+            "# HELP iai_callgrind_events The costs of the callgrind events of a benchmark"
+        )
+        .unwrap();
+        for summary in summaries {
+            let run = match summary
+                .callgrind_summary
+                .as_ref()
+                .and_then(|callgrind| callgrind.summaries.first())
+            {
+                Some(run) => run,
+                None => continue,
+            };
+            for (event_kind, diff) in &run.events {
+                for (baseline, cost) in [("new", diff.new), ("old", diff.old)] {
+                    if let Some(cost) = cost {
+                        writeln!(
+                            text,
+                            "iai_callgrind_events{{{},event=\"{event_kind}\",baseline=\"\
+                             {baseline}\"}} {cost}",
+                            labels(summary)
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+        }
+
+        writeln!(text, "# TYPE iai_callgrind_regressed gauge").unwrap();
+        writeln!(
+            text,
+            "# HELP iai_callgrind_regressed Whether the performance of a benchmark has regressed"
+        )
+        .unwrap();
+        for summary in summaries.iter().filter(|summary| summary.error.is_none()) {
+            writeln!(
+                text,
+                "iai_callgrind_regressed{{{}}} {}",
+                labels(summary),
+                u8::from(is_regressed(summary))
+            )
+            .unwrap();
+        }
+
+        writeln!(text, "# TYPE iai_callgrind_error gauge").unwrap();
+        writeln!(
+            text,
+            "# HELP iai_callgrind_error Whether a benchmark could not be run successfully"
+        )
+        .unwrap();
+        for summary in summaries {
+            writeln!(
+                text,
+                "iai_callgrind_error{{{}}} {}",
+                labels(summary),
+                u8::from(summary.error.is_some())
+            )
+            .unwrap();
+        }
+        writeln!(text, "# EOF").unwrap();
+        text
+    }
+}
+
+/// Return the `benchmark` and `id` labels of the benchmark
+fn labels(summary: &BenchmarkSummary) -> String {
+    format!(
+        "benchmark=\"{}\",id=\"{}\"",
+        escape_label(&summary.module_path),
+        escape_label(summary.id.as_deref().unwrap_or_default())
+    )
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
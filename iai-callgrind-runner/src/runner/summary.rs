@@ -2,6 +2,8 @@ use std::borrow::Cow;
 use std::ffi::OsString;
 use std::fs::File;
 use std::path::{Path, PathBuf};
+use std::slice;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
 use glob::glob;
@@ -12,11 +14,22 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::callgrind::model::Costs;
+use super::report::csv::CsvReport;
 use super::tool::{ToolOutputPath, ValgrindTool};
 use super::Error;
 use crate::api::EventKind;
 use crate::util::{factor_diff, make_absolute, percentage_diff};
 
+/// The `AnnotateOutput` records the paths to the files of the source line annotation report
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct AnnotateOutput {
+    /// The path to the report in text format
+    pub text_path: PathBuf,
+    /// The path to the report in html format
+    pub html_path: PathBuf,
+}
+
 /// A `Baseline` depending on the [`BaselineKind`] which points to the corresponding path
 ///
 /// This baseline is used for comparisons with the new output of valgrind tools.
@@ -79,6 +92,8 @@ pub struct BenchmarkSummary {
     pub callgrind_summary: Option<CallgrindSummary>,
     /// The summary of other valgrind tool runs
     pub tool_summaries: Vec<ToolSummary>,
+    /// The error message if this benchmark could not be run successfully
+    pub error: Option<String>,
 }
 
 /// The `CallgrindRegressionSummary` describing a single event based performance regression
@@ -110,6 +125,8 @@ pub struct CallgrindRunSummary {
     pub events: CostsSummary,
     /// All detected performance regressions
     pub regressions: Vec<CallgrindRegressionSummary>,
+    /// If present, the most expensive functions of this run
+    pub hot_functions: Option<HotFunctionsSummary>,
 }
 
 /// The `CallgrindSummary` summarizes all callgrind runs
@@ -124,6 +141,14 @@ pub struct CallgrindSummary {
     pub out_paths: Vec<PathBuf>,
     /// The summaries of possibly created flamegraphs
     pub flamegraphs: Vec<FlamegraphSummary>,
+    /// If present, the paths to the files of the function-level differential report
+    pub function_diff: Option<FunctionDiffOutput>,
+    /// The paths to the source line annotation report if it was created
+    pub annotate: Option<AnnotateOutput>,
+    /// The path to the instruction-level hotspot report if it was created
+    pub hot_instructions: Option<PathBuf>,
+    /// The summaries of the exports of the callgrind output in other formats
+    pub exports: Vec<ExportSummary>,
     /// The summaries of all callgrind runs
     pub summaries: Vec<CallgrindRunSummary>,
 }
@@ -133,7 +158,7 @@ pub struct CallgrindSummary {
 ///
 /// There is either a `new` or an `old` value present. Never can both be absent. If both values are
 /// present, then there is also a `diff_pct` and `factor` present.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub struct CostsDiff {
     /// The value of the new cost
@@ -147,14 +172,37 @@ pub struct CostsDiff {
 }
 
 /// The `CostsSummary` contains all differences for affected [`EventKind`]s
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub struct CostsSummary(IndexMap<EventKind, CostsDiff>);
 
+/// The format in which the callgrind output is exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum ExportFormat {
+    /// The DOT format of [Graphviz](https://graphviz.org)
+    Dot,
+    /// The protobuf format of [pprof](https://github.com/google/pprof)
+    Pprof,
+    /// The json format of [speedscope](https://www.speedscope.app)
+    Speedscope,
+}
+
+/// The `ExportSummary` records the path of the exported callgrind output
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ExportSummary {
+    /// The [`ExportFormat`]
+    pub format: ExportFormat,
+    /// The path to the exported file
+    pub path: PathBuf,
+}
+
 /// The `FlamegraphSummary` records all created paths for an [`EventKind`] specific flamegraph
 ///
 /// Either the `regular_path`, `old_path` or the `diff_path` are present. Never can all of them be
-/// absent.
+/// absent. The paths to the folded stacks are only present if the creation of the folded stacks
+/// files was requested.
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub struct FlamegraphSummary {
@@ -166,9 +214,131 @@ pub struct FlamegraphSummary {
     pub old_path: Option<PathBuf>,
     /// If present, the path to the file of the differential flamegraph
     pub diff_path: Option<PathBuf>,
+    /// If present, the path to the file with the folded stacks of the new callgrind run
+    pub folded_path: Option<PathBuf>,
+    /// If present, the path to the file with the folded stacks of the old callgrind run
+    pub old_folded_path: Option<PathBuf>,
 }
 
-/// The format (json, ...) in which the summary file should be saved or printed
+/// The `FunctionDiff` describes the difference between the new and old costs of a single function
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct FunctionDiff {
+    /// The name of the function
+    pub function: String,
+    /// If known, the path to the source file of the function
+    pub file: Option<PathBuf>,
+    /// If known, the path to the object file of the function
+    pub object: Option<PathBuf>,
+    /// Whether the function appeared, disappeared or changed
+    pub kind: FunctionDiffKind,
+    /// The new and old inclusive costs of the function
+    pub inclusive: CostsDiff,
+    /// The new and old exclusive (self) costs of the function
+    pub exclusive: CostsDiff,
+}
+
+/// The `FunctionDiffKind` describes how a function changed compared to the old benchmark run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum FunctionDiffKind {
+    /// The function is only present in the new benchmark run
+    Appeared,
+    /// The function is only present in the old benchmark run
+    Disappeared,
+    /// The costs of the function changed
+    Changed,
+}
+
+/// The `FunctionDiffOutput` records the paths to the files of the function-level differential
+/// report
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct FunctionDiffOutput {
+    /// The path to the report in json format
+    pub json_path: PathBuf,
+    /// The path to the report in markdown format
+    pub markdown_path: PathBuf,
+}
+
+/// The `FunctionDiffReport` lists all functions which changed between the new and old benchmark
+/// run
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct FunctionDiffReport {
+    /// The title of the benchmark
+    pub benchmark: String,
+    /// The changed functions for each `EventKind`
+    pub sections: Vec<FunctionDiffSection>,
+}
+
+/// The `FunctionDiffSection` contains the changed functions for a single [`EventKind`]
+///
+/// The functions are sorted by the absolute difference of their inclusive costs in descending
+/// order.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct FunctionDiffSection {
+    /// The `EventKind` of this section
+    pub event_kind: EventKind,
+    /// The functions which appeared, disappeared or changed
+    pub functions: Vec<FunctionDiff>,
+}
+
+/// The `FunctionSummary` describes the costs of a single function for a specific [`EventKind`]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct FunctionSummary {
+    /// The name of the function
+    pub function: String,
+    /// If known, the path to the source file of the function
+    pub file: Option<PathBuf>,
+    /// If known, the path to the object file of the function
+    pub object: Option<PathBuf>,
+    /// The new and old (if any) costs of the function
+    pub costs: CostsDiff,
+}
+
+/// The `HotFunctionsSummary` contains the most expensive functions of a callgrind run
+///
+/// The functions are sorted by their new costs in descending order.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct HotFunctionsSummary {
+    /// The `EventKind` by which the functions were sorted
+    pub event_kind: EventKind,
+    /// The functions with the highest inclusive costs
+    pub inclusive: Vec<FunctionSummary>,
+    /// The functions with the highest exclusive (self) costs
+    pub exclusive: Vec<FunctionSummary>,
+}
+
+/// The `RunSummary` of a whole benchmark run
+///
+/// This is the format of the file created with `--save-summary-to`. It contains the
+/// [`BenchmarkSummary`] of each benchmark of all benchmark files.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct RunSummary {
+    /// The version of this format. Only backwards incompatible cause an increase of the version
+    pub version: String,
+    /// The version of the `iai-callgrind-runner` which created this summary
+    pub runner_version: String,
+    /// The id shared by all benchmark files of the same `cargo bench` invocation
+    pub run_id: Option<String>,
+    /// The command line arguments passed to the benchmarks after `--`
+    pub command_line_args: Vec<String>,
+    /// The start time of the benchmark run in milliseconds since the unix epoch
+    pub start_time: u64,
+    /// The end time of the benchmark run in milliseconds since the unix epoch
+    pub end_time: u64,
+    /// True if the performance of any benchmark has regressed
+    pub is_regressed: bool,
+    /// The summaries of all benchmarks
+    pub benchmarks: Vec<BenchmarkSummary>,
+}
+
+/// The format (json, csv, ...) in which the summary file should be saved or printed
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub enum SummaryFormat {
@@ -176,6 +346,10 @@ pub enum SummaryFormat {
     Json,
     /// The format in pretty printed json
     PrettyJson,
+    /// A csv table with one row per `EventKind`
+    Csv,
+    /// A tab separated table with one row per `EventKind`
+    Tsv,
 }
 
 /// Manage the summary output file with this `SummaryOutput`
@@ -246,12 +420,20 @@ impl BenchmarkSummary {
             details,
             callgrind_summary: None,
             tool_summaries: vec![],
+            error: None,
             summary_output: output,
             project_root,
             package_dir,
         }
     }
 
+    /// Record the `error` which prevented this benchmark from being run successfully
+    #[must_use]
+    pub fn with_error(mut self, error: &anyhow::Error) -> Self {
+        self.error = Some(format!("{error:#}"));
+        self
+    }
+
     /// If this `BenchmarkSummary` has a value in the option `SummaryOutput` save it in json format
     pub fn save_json(&self, pretty: bool) -> Result<()> {
         if let Some(output) = &self.summary_output {
@@ -278,6 +460,8 @@ impl BenchmarkSummary {
             match output.format {
                 SummaryFormat::Json => self.save_json(false)?,
                 SummaryFormat::PrettyJson => self.save_json(true)?,
+                SummaryFormat::Csv => CsvReport::csv().save(&output.path, slice::from_ref(self))?,
+                SummaryFormat::Tsv => CsvReport::tsv().save(&output.path, slice::from_ref(self))?,
             }
         } else {
             debug!("No summary output file specified for {:?}", self.id);
@@ -320,6 +504,10 @@ impl CallgrindSummary {
             log_paths,
             out_paths,
             flamegraphs: Vec::default(),
+            function_diff: Option::default(),
+            annotate: Option::default(),
+            hot_instructions: Option::default(),
+            exports: Vec::default(),
             summaries: Vec::default(),
         }
     }
@@ -358,10 +546,43 @@ impl CallgrindSummary {
             }),
             events,
             regressions,
+            hot_functions: None,
         });
     }
 }
 
+impl CostsDiff {
+    /// Create a new `CostsDiff` calculating the difference if both costs are present
+    ///
+    /// # Panics
+    ///
+    /// If neither a `new` nor an `old` cost is present
+    pub fn new(new: Option<u64>, old: Option<u64>) -> Self {
+        match (new, old) {
+            (Some(new), Some(old)) => Self {
+                new: Some(new),
+                old: Some(old),
+                diff_pct: Some(percentage_diff(new, old)),
+                factor: Some(factor_diff(new, old)),
+            },
+            (None, None) => panic!("A new or an old cost has to be present"),
+            (new, old) => Self {
+                new,
+                old,
+                diff_pct: None,
+                factor: None,
+            },
+        }
+    }
+
+    /// Return the absolute difference between the new and old cost
+    ///
+    /// A missing cost counts as `0`.
+    pub fn delta(&self) -> i128 {
+        i128::from(self.new.unwrap_or(0)) - i128::from(self.old.unwrap_or(0))
+    }
+}
+
 impl CostsSummary {
     /// Create a new `CostsSummary` calculating the differences between new and old (if any)
     /// [`Costs`]
@@ -378,30 +599,10 @@ impl CostsSummary {
             }
             let mut map = indexmap! {};
             for event_kind in new_costs.event_kinds_union(old_costs.as_ref()) {
-                let diff = match (
+                let diff = CostsDiff::new(
                     new_costs.cost_by_kind(&event_kind),
                     old_costs.cost_by_kind(&event_kind),
-                ) {
-                    (None, Some(cost)) => CostsDiff {
-                        new: None,
-                        old: Some(cost),
-                        diff_pct: None,
-                        factor: None,
-                    },
-                    (Some(cost), None) => CostsDiff {
-                        new: Some(cost),
-                        old: None,
-                        diff_pct: None,
-                        factor: None,
-                    },
-                    (Some(new), Some(old)) => CostsDiff {
-                        new: Some(new),
-                        old: Some(old),
-                        diff_pct: Some(percentage_diff(new, old)),
-                        factor: Some(factor_diff(new, old)),
-                    },
-                    (None, None) => unreachable!(),
-                };
+                );
                 map.insert(event_kind, diff);
             }
             Self(map)
@@ -409,17 +610,7 @@ impl CostsSummary {
             CostsSummary(
                 new_costs
                     .iter()
-                    .map(|(event_kind, cost)| {
-                        (
-                            *event_kind,
-                            CostsDiff {
-                                new: Some(*cost),
-                                old: None,
-                                diff_pct: None,
-                                factor: None,
-                            },
-                        )
-                    })
+                    .map(|(event_kind, cost)| (*event_kind, CostsDiff::new(Some(*cost), None)))
                     .collect::<IndexMap<_, _>>(),
             )
         }
@@ -429,6 +620,20 @@ impl CostsSummary {
     pub fn diff_by_kind(&self, event_kind: &EventKind) -> Option<&CostsDiff> {
         self.0.get(event_kind)
     }
+
+    /// Return an iterator over all [`EventKind`]s and their [`CostsDiff`]s
+    pub fn iter(&self) -> indexmap::map::Iter<'_, EventKind, CostsDiff> {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a CostsSummary {
+    type Item = (&'a EventKind, &'a CostsDiff);
+    type IntoIter = indexmap::map::Iter<'a, EventKind, CostsDiff>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 impl FlamegraphSummary {
@@ -439,17 +644,80 @@ impl FlamegraphSummary {
             regular_path: Option::default(),
             old_path: Option::default(),
             diff_path: Option::default(),
+            folded_path: Option::default(),
+            old_folded_path: Option::default(),
+        }
+    }
+}
+
+impl RunSummary {
+    /// Create a new `RunSummary` of the `benchmarks` of the benchmark run with the `run_id`
+    pub fn new(
+        run_id: Option<String>,
+        command_line_args: Vec<String>,
+        start_time: SystemTime,
+        end_time: SystemTime,
+        benchmarks: Vec<BenchmarkSummary>,
+    ) -> Self {
+        Self {
+            version: "1".to_owned(),
+            runner_version: env!("CARGO_PKG_VERSION").to_owned(),
+            run_id,
+            command_line_args,
+            start_time: to_millis(start_time),
+            end_time: to_millis(end_time),
+            is_regressed: benchmarks.iter().any(|benchmark| {
+                benchmark
+                    .callgrind_summary
+                    .as_ref()
+                    .map_or(false, CallgrindSummary::is_regressed)
+            }),
+            benchmarks,
         }
     }
+
+    /// Merge the `other` summary of another benchmark file of the same run into this summary
+    ///
+    /// The start time is the earliest and the end time the latest of both summaries. Summaries of
+    /// other runs have to be filtered out before, for example with [`RunSummary::is_same_run`].
+    pub fn merge(&mut self, other: RunSummary) {
+        self.start_time = self.start_time.min(other.start_time);
+        self.end_time = self.end_time.max(other.end_time);
+        self.is_regressed |= other.is_regressed;
+        self.benchmarks.extend(other.benchmarks);
+    }
+
+    /// Return true if this summary was created by the benchmark run with the `run_id`
+    pub fn is_same_run(&self, run_id: &str) -> bool {
+        self.run_id.as_deref() == Some(run_id)
+    }
+
+    /// Save this `RunSummary` in pretty json format to the file at `path`
+    pub fn save(&self, path: &Path) -> Result<()> {
+        debug!("Saving run summary to '{}'", path.display());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed creating directory '{}'", parent.display()))?;
+        }
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create run summary file '{}'", path.display()))?;
+        serde_json::to_writer_pretty(file, self)
+            .with_context(|| format!("Failed writing run summary file '{}'", path.display()))
+    }
 }
 
 impl SummaryOutput {
     /// Create a new `SummaryOutput` with `dir` as base dir and an extension fitting the
     /// [`SummaryFormat`]
     pub fn new(format: SummaryFormat, dir: &Path) -> Self {
+        let extension = match format {
+            SummaryFormat::Json | SummaryFormat::PrettyJson => "json",
+            SummaryFormat::Csv => "csv",
+            SummaryFormat::Tsv => "tsv",
+        };
         Self {
             format,
-            path: dir.join("summary.json"),
+            path: dir.join("summary").with_extension(extension),
         }
     }
 
@@ -468,3 +736,10 @@ impl SummaryOutput {
         File::create(&self.path).with_context(|| "Failed to create json summary file")
     }
 }
+
+/// Return the milliseconds since the unix epoch
+pub(crate) fn to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |duration| {
+        u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+    })
+}
@@ -5,7 +5,7 @@ pub mod logfile_parser;
 use std::ffi::OsString;
 use std::fmt::Display;
 use std::fs::File;
-use std::io::{stdout, BufRead, BufReader, Write};
+use std::io::{stderr, stdout, BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
 
@@ -21,6 +21,8 @@ use serde::{Deserialize, Serialize};
 use self::args::ToolArgs;
 use super::callgrind::parser::Parser;
 use super::dhat::logfile_parser::LogfileParser as DhatLogfileParser;
+use super::git;
+use super::message::MessageFormat;
 use super::meta::Metadata;
 use super::summary::ToolSummary;
 use crate::api::ExitWith;
@@ -208,6 +210,7 @@ impl ToolConfigs {
         options: &RunOptions,
         output_path: &ToolOutputPath,
     ) -> Result<Vec<ToolSummary>> {
+        let is_human = meta.args.message_format == MessageFormat::Human;
         let mut tool_summaries = vec![];
         for tool_config in self.0.iter().filter(|t| t.is_enabled) {
             let tool = tool_config.tool;
@@ -227,7 +230,14 @@ impl ToolConfigs {
             let log_path = output_path.to_log_output();
             log_path.init();
 
-            println!("{}", tool_summary_header(tool));
+            if tool.has_output_file() {
+                meta.git_baselines.load(&output_path)?;
+            }
+            meta.git_baselines.load(&log_path)?;
+
+            if is_human {
+                println!("{}", tool_summary_header(tool));
+            }
 
             let output = command.run(
                 tool_config.clone(),
@@ -237,13 +247,20 @@ impl ToolConfigs {
                 &output_path,
             )?;
 
+            if tool.has_output_file() {
+                meta.git_baselines.save(&output_path)?;
+            }
+            meta.git_baselines.save(&log_path)?;
+
             if let ValgrindTool::DHAT = tool {
                 let parser = DhatLogfileParser {
                     root_dir: meta.project_root.clone(),
                 };
                 let logfile_summaries = parser.parse(&log_path)?;
                 for logfile_summary in logfile_summaries {
-                    LogfileSummaryFormatter::print(&logfile_summary);
+                    if is_human {
+                        LogfileSummaryFormatter::print(&logfile_summary);
+                    }
 
                     tool_summary.summaries.push(ToolRunSummary {
                         command: logfile_summary.command.to_string_lossy().to_string(),
@@ -261,7 +278,9 @@ impl ToolConfigs {
                 };
                 let logfile_summaries = parser.parse(&log_path)?;
                 for logfile_summary in logfile_summaries {
-                    LogfileSummaryFormatter::print(&logfile_summary);
+                    if is_human {
+                        LogfileSummaryFormatter::print(&logfile_summary);
+                    }
                     let mut summary: IndexMap<String, String> =
                         logfile_summary.fields.iter().cloned().collect();
                     if !logfile_summary.body.is_empty() {
@@ -284,7 +303,11 @@ impl ToolConfigs {
             }
 
             output.dump_log(log::Level::Info);
-            log_path.dump_log(log::Level::Info, &mut stdout())?;
+            if is_human {
+                log_path.dump_log(log::Level::Info, &mut stdout())?;
+            } else {
+                log_path.dump_log(log::Level::Info, &mut stderr())?;
+            }
 
             tool_summaries.push(tool_summary);
         }
@@ -347,8 +370,12 @@ impl ToolOutputPath {
         let file_name = path.file_name().unwrap().to_string_lossy();
         let re = Regex::new(r"^(?<tool>.*?)[.](?<name>.*)[.](?<extension>out(\..*)?)$")
             .expect("Regex should compile");
+        // The default file name of valgrind like `callgrind.out.<pid>`
+        let re_valgrind = Regex::new(r"^(?<tool>[^.]+)[.](?<name>out)[.](?<extension>.+)$")
+            .expect("Regex should compile");
         let caps = re
             .captures(&file_name)
+            .or_else(|| re_valgrind.captures(&file_name))
             .ok_or_else(|| anyhow!("Illegal file name: {file_name}"))?;
 
         Ok(Self {
@@ -357,7 +384,7 @@ impl ToolOutputPath {
                 .ok_or_else(|| anyhow!("Illegal file name: {file_name}"))?
                 .as_str()
                 .try_into()
-                .unwrap(),
+                .with_context(|| format!("Illegal file name: {file_name}"))?,
             dir: path.parent().unwrap().to_owned(),
             extension: caps
                 .name("extension")
@@ -410,6 +437,75 @@ impl ToolOutputPath {
         }
     }
 
+    /// Remove all `*.old` output files
+    pub fn remove_old(&self) -> Result<()> {
+        for entry in glob(&format!("{}*.old", self.to_path().display()))
+            .expect("Reading glob patterns should succeed")
+            .map(Result::unwrap)
+        {
+            std::fs::remove_file(&entry)
+                .with_context(|| format!("Failed removing '{}'", entry.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Return the output path of the stored baseline in the directory `baseline_dir`
+    ///
+    /// The `baseline_dir` is relative to the [`git::DIR_NAME`] directory of the output directory.
+    pub fn to_baseline_output(&self, baseline_dir: &Path) -> Self {
+        Self {
+            tool: self.tool,
+            name: self.name.clone(),
+            extension: self.extension.clone(),
+            dir: self.dir.join(git::DIR_NAME).join(baseline_dir),
+        }
+    }
+
+    /// Copy all output files into the stored baseline in the directory `baseline_dir`
+    ///
+    /// The output files of a previously stored baseline are replaced.
+    pub fn save_baseline(&self, baseline_dir: &Path) -> Result<()> {
+        let baseline = self.to_baseline_output(baseline_dir);
+        std::fs::create_dir_all(&baseline.dir)
+            .with_context(|| format!("Failed creating directory '{}'", baseline.dir.display()))?;
+        for path in baseline.real_paths() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed removing '{}'", path.display()))?;
+        }
+        debug!("Saving baseline '{}'", baseline.dir.display());
+        for path in self.real_paths() {
+            let dest = baseline.dir.join(path.file_name().unwrap());
+            std::fs::copy(&path, &dest).with_context(|| {
+                format!(
+                    "Failed copying '{}' to '{}'",
+                    path.display(),
+                    dest.display()
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Replace the `*.old` output files with the stored baseline in the directory `baseline_dir`
+    pub fn load_baseline(&self, baseline_dir: &Path) -> Result<()> {
+        let baseline = self.to_baseline_output(baseline_dir);
+        self.remove_old()?;
+        debug!("Loading baseline '{}'", baseline.dir.display());
+        for path in baseline.real_paths() {
+            let mut file_name = path.file_name().unwrap().to_owned();
+            file_name.push(".old");
+            let dest = self.dir.join(file_name);
+            std::fs::copy(&path, &dest).with_context(|| {
+                format!(
+                    "Failed copying '{}' to '{}'",
+                    path.display(),
+                    dest.display()
+                )
+            })?;
+        }
+        Ok(())
+    }
+
     pub fn exists(&self) -> bool {
         self.to_path().exists()
     }
@@ -1,6 +1,6 @@
 use std::fs::File;
 
-use iai_callgrind_runner::runner::summary::BenchmarkSummary;
+use iai_callgrind_runner::runner::summary::{BenchmarkSummary, RunSummary};
 use schemars::schema_for;
 
 fn main() {
@@ -9,4 +9,9 @@ fn main() {
         &schema_for!(BenchmarkSummary),
     )
     .expect("Schema creation should be successful");
+    serde_json::to_writer_pretty(
+        File::create("run-summary.schema.json").unwrap(),
+        &schema_for!(RunSummary),
+    )
+    .expect("Schema creation should be successful");
 }
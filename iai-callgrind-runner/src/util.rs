@@ -213,6 +213,42 @@ pub fn factor_diff(new: u64, old: u64) -> f64 {
     }
 }
 
+/// Format the `cost` as percentage of the `total` with two decimal places
+///
+/// A `total` of zero has no meaningful percentage and is formatted as `-`.
+#[allow(clippy::cast_precision_loss)]
+pub fn format_pct(cost: u64, total: u64) -> String {
+    if total == 0 {
+        "-".to_owned()
+    } else {
+        format!("{:.2}%", cost as f64 / total as f64 * 100f64)
+    }
+}
+
+/// Format the `cost` or `N/A` if there is no `cost`
+pub fn format_cost(cost: Option<u64>) -> String {
+    cost.map_or_else(|| "N/A".to_owned(), |c| c.to_string())
+}
+
+/// Escape the characters with a special meaning in html text and attribute values
+pub fn escape_html(string: &str) -> String {
+    string
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escape the five predefined entities of xml in text and attribute values
+pub fn escape_xml(string: &str) -> String {
+    string
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
 pub fn make_relative<B, T>(base_dir: B, path: T) -> PathBuf
 where
     B: AsRef<Path>,
@@ -284,4 +320,40 @@ mod tests {
     fn test_factor_diff_eq(#[case] a: u64, #[case] b: u64, #[case] expected: f64) {
         assert_eq!(factor_diff(a, b), expected);
     }
+
+    #[rstest]
+    #[case::zero_total(0, 0, "-")]
+    #[case::zero(0, 10, "0.00%")]
+    #[case::half(5, 10, "50.00%")]
+    #[case::all(10, 10, "100.00%")]
+    #[case::rounded(1, 3, "33.33%")]
+    fn test_format_pct(#[case] cost: u64, #[case] total: u64, #[case] expected: &str) {
+        assert_eq!(format_pct(cost, total), expected);
+    }
+
+    #[rstest]
+    #[case::none(None, "N/A")]
+    #[case::zero(Some(0), "0")]
+    #[case::some(Some(123), "123")]
+    fn test_format_cost(#[case] cost: Option<u64>, #[case] expected: &str) {
+        assert_eq!(format_cost(cost), expected);
+    }
+
+    #[rstest]
+    #[case::plain("main", "main")]
+    #[case::generics("Vec<T>", "Vec&lt;T&gt;")]
+    #[case::ampersand("&str", "&amp;str")]
+    #[case::quotes("\"a\" 'b'", "&quot;a&quot; 'b'")]
+    fn test_escape_html(#[case] string: &str, #[case] expected: &str) {
+        assert_eq!(escape_html(string), expected);
+    }
+
+    #[rstest]
+    #[case::plain("main", "main")]
+    #[case::generics("Vec<T>", "Vec&lt;T&gt;")]
+    #[case::ampersand("&str", "&amp;str")]
+    #[case::quotes("\"a\" 'b'", "&quot;a&quot; &apos;b&apos;")]
+    fn test_escape_xml(#[case] string: &str, #[case] expected: &str) {
+        assert_eq!(escape_xml(string), expected);
+    }
 }
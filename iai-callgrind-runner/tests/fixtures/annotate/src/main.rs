@@ -0,0 +1,22 @@
+fn main() {
+    foo(1);
+    bar(2) + bar(3);
+}
+
+// Some lines without costs
+//
+//
+//
+fn foo(x: u64) -> u64 {
+    x + bar(x)
+}
+
+// More lines without costs
+//
+//
+//
+//
+//
+fn bar(x: u64) -> u64 {
+    x * 2
+}
@@ -1,8 +1,49 @@
 #[cfg(test)]
+mod test_annotate;
+#[cfg(test)]
+mod test_compare;
+#[cfg(test)]
+mod test_dot;
+#[cfg(test)]
+mod test_flamegraph;
+#[cfg(test)]
 mod test_flamegraph_parser;
 #[cfg(test)]
+mod test_function_diff;
+#[cfg(test)]
 mod test_hashmap_parser;
 #[cfg(test)]
+mod test_hot_functions;
+#[cfg(test)]
+mod test_hot_instructions;
+#[cfg(test)]
+mod test_pprof;
+#[cfg(test)]
 mod test_sentinel_parser;
 #[cfg(test)]
+mod test_speedscope;
+#[cfg(test)]
 mod test_summary_parser;
+
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::tool::ToolOutputPath;
+
+/// Return the costs of the `totals:` line of the callgrind output by event kind
+pub fn load_totals(output: &ToolOutputPath) -> Vec<(EventKind, u64)> {
+    let content = std::fs::read_to_string(output.to_path()).unwrap();
+    let mut event_kinds = vec![];
+    for line in content.lines() {
+        if let Some(events) = line.strip_prefix("events:") {
+            event_kinds = events
+                .split_ascii_whitespace()
+                .map(EventKind::from)
+                .collect();
+        } else if let Some(totals) = line.strip_prefix("totals:") {
+            return event_kinds
+                .into_iter()
+                .zip(totals.split_ascii_whitespace().map(|c| c.parse().unwrap()))
+                .collect();
+        }
+    }
+    panic!("No totals line found in '{output}'");
+}
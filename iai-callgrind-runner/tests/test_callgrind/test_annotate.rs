@@ -0,0 +1,100 @@
+use iai_callgrind_runner::api;
+use iai_callgrind_runner::runner::callgrind::annotate::Annotate;
+use iai_callgrind_runner::runner::callgrind::hashmap_parser::HashMapParser;
+use iai_callgrind_runner::runner::callgrind::parser::Parser;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use crate::common::Fixtures;
+
+#[rstest]
+#[case::calls(None, "valid.calls", api::AnnotateConfig::default())]
+#[case::calls_with_context(
+    None,
+    "valid.calls.context_1",
+    api::AnnotateConfig {
+        context: Some(1),
+        limit: Some(2),
+        ..Default::default()
+    }
+)]
+#[case::calls_with_old(
+    Some("valid.calls_old"),
+    "valid.calls.with_old",
+    api::AnnotateConfig::default()
+)]
+fn test_annotate_to_text(
+    #[case] old_fixture: Option<&str>,
+    #[case] expected: &str,
+    #[case] config: api::AnnotateConfig,
+) {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.out");
+    let map = parser.parse(&output).unwrap();
+    let old_map = old_fixture.map(|old| {
+        parser
+            .parse(&Fixtures::get_callgrind_output_path(format!(
+                "callgrind.out/callgrind.{old}.out"
+            )))
+            .unwrap()
+    });
+
+    let annotate = Annotate::new(config.into());
+    let report = annotate.annotate(
+        "bench".to_owned(),
+        &map,
+        old_map.as_ref(),
+        &Fixtures::get_path_of("annotate"),
+    );
+
+    let expected =
+        Fixtures::load_stacks(format!("callgrind.out/callgrind.{expected}.exp_annotate"));
+    assert_eq!(
+        annotate.to_text(&report).lines().collect::<Vec<&str>>(),
+        expected
+    );
+}
+
+#[test]
+fn test_annotate_to_html() {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.out");
+    let map = parser.parse(&output).unwrap();
+
+    let annotate = Annotate::new(api::AnnotateConfig::default().into());
+    let report = annotate.annotate(
+        "bench".to_owned(),
+        &map,
+        None,
+        &Fixtures::get_path_of("annotate"),
+    );
+    let html = annotate.to_html(&report);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    // The whole source file is shown
+    assert_eq!(html.matches("<tr id=\"f0-l").count(), 22);
+    // The hottest lines are highlighted
+    assert!(html.contains(
+        "<tr id=\"f0-l20\" class=\"hot\" style=\"background-color: rgba(255, 80, 0, 0.600)\">"
+    ));
+    assert!(html.contains("<a href=\"#f0-l20\">src/main.rs:20</a>"));
+    assert!(html.contains("<pre>fn bar(x: u64) -&gt; u64 {</pre>"));
+}
+
+#[test]
+fn test_annotate_when_source_not_available() {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.out");
+    let map = parser.parse(&output).unwrap();
+
+    let annotate = Annotate::new(api::AnnotateConfig::default().into());
+    let report = annotate.annotate("bench".to_owned(), &map, None, &Fixtures::get_path());
+
+    assert_eq!(report.files.len(), 1);
+    assert_eq!(report.files[0].source, None);
+    assert_eq!(
+        report.files[0].lines.keys().copied().collect::<Vec<u64>>(),
+        vec![1, 10, 20]
+    );
+    assert_eq!(report.files[0].total.new, Some(125));
+}
@@ -0,0 +1,110 @@
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::callgrind::parser::{Parser, Sentinel};
+use iai_callgrind_runner::runner::callgrind::sentinel_parser::SentinelParser;
+use iai_callgrind_runner::runner::callgrind::summary_parser::SummaryParser;
+use iai_callgrind_runner::runner::cli::compare;
+use iai_callgrind_runner::runner::summary::CostsSummary;
+use iai_callgrind_runner::runner::tool::ToolOutputPath;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use crate::common::Fixtures;
+
+const NEW: &str = "callgrind.out/callgrind.valid.calls.out";
+const OLD: &str = "callgrind.out/callgrind.valid.calls_old.out";
+
+#[rstest]
+#[case::total(None)]
+#[case::sentinel(Some("main"))]
+fn test_compare(#[case] sentinel: Option<&str>) {
+    let sentinel = sentinel.map(Sentinel::new);
+    let new = Fixtures::get_callgrind_output_path(NEW);
+    let old = Fixtures::get_callgrind_output_path(OLD);
+    let expected = if let Some(sentinel) = &sentinel {
+        let parser = SentinelParser::new(sentinel);
+        CostsSummary::new(
+            &parser.parse(&new).unwrap(),
+            Some(&parser.parse(&old).unwrap()),
+        )
+    } else {
+        CostsSummary::new(
+            &SummaryParser.parse(&new).unwrap(),
+            Some(&SummaryParser.parse(&old).unwrap()),
+        )
+    };
+
+    let actual = compare(
+        &Fixtures::get_path_of(NEW),
+        &Fixtures::get_path_of(OLD),
+        sentinel.as_ref(),
+    )
+    .unwrap();
+
+    assert_eq!(actual, expected);
+    let ir = actual.diff_by_kind(&EventKind::Ir).unwrap();
+    assert!(ir.new.is_some() && ir.old.is_some());
+}
+
+#[rstest]
+#[case::total(None)]
+#[case::sentinel(Some("main"))]
+#[case::sentinel_defined_in_cfn(Some("foo"))]
+fn test_compare_when_compressed(#[case] sentinel: Option<&str>) {
+    let sentinel = sentinel.map(Sentinel::new);
+    let expected = compare(
+        &Fixtures::get_path_of(NEW),
+        &Fixtures::get_path_of(OLD),
+        sentinel.as_ref(),
+    )
+    .unwrap();
+
+    let actual = compare(
+        &Fixtures::get_path_of("callgrind.out/callgrind.valid.calls.compressed.out"),
+        &Fixtures::get_path_of("callgrind.out/callgrind.valid.calls_old.compressed.out"),
+        sentinel.as_ref(),
+    )
+    .unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_compare_with_valgrind_file_names() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let new = temp_dir.path().join("callgrind.out.1234");
+    let old = temp_dir.path().join("callgrind.out.1000");
+    std::fs::copy(Fixtures::get_path_of(NEW), &new).unwrap();
+    std::fs::copy(Fixtures::get_path_of(OLD), &old).unwrap();
+
+    let output_path = ToolOutputPath::from_existing(&new).unwrap();
+    assert_eq!(output_path.to_path(), new);
+
+    let expected = compare(
+        &Fixtures::get_path_of(NEW),
+        &Fixtures::get_path_of(OLD),
+        None,
+    )
+    .unwrap();
+    assert_eq!(compare(&new, &old, None).unwrap(), expected);
+}
+
+#[rstest]
+#[case::unknown_tool("unknown.bench.out")]
+#[case::no_tool("my_output")]
+fn test_compare_when_illegal_file_name_then_error(#[case] file_name: &str) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join(file_name);
+    std::fs::copy(Fixtures::get_path_of(NEW), &path).unwrap();
+
+    assert!(compare(&path, &Fixtures::get_path_of(OLD), None).is_err());
+}
+
+#[test]
+fn test_compare_when_sentinel_not_found_then_error() {
+    assert!(compare(
+        &Fixtures::get_path_of(NEW),
+        &Fixtures::get_path_of(OLD),
+        Some(&Sentinel::new("does_not_exist")),
+    )
+    .is_err());
+}
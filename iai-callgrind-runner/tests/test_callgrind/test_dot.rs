@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use iai_callgrind_runner::api::{self, EventKind};
+use iai_callgrind_runner::runner::callgrind::dot::{Config, Dot};
+use iai_callgrind_runner::runner::callgrind::hashmap_parser::HashMapParser;
+use iai_callgrind_runner::runner::callgrind::parser::{Parser, Sentinel};
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use crate::common::{get_project_root, Fixtures};
+
+#[rstest]
+#[case::calls("valid.calls", None, "valid.calls", None, api::DotConfig::default())]
+#[case::calls_with_node_threshold(
+    "valid.calls",
+    None,
+    "valid.calls.node_threshold_50",
+    None,
+    api::DotConfig {
+        node_threshold: Some(50f64),
+        ..Default::default()
+    }
+)]
+#[case::calls_with_edge_threshold(
+    "valid.calls",
+    None,
+    "valid.calls.edge_threshold_20",
+    None,
+    api::DotConfig {
+        edge_threshold: Some(20f64),
+        ..Default::default()
+    }
+)]
+#[case::calls_differential(
+    "valid.calls",
+    Some("valid.calls_old"),
+    "valid.calls.differential",
+    None,
+    api::DotConfig {
+        differential: Some(true),
+        ..Default::default()
+    }
+)]
+#[case::when_entry_point(
+    "when_entry_point",
+    None,
+    "when_entry_point",
+    Some(Sentinel::new("benchmark_tests_exit::main")),
+    api::DotConfig::default()
+)]
+fn test_dot_create(
+    #[case] fixture: &str,
+    #[case] old_fixture: Option<&str>,
+    #[case] expected: &str,
+    #[case] sentinel: Option<Sentinel>,
+    #[case] config: api::DotConfig,
+) {
+    let parser = HashMapParser {
+        sentinel,
+        project_root: get_project_root(),
+    };
+    let output =
+        Fixtures::get_callgrind_output_path(format!("callgrind.out/callgrind.{fixture}.out"));
+    let map = parser.parse(&output).unwrap();
+    let old_map = old_fixture.map(|old| {
+        parser
+            .parse(&Fixtures::get_callgrind_output_path(format!(
+                "callgrind.out/callgrind.{old}.out"
+            )))
+            .unwrap()
+    });
+
+    let dot = Dot::new(config.into())
+        .create(&map, old_map.as_ref())
+        .unwrap();
+
+    let expected = Fixtures::load_stacks(format!("callgrind.out/callgrind.{expected}.exp_dot"));
+    assert_eq!(dot.lines().collect::<Vec<&str>>(), expected);
+}
+
+/// Return the inclusive percentage of each node and the edges of the `dot` graph
+fn parse_graph(dot: &str) -> (HashMap<&str, f64>, Vec<(&str, &str)>) {
+    let mut nodes = HashMap::new();
+    let mut edges = vec![];
+    for line in dot.lines().map(str::trim) {
+        if let Some((node, rest)) = line.split_once(" [label=\"") {
+            match node.split_once(" -> ") {
+                Some((caller, callee)) => edges.push((caller, callee)),
+                None => {
+                    let inclusive = rest.split("\\n").nth(1).unwrap();
+                    nodes.insert(node, inclusive.trim_end_matches('%').parse().unwrap());
+                }
+            }
+        }
+    }
+    (nodes, edges)
+}
+
+#[rstest]
+#[case::calls("valid.calls", None)]
+#[case::recursion("valid.recursion", None)]
+#[case::when_entry_point("when_entry_point", Some(Sentinel::new("benchmark_tests_exit::main")))]
+#[case::no_entry_point("no_entry_point", None)]
+fn test_dot_create_callee_never_exceeds_only_caller(
+    #[case] fixture: &str,
+    #[case] sentinel: Option<Sentinel>,
+) {
+    let parser = HashMapParser {
+        sentinel,
+        project_root: get_project_root(),
+    };
+    let output =
+        Fixtures::get_callgrind_output_path(format!("callgrind.out/callgrind.{fixture}.out"));
+    let map = parser.parse(&output).unwrap();
+
+    let dot = Dot::new(Config {
+        node_threshold: 0f64,
+        edge_threshold: 0f64,
+        ..Default::default()
+    })
+    .create(&map, None)
+    .unwrap();
+
+    let (nodes, edges) = parse_graph(&dot);
+    let mut callers: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (caller, callee) in edges.into_iter().filter(|(a, b)| a != b) {
+        callers.entry(callee).or_default().push(caller);
+    }
+    for (callee, callers) in callers.into_iter().filter(|(_, c)| c.len() == 1) {
+        assert!(
+            nodes[callee] <= nodes[callers[0]],
+            "Callee {callee} ({}%) exceeds its only caller {} ({}%)",
+            nodes[callee],
+            callers[0],
+            nodes[callers[0]]
+        );
+    }
+}
+
+#[test]
+fn test_dot_create_when_missing_event_kind() {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.out");
+    let map = parser.parse(&output).unwrap();
+
+    let result = Dot::new(Config {
+        event_kind: EventKind::Dr,
+        ..Default::default()
+    })
+    .create(&map, None);
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Failed creating the DOT call graph: Missing event type 'Dr'"
+    );
+}
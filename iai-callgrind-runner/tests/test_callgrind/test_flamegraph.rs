@@ -0,0 +1,289 @@
+use std::path::{Path, PathBuf};
+
+use iai_callgrind_runner::api::{self, EventKind, FlamegraphKind};
+use iai_callgrind_runner::runner::callgrind::flamegraph::Flamegraph;
+use iai_callgrind_runner::runner::cli::{flamegraph, FlamegraphArgs};
+use iai_callgrind_runner::runner::tool::{ToolOutputPath, ValgrindTool};
+use rstest::rstest;
+
+use crate::common::Fixtures;
+
+/// Copy the fixture `name` into `dir` as `file_name` and return the path of the copy
+fn copy_fixture(name: &str, dir: &Path, file_name: &str) -> PathBuf {
+    let path = dir.join(file_name);
+    std::fs::copy(Fixtures::get_path_of(name), &path).unwrap();
+    path
+}
+
+/// Create the callgrind output `callgrind.bench_fn.out` in `dir` from the fixture `name` and the
+/// old output from the fixture `old` if present
+fn output_path(dir: &Path, name: &str, old: Option<&str>) -> ToolOutputPath {
+    let output = ToolOutputPath::new(ValgrindTool::Callgrind, dir, "bench", "bench_fn");
+    std::fs::create_dir_all(&output.dir).unwrap();
+    std::fs::copy(Fixtures::get_path_of(name), output.to_path()).unwrap();
+    if let Some(old) = old {
+        std::fs::copy(Fixtures::get_path_of(old), output.to_old_output().to_path()).unwrap();
+    }
+    output
+}
+
+fn folded_flamegraph(kind: FlamegraphKind, folded: bool) -> Flamegraph {
+    Flamegraph::new(
+        "bench::bench_fn".to_owned(),
+        api::FlamegraphConfig {
+            kind: Some(kind),
+            event_kinds: Some(vec![EventKind::Ir]),
+            folded: Some(folded),
+            ..Default::default()
+        }
+        .into(),
+    )
+}
+
+fn args(
+    path: PathBuf,
+    old: Option<PathBuf>,
+    event_kinds: Option<Vec<EventKind>>,
+) -> FlamegraphArgs {
+    FlamegraphArgs {
+        path,
+        old,
+        event_kinds,
+        sentinel: None,
+        title: None,
+    }
+}
+
+#[test]
+fn test_flamegraph_regular() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = copy_fixture(
+        "callgrind.out/callgrind.valid.calls.out",
+        temp_dir.path(),
+        "callgrind.out.1234",
+    );
+
+    let summaries = flamegraph(&args(path, None, None), temp_dir.path()).unwrap();
+
+    assert_eq!(summaries.len(), 1);
+    let summary = &summaries[0];
+    assert_eq!(summary.event_kind, EventKind::Ir);
+    let regular_path = summary.regular_path.as_ref().unwrap();
+    assert_eq!(regular_path, &temp_dir.path().join("callgrind.out.Ir.svg"));
+    let svg = std::fs::read_to_string(regular_path).unwrap();
+    assert!(svg.contains("callgrind.out.1234"));
+    assert_eq!(summary.diff_path, None);
+}
+
+#[test]
+fn test_flamegraph_differential() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = copy_fixture(
+        "callgrind.out/callgrind.valid.calls.out",
+        temp_dir.path(),
+        "callgrind.new.out",
+    );
+    let old = copy_fixture(
+        "callgrind.out/callgrind.valid.calls_old.out",
+        temp_dir.path(),
+        "callgrind.out.1000",
+    );
+
+    let summaries = flamegraph(&args(path, Some(old), None), temp_dir.path()).unwrap();
+
+    assert_eq!(summaries.len(), 1);
+    for summary in &summaries {
+        assert!(summary.regular_path.as_ref().unwrap().exists());
+        let diff_path = summary.diff_path.as_ref().unwrap();
+        assert_eq!(
+            diff_path,
+            &temp_dir
+                .path()
+                .join(format!("callgrind.new.{}.diff.svg", summary.event_kind))
+        );
+        let svg = std::fs::read_to_string(diff_path).unwrap();
+        assert!(svg.contains("compared with callgrind.out.1000"));
+    }
+}
+
+#[test]
+fn test_flamegraph_with_sentinel() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = copy_fixture(
+        "callgrind.out/callgrind.valid.calls.out",
+        temp_dir.path(),
+        "callgrind.out.1234",
+    );
+    let mut args = args(path, None, None);
+    args.sentinel = Some("main".to_owned());
+    args.title = Some("my title".to_owned());
+
+    let summaries = flamegraph(&args, temp_dir.path()).unwrap();
+
+    let svg = std::fs::read_to_string(summaries[0].regular_path.as_ref().unwrap()).unwrap();
+    assert!(svg.contains("my title"));
+}
+
+#[test]
+fn test_flamegraph_when_compressed() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = copy_fixture(
+        "callgrind.out/callgrind.valid.calls.compressed.out",
+        temp_dir.path(),
+        "callgrind.out.1234",
+    );
+    let old = copy_fixture(
+        "callgrind.out/callgrind.valid.calls_old.compressed.out",
+        temp_dir.path(),
+        "callgrind.out.1000",
+    );
+
+    let summaries = flamegraph(&args(path, Some(old), None), temp_dir.path()).unwrap();
+
+    assert_eq!(summaries.len(), 1);
+    let summary = &summaries[0];
+    let svg = std::fs::read_to_string(summary.regular_path.as_ref().unwrap()).unwrap();
+    for stack in ["src/main.rs:main", "src/main.rs:foo", "src/main.rs:bar"] {
+        assert!(svg.contains(stack), "Missing '{stack}' in flamegraph");
+    }
+    assert!(summary.diff_path.as_ref().unwrap().exists());
+}
+
+#[test]
+fn test_flamegraph_when_file_does_not_exist_then_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    assert!(flamegraph(
+        &args(temp_dir.path().join("callgrind.out.1234"), None, None),
+        temp_dir.path()
+    )
+    .is_err());
+}
+
+#[rstest]
+#[case::regular(FlamegraphKind::Regular)]
+#[case::all(FlamegraphKind::All)]
+fn test_flamegraph_folded_without_old(#[case] kind: FlamegraphKind) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output = output_path(
+        temp_dir.path(),
+        "callgrind.out/callgrind.valid.calls.out",
+        None,
+    );
+
+    let summaries = folded_flamegraph(kind, true)
+        .create(&output, None, temp_dir.path())
+        .unwrap();
+
+    assert_eq!(summaries.len(), 1);
+    let summary = &summaries[0];
+    let folded_path = summary.folded_path.as_ref().unwrap();
+    assert_eq!(
+        folded_path,
+        &output.dir.join("callgrind.bench_fn.Ir.folded")
+    );
+    assert_eq!(
+        std::fs::read_to_string(folded_path).unwrap(),
+        "src/main.rs:main 5\nsrc/main.rs:main;src/main.rs:foo \
+         60\nsrc/main.rs:main;src/main.rs:foo;src/main.rs:bar \
+         20\nsrc/main.rs:main;src/main.rs:bar 40\n"
+    );
+    assert_eq!(summary.old_folded_path, None);
+    assert!(!output.dir.join("callgrind.bench_fn.Ir.old.folded").exists());
+}
+
+#[rstest]
+#[case::regular(FlamegraphKind::Regular)]
+#[case::all(FlamegraphKind::All)]
+fn test_flamegraph_folded_with_old(#[case] kind: FlamegraphKind) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output = output_path(
+        temp_dir.path(),
+        "callgrind.out/callgrind.valid.calls.out",
+        Some("callgrind.out/callgrind.valid.calls_old.out"),
+    );
+
+    let summaries = folded_flamegraph(kind, true)
+        .create(&output, None, temp_dir.path())
+        .unwrap();
+
+    assert_eq!(summaries.len(), 1);
+    let summary = &summaries[0];
+    assert_eq!(
+        summary.folded_path.as_ref().unwrap(),
+        &output.dir.join("callgrind.bench_fn.Ir.folded")
+    );
+    let old_folded_path = summary.old_folded_path.as_ref().unwrap();
+    assert_eq!(
+        old_folded_path,
+        &output.dir.join("callgrind.bench_fn.Ir.old.folded")
+    );
+    assert_eq!(
+        std::fs::read_to_string(old_folded_path).unwrap(),
+        "src/main.rs:main 5\nsrc/main.rs:main;src/main.rs:foo \
+         30\nsrc/main.rs:main;src/main.rs:foo;src/main.rs:bar \
+         20\nsrc/main.rs:main;src/main.rs:baz 10\n"
+    );
+}
+
+#[test]
+fn test_flamegraph_when_not_folded_then_no_folded_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output = output_path(
+        temp_dir.path(),
+        "callgrind.out/callgrind.valid.calls.out",
+        Some("callgrind.out/callgrind.valid.calls_old.out"),
+    );
+
+    let summaries = folded_flamegraph(FlamegraphKind::All, false)
+        .create(&output, None, temp_dir.path())
+        .unwrap();
+
+    assert_eq!(summaries[0].folded_path, None);
+    assert_eq!(summaries[0].old_folded_path, None);
+    assert!(!output.dir.join("callgrind.bench_fn.Ir.folded").exists());
+    assert!(!output.dir.join("callgrind.bench_fn.Ir.old.folded").exists());
+}
+
+#[test]
+fn test_flamegraph_old_svg_is_created_from_old_output() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output = output_path(
+        temp_dir.path(),
+        "callgrind.out/callgrind.valid.calls.out",
+        Some("callgrind.out/callgrind.valid.calls_old.out"),
+    );
+    let old_svg = output.dir.join("callgrind.bench_fn.Ir.old.svg");
+    std::fs::write(output.dir.join("callgrind.bench_fn.Ir.svg"), "previous run").unwrap();
+
+    let summaries = folded_flamegraph(FlamegraphKind::Regular, false)
+        .create(&output, None, temp_dir.path())
+        .unwrap();
+
+    assert_eq!(summaries[0].old_path.as_ref(), Some(&old_svg));
+    let svg = std::fs::read_to_string(&old_svg).unwrap();
+    assert!(!svg.contains("previous run"));
+    assert!(svg.contains("src/main.rs:baz"));
+}
+
+#[test]
+fn test_flamegraph_when_no_old_output_then_stale_svgs_removed() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output = output_path(
+        temp_dir.path(),
+        "callgrind.out/callgrind.valid.calls.out",
+        None,
+    );
+    let old_svg = output.dir.join("callgrind.bench_fn.Ir.old.svg");
+    let diff_svg = output.dir.join("callgrind.bench_fn.Ir.diff.svg");
+    std::fs::write(&old_svg, "stale").unwrap();
+    std::fs::write(&diff_svg, "stale").unwrap();
+
+    let summaries = folded_flamegraph(FlamegraphKind::All, false)
+        .create(&output, None, temp_dir.path())
+        .unwrap();
+
+    assert_eq!(summaries[0].old_path, None);
+    assert_eq!(summaries[0].diff_path, None);
+    assert!(!old_svg.exists());
+    assert!(!diff_svg.exists());
+}
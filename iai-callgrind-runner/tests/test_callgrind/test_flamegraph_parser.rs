@@ -1,22 +1,48 @@
+use std::collections::{HashMap, HashSet};
+
 use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::callgrind::call_graph::{to_key, to_source, CallGraph};
 use iai_callgrind_runner::runner::callgrind::flamegraph_parser::FlamegraphParser;
+use iai_callgrind_runner::runner::callgrind::hashmap_parser::HashMapParser;
 use iai_callgrind_runner::runner::callgrind::parser::{Parser, Sentinel};
 use rstest::rstest;
 
+use super::load_totals;
 use crate::common::{get_project_root, Fixtures};
 
+// The expected stacks of the small `valid.*` fixtures are derived by hand from the call graph of
+// the callgrind output. The callgrind outputs of `when_entry_point` and `no_entry_point` are from
+// real benchmark runs and their expected stacks were written with `to_stack_format`, so they only
+// guard against unintended changes. Their costs are verified independently by
+// `test_flamegraph_parser_totals` against the `totals:` line and by
+// `test_flamegraph_parser_inclusive_costs` against the inclusive costs of each function in the
+// callgrind output.
 #[rstest]
-#[case::when_entry_point("when_entry_point", Some(Sentinel::new("benchmark_tests_exit::main")))]
-#[case::no_entry_point("no_entry_point", None)]
-fn test_flamegraph_parser(#[case] fixture: &str, #[case] sentinel: Option<Sentinel>) {
+#[case::when_entry_point(
+    "when_entry_point",
+    "when_entry_point",
+    Some(Sentinel::new("benchmark_tests_exit::main")),
+    None
+)]
+#[case::no_entry_point("no_entry_point", "no_entry_point", None, None)]
+#[case::calls("valid.calls", "valid.calls", None, None)]
+#[case::calls_with_max_depth("valid.calls", "valid.calls.max_depth_2", None, Some(2))]
+#[case::recursion("valid.recursion", "valid.recursion", None, None)]
+#[case::cycle("valid.cycle", "valid.cycle", None, None)]
+fn test_flamegraph_parser(
+    #[case] fixture: &str,
+    #[case] expected: &str,
+    #[case] sentinel: Option<Sentinel>,
+    #[case] max_depth: Option<usize>,
+) {
     let output =
         Fixtures::get_callgrind_output_path(format!("callgrind.out/callgrind.{fixture}.out"));
     let expected_stacks =
-        Fixtures::load_stacks(format!("callgrind.out/callgrind.{fixture}.exp_stacks"));
+        Fixtures::load_stacks(format!("callgrind.out/callgrind.{expected}.exp_stacks"));
     let parser = FlamegraphParser::new(sentinel.as_ref(), get_project_root());
 
     let result = parser.parse(&output).unwrap();
-    let stacks = result.to_stack_format(&EventKind::Ir).unwrap();
+    let stacks = result.to_stack_format(&EventKind::Ir, max_depth).unwrap();
 
     assert_eq!(stacks.len(), expected_stacks.len());
     // Assert line by line or else the output on error is unreadable. Also, provide an additional
@@ -42,3 +68,108 @@ fn test_flamegraph_parser(#[case] fixture: &str, #[case] sentinel: Option<Sentin
 
     assert!(!failed);
 }
+
+// The `when_entry_point` output was recorded with `--toggle-collect` of the entry point, so its
+// totals are the costs of the entry point
+#[rstest]
+#[case::when_entry_point(
+    "when_entry_point",
+    Some(Sentinel::new("benchmark_tests_exit::main")),
+    None
+)]
+#[case::no_entry_point("no_entry_point", None, None)]
+#[case::calls("valid.calls", None, None)]
+#[case::calls_with_max_depth("valid.calls", None, Some(2))]
+#[case::recursion("valid.recursion", None, None)]
+#[case::cycle("valid.cycle", None, None)]
+fn test_flamegraph_parser_totals(
+    #[case] fixture: &str,
+    #[case] sentinel: Option<Sentinel>,
+    #[case] max_depth: Option<usize>,
+) {
+    let output =
+        Fixtures::get_callgrind_output_path(format!("callgrind.out/callgrind.{fixture}.out"));
+    let result = FlamegraphParser::new(sentinel.as_ref(), get_project_root())
+        .parse(&output)
+        .unwrap();
+
+    for (event_kind, total) in load_totals(&output) {
+        let sum: u64 = result
+            .to_stack_format(&event_kind, max_depth)
+            .unwrap()
+            .iter()
+            .map(|stack| stack.rsplit_once(' ').unwrap().1.parse::<u64>().unwrap())
+            .sum();
+        assert_eq!(sum, total, "Sum of the stacks of event kind {event_kind}");
+    }
+}
+
+// An independent check of the split of the costs between the stacks: The stacks containing a
+// function have to add up to the inclusive costs of the function in the callgrind output. These
+// are the self costs and the costs of the calls to other functions summed over all parts of the
+// function. Each stack is rounded on its own, so the sum may differ by the number of stacks.
+// Functions within a cycle appear more than once in a stack and are left out.
+#[rstest]
+#[case::calls("valid.calls")]
+#[case::recursion("valid.recursion")]
+#[case::no_entry_point("no_entry_point")]
+#[case::when_entry_point("when_entry_point")]
+fn test_flamegraph_parser_inclusive_costs(#[case] fixture: &str) {
+    let output =
+        Fixtures::get_callgrind_output_path(format!("callgrind.out/callgrind.{fixture}.out"));
+    let map = HashMapParser {
+        sentinel: None,
+        project_root: get_project_root(),
+    }
+    .parse(&output)
+    .unwrap();
+    let call_graph = CallGraph::new(&map, &[EventKind::Ir]).unwrap();
+
+    let mut expected: HashMap<String, u64> = HashMap::new();
+    for (id, value) in map.iter() {
+        let key = to_key(id);
+        let label = to_source(call_graph.id(&key).unwrap());
+        let cost = expected.entry(label).or_default();
+        *cost += value.self_costs.cost_by_kind(&EventKind::Ir).unwrap();
+        for (callee, call) in &value.calls {
+            if to_key(callee) != key {
+                *cost += call.costs.cost_by_kind(&EventKind::Ir).unwrap();
+            }
+        }
+    }
+
+    let stacks = FlamegraphParser::new(None, get_project_root())
+        .parse(&output)
+        .unwrap()
+        .to_stack_format(&EventKind::Ir, None)
+        .unwrap();
+    let mut actual: HashMap<&str, (u64, u64)> = HashMap::new();
+    let mut cycles: HashSet<&str> = HashSet::new();
+    for stack in &stacks {
+        let (frames, cost) = stack.rsplit_once(' ').unwrap();
+        let cost = cost.parse::<u64>().unwrap();
+        let mut seen = HashSet::new();
+        for frame in frames.split(';') {
+            if seen.insert(frame) {
+                let (sum, count) = actual.entry(frame).or_default();
+                *sum += cost;
+                *count += 1;
+            } else {
+                cycles.insert(frame);
+            }
+        }
+    }
+
+    assert!(!actual.is_empty());
+    for (label, (sum, count)) in actual {
+        if cycles.contains(label) {
+            continue;
+        }
+        let expected = expected[label];
+        assert!(
+            sum.abs_diff(expected) <= count,
+            "The stacks containing '{label}' add up to {sum} but the inclusive costs are \
+             {expected}"
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::callgrind::function_diff::{to_markdown, Config, FunctionDiff};
+use iai_callgrind_runner::runner::callgrind::hashmap_parser::HashMapParser;
+use iai_callgrind_runner::runner::callgrind::parser::Parser;
+use iai_callgrind_runner::runner::summary::{FunctionDiffKind, FunctionDiffReport};
+use pretty_assertions::assert_eq;
+
+use crate::common::Fixtures;
+
+fn create_report() -> FunctionDiffReport {
+    let parser = HashMapParser::default();
+    let map = parser
+        .parse(&Fixtures::get_callgrind_output_path(
+            "callgrind.out/callgrind.valid.calls.out",
+        ))
+        .unwrap();
+    let old_map = parser
+        .parse(&Fixtures::get_callgrind_output_path(
+            "callgrind.out/callgrind.valid.calls_old.out",
+        ))
+        .unwrap();
+
+    FunctionDiff::new(Config {
+        event_kinds: vec![EventKind::Ir],
+        limit: 10,
+    })
+    .diff("bench".to_owned(), &map, &old_map)
+}
+
+#[test]
+fn test_function_diff_sorted_by_absolute_delta() {
+    let report = create_report();
+    assert_eq!(report.sections.len(), 1);
+
+    let actual = report.sections[0]
+        .functions
+        .iter()
+        .map(|f| {
+            (
+                f.function.as_str(),
+                f.kind,
+                f.inclusive.delta(),
+                f.exclusive.delta(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let expected = vec![
+        ("main", FunctionDiffKind::Changed, 60, 0),
+        ("bar", FunctionDiffKind::Changed, 40, 40),
+        ("foo", FunctionDiffKind::Changed, 30, 30),
+        ("baz", FunctionDiffKind::Disappeared, -10, -10),
+    ];
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_function_diff_when_same_then_empty() {
+    let parser = HashMapParser::default();
+    let map = parser
+        .parse(&Fixtures::get_callgrind_output_path(
+            "callgrind.out/callgrind.valid.calls.out",
+        ))
+        .unwrap();
+
+    let report = FunctionDiff::new(Config {
+        event_kinds: vec![EventKind::Ir],
+        limit: 10,
+    })
+    .diff("bench".to_owned(), &map, &map);
+
+    assert!(report.sections[0].functions.is_empty());
+}
+
+#[test]
+fn test_function_diff_markdown() {
+    let report = create_report();
+    let markdown = to_markdown(&report).unwrap();
+
+    let expected = "# Function diff: bench
+
+## Ir
+
+| Status | Function | New | Old | Change | Self New | Self Old | Self Change |
+|---|---|--:|--:|--:|--:|--:|--:|
+| Changed | `main` | 125 | 65 | +60 (+92.3077%) | 5 | 5 | +0 (+0.00000%) |
+| Changed | `bar` | 60 | 20 | +40 (+200.000%) | 60 | 20 | +40 (+200.000%) |
+| Changed | `foo` | 80 | 50 | +30 (+60.0000%) | 60 | 30 | +30 (+100.000%) |
+| Disappeared | `baz` | N/A | 10 | -10 | N/A | 10 | -10 |
+";
+    assert_eq!(markdown, expected);
+    assert_eq!(
+        report.sections[0].functions[0].file,
+        Some(PathBuf::from("src/main.rs"))
+    );
+}
@@ -37,6 +37,35 @@ fn test_valid_just_main() {
     assert_eq!(actual_map, expected_map);
 }
 
+#[rstest]
+#[case::not_a_cost_line("malformed_line", "this is not a cost line")]
+#[case::calls_without_cfn("missing_cfn", "calls=1 10")]
+#[case::costs_without_fn("missing_fn", "1 5")]
+fn test_when_malformed_line_then_should_return_error(#[case] fixture: &str, #[case] line: &str) {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path(format!(
+        "callgrind.out/callgrind.invalid.{fixture}.out"
+    ));
+    assert_parse_error(
+        &output.to_path(),
+        parser.parse(&output),
+        &format!("Malformed line: '{line}'"),
+    );
+}
+
+#[test]
+fn test_compressed_is_same_as_uncompressed() {
+    let parser = HashMapParser::default();
+    let compressed =
+        Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.compressed.out");
+    let uncompressed =
+        Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.out");
+
+    let actual_map = parser.parse(&compressed).unwrap();
+
+    assert_eq!(actual_map, parser.parse(&uncompressed).unwrap());
+}
+
 #[rstest]
 #[case::no_summary_and_totals("callgrind.out/callgrind.no_records.no_summary_and_totals.out")]
 #[case::summary_and_totals("callgrind.out/callgrind.no_records.with_summary_and_totals.out")]
@@ -0,0 +1,95 @@
+use iai_callgrind_runner::api::{EventKind, HotFunctionsConfig};
+use iai_callgrind_runner::runner::callgrind::hashmap_parser::HashMapParser;
+use iai_callgrind_runner::runner::callgrind::hot_functions::{Config, HotFunctions};
+use iai_callgrind_runner::runner::callgrind::parser::Parser;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use crate::common::Fixtures;
+
+fn summarize(limit: usize) -> Vec<(String, String, u64)> {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.out");
+    let map = parser.parse(&output).unwrap();
+
+    let summary = HotFunctions::new(Config {
+        limit,
+        event_kind: EventKind::Ir,
+    })
+    .summarize(&map, None);
+
+    summary
+        .inclusive
+        .into_iter()
+        .map(|f| ("inclusive".to_owned(), f.function, f.costs.new.unwrap()))
+        .chain(
+            summary
+                .exclusive
+                .into_iter()
+                .map(|f| ("exclusive".to_owned(), f.function, f.costs.new.unwrap())),
+        )
+        .collect()
+}
+
+#[test]
+fn test_hot_functions_all() {
+    let expected = [
+        ("inclusive", "main", 125),
+        ("inclusive", "foo", 80),
+        ("inclusive", "bar", 60),
+        ("exclusive", "bar", 60),
+        ("exclusive", "foo", 60),
+        ("exclusive", "main", 5),
+    ]
+    .into_iter()
+    .map(|(k, f, c)| (k.to_owned(), f.to_owned(), c))
+    .collect::<Vec<_>>();
+
+    assert_eq!(summarize(10), expected);
+}
+
+#[test]
+fn test_hot_functions_with_limit() {
+    let expected = [
+        ("inclusive", "main", 125),
+        ("inclusive", "foo", 80),
+        ("exclusive", "bar", 60),
+        ("exclusive", "foo", 60),
+    ]
+    .into_iter()
+    .map(|(k, f, c)| (k.to_owned(), f.to_owned(), c))
+    .collect::<Vec<_>>();
+
+    assert_eq!(summarize(2), expected);
+}
+
+#[rstest]
+#[case::none(None, None, None)]
+#[case::only_cli(None, Some(5), Some(5))]
+#[case::only_config(Some(3), None, Some(3))]
+#[case::cli_takes_precedence(Some(3), Some(5), Some(5))]
+#[case::zero_disables(Some(3), Some(0), None)]
+fn test_hot_functions_config(
+    #[case] config_limit: Option<usize>,
+    #[case] cli_limit: Option<usize>,
+    #[case] expected_limit: Option<usize>,
+) {
+    let config = config_limit.map(|limit| HotFunctionsConfig {
+        limit: Some(limit),
+        event_kind: None,
+    });
+    let actual = Config::new(config, cli_limit).map(|c| c.limit);
+    assert_eq!(actual, expected_limit);
+}
+
+#[test]
+fn test_hot_functions_config_default() {
+    let actual = Config::new(Some(HotFunctionsConfig::default()), None);
+    assert_eq!(
+        actual,
+        Some(Config {
+            limit: 10,
+            event_kind: EventKind::Ir
+        })
+    );
+}
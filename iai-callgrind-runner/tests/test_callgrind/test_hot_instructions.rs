@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+
+use iai_callgrind_runner::api;
+use iai_callgrind_runner::runner::callgrind::hashmap_parser::{HashMapParser, Id, SourcePath};
+use iai_callgrind_runner::runner::callgrind::hot_instructions::{
+    parse_objdump, HotInstructions, Symbol,
+};
+use iai_callgrind_runner::runner::callgrind::parser::Parser;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use crate::common::Fixtures;
+
+fn load_symbols() -> Vec<Symbol> {
+    parse_objdump(
+        &std::fs::read_to_string(Fixtures::get_path_of("hot_instructions/objdump.txt")).unwrap(),
+    )
+}
+
+#[test]
+fn test_parse_objdump() {
+    let symbols = load_symbols();
+
+    assert_eq!(
+        symbols
+            .iter()
+            .map(|symbol| (
+                symbol.name.as_str(),
+                symbol.address,
+                symbol.instructions.len()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("_init", 0x1000, 3),
+            ("main", 0x1130, 6),
+            ("my_lib::fib::h0123456789abcdef", 0x1150, 13),
+        ]
+    );
+    assert_eq!(symbols[0].instructions[&0x1000], "endbr64");
+    assert_eq!(
+        symbols[2].instructions[&0x1154],
+        "jb     1170 <my_lib::fib::h0123456789abcdef+0x20>"
+    );
+}
+
+#[test]
+fn test_hashmap_parser_when_instr_positions() {
+    let map = HashMapParser::default()
+        .parse(&Fixtures::get_callgrind_output_path(
+            "callgrind.out/callgrind.valid.instr.out",
+        ))
+        .unwrap();
+
+    let value = map
+        .get(&Id {
+            obj: Some(SourcePath::Relative(PathBuf::from("target/release/bench"))),
+            file: Some(SourcePath::Relative(PathBuf::from("src/lib.rs"))),
+            func: "my_lib::fib".to_owned(),
+        })
+        .unwrap();
+    assert_eq!(value.instrs.len(), 10);
+    assert_eq!(
+        value.instrs[&0x0400_1160].cost_by_kind(&api::EventKind::Ir),
+        Some(9)
+    );
+    assert_eq!(value.lines[&13].cost_by_kind(&api::EventKind::Ir), Some(27));
+}
+
+#[rstest]
+#[case::default("valid.instr", api::HotInstructionsConfig::default())]
+#[case::with_limits(
+    "valid.instr.limits",
+    api::HotInstructionsConfig {
+        functions: Some(2),
+        limit: Some(3),
+        ..Default::default()
+    }
+)]
+fn test_hot_instructions_to_text(
+    #[case] expected: &str,
+    #[case] config: api::HotInstructionsConfig,
+) {
+    let map = HashMapParser::default()
+        .parse(&Fixtures::get_callgrind_output_path(
+            "callgrind.out/callgrind.valid.instr.out",
+        ))
+        .unwrap();
+    let symbols = load_symbols();
+
+    let hot_instructions = HotInstructions::new(config.into());
+    let report = hot_instructions
+        .report("bench".to_owned(), &map, |obj| {
+            (obj == &SourcePath::Relative(PathBuf::from("target/release/bench")))
+                .then(|| symbols.clone())
+        })
+        .unwrap();
+
+    let expected = Fixtures::load_stacks(format!(
+        "callgrind.out/callgrind.{expected}.exp_hot_instructions"
+    ));
+    assert_eq!(
+        hot_instructions
+            .to_text(&report)
+            .lines()
+            .collect::<Vec<&str>>(),
+        expected
+    );
+}
+
+#[test]
+fn test_hot_instructions_when_event_kind_missing() {
+    let map = HashMapParser::default()
+        .parse(&Fixtures::get_callgrind_output_path(
+            "callgrind.out/callgrind.valid.instr.out",
+        ))
+        .unwrap();
+
+    let hot_instructions = HotInstructions::new(
+        api::HotInstructionsConfig {
+            event_kind: Some(api::EventKind::Dr),
+            ..Default::default()
+        }
+        .into(),
+    );
+    assert_eq!(
+        hot_instructions
+            .report("bench".to_owned(), &map, |_| None)
+            .unwrap_err()
+            .to_string(),
+        "Failed creating the hot instructions report: Missing event type 'Dr'"
+    );
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::callgrind::hashmap_parser::HashMapParser;
+use iai_callgrind_runner::runner::callgrind::parser::Parser;
+use iai_callgrind_runner::runner::callgrind::pprof::{Config, Pprof};
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::load_totals;
+use crate::common::Fixtures;
+
+#[derive(Debug)]
+enum Field {
+    Varint(u64),
+    Bytes(Vec<u8>),
+}
+
+/// The decoded pprof profile with the samples as `function:line` stacks from the root to the leaf
+#[derive(Debug, Default)]
+struct Profile {
+    sample_types: Vec<String>,
+    samples: Vec<(String, Vec<u64>)>,
+    filenames: Vec<String>,
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> u64 {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = bytes[*pos];
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte < 0x80 {
+            return value;
+        }
+        shift += 7;
+    }
+}
+
+fn decode_message(bytes: &[u8]) -> Vec<(u64, Field)> {
+    let mut fields = vec![];
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let key = read_varint(bytes, &mut pos);
+        match key & 0x7 {
+            0 => fields.push((key >> 3, Field::Varint(read_varint(bytes, &mut pos)))),
+            2 => {
+                let len = read_varint(bytes, &mut pos) as usize;
+                fields.push((key >> 3, Field::Bytes(bytes[pos..pos + len].to_vec())));
+                pos += len;
+            }
+            wire_type => panic!("Unexpected wire type: {wire_type}"),
+        }
+    }
+    fields
+}
+
+fn get_varint(fields: &[(u64, Field)], field: u64) -> u64 {
+    fields
+        .iter()
+        .find_map(|(f, v)| match v {
+            Field::Varint(v) if *f == field => Some(*v),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn get_bytes(fields: &[(u64, Field)], field: u64) -> Vec<&[u8]> {
+    fields
+        .iter()
+        .filter_map(|(f, v)| match v {
+            Field::Bytes(v) if *f == field => Some(v.as_slice()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn unpack(bytes: &[u8]) -> Vec<u64> {
+    let mut values = vec![];
+    let mut pos = 0;
+    while pos < bytes.len() {
+        values.push(read_varint(bytes, &mut pos));
+    }
+    values
+}
+
+fn decode(data: &[u8]) -> Profile {
+    let profile = decode_message(data);
+    let strings = get_bytes(&profile, 6)
+        .into_iter()
+        .map(|s| String::from_utf8(s.to_vec()).unwrap())
+        .collect::<Vec<String>>();
+    assert_eq!(strings[0], "");
+
+    let functions = get_bytes(&profile, 5)
+        .into_iter()
+        .map(|f| {
+            let f = decode_message(f);
+            (
+                get_varint(&f, 1),
+                (
+                    strings[get_varint(&f, 2) as usize].clone(),
+                    strings[get_varint(&f, 4) as usize].clone(),
+                ),
+            )
+        })
+        .collect::<HashMap<u64, (String, String)>>();
+    let locations = get_bytes(&profile, 4)
+        .into_iter()
+        .map(|l| {
+            let l = decode_message(l);
+            let line = decode_message(get_bytes(&l, 4)[0]);
+            (
+                get_varint(&l, 1),
+                format!(
+                    "{}:{}",
+                    functions[&get_varint(&line, 1)].0,
+                    get_varint(&line, 2)
+                ),
+            )
+        })
+        .collect::<HashMap<u64, String>>();
+
+    let mut filenames = functions
+        .values()
+        .map(|(_, file)| file.clone())
+        .collect::<Vec<String>>();
+    filenames.sort();
+    filenames.dedup();
+
+    Profile {
+        sample_types: get_bytes(&profile, 1)
+            .into_iter()
+            .map(|t| strings[get_varint(&decode_message(t), 1) as usize].clone())
+            .collect(),
+        samples: get_bytes(&profile, 2)
+            .into_iter()
+            .map(|s| {
+                let s = decode_message(s);
+                let mut stack = unpack(get_bytes(&s, 1)[0])
+                    .into_iter()
+                    .map(|id| locations[&id].clone())
+                    .collect::<Vec<String>>();
+                stack.reverse();
+                (stack.join(";"), unpack(get_bytes(&s, 2)[0]))
+            })
+            .collect(),
+        filenames,
+    }
+}
+
+#[rstest]
+#[case::all_event_kinds(None, &["Ir"])]
+#[case::with_event_kinds(Some(vec![EventKind::Ir, EventKind::Ir]), &["Ir", "Ir"])]
+fn test_pprof_encode(
+    #[case] event_kinds: Option<Vec<EventKind>>,
+    #[case] expected_sample_types: &[&str],
+) {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.out");
+    let map = parser.parse(&output).unwrap();
+
+    let profile = decode(&Pprof::new(Config { event_kinds }).encode(&map).unwrap());
+
+    let values = |value: u64| vec![value; expected_sample_types.len()];
+    let expected_samples = vec![
+        ("main:1".to_owned(), values(5)),
+        ("main:2;foo:10".to_owned(), values(60)),
+        ("main:2;foo:11;bar:20".to_owned(), values(20)),
+        ("main:3;bar:20".to_owned(), values(40)),
+    ];
+    assert_eq!(profile.sample_types, expected_sample_types);
+    assert_eq!(profile.samples, expected_samples);
+    assert_eq!(profile.filenames, vec!["src/main.rs".to_owned()]);
+}
+
+#[rstest]
+#[case::calls("valid.calls")]
+#[case::recursion("valid.recursion")]
+#[case::cycle("valid.cycle")]
+#[case::no_entry_point("no_entry_point")]
+fn test_pprof_encode_totals(#[case] fixture: &str) {
+    let parser = HashMapParser::default();
+    let output =
+        Fixtures::get_callgrind_output_path(format!("callgrind.out/callgrind.{fixture}.out"));
+    let map = parser.parse(&output).unwrap();
+
+    let profile = decode(
+        &Pprof::new(Config { event_kinds: None })
+            .encode(&map)
+            .unwrap(),
+    );
+
+    let totals = load_totals(&output);
+    assert_eq!(profile.sample_types.len(), totals.len());
+    for (index, (event_kind, total)) in totals.into_iter().enumerate() {
+        let sum: u64 = profile
+            .samples
+            .iter()
+            .map(|(_, values)| values[index])
+            .sum();
+        assert_eq!(sum, total, "Sum of the samples of event kind {event_kind}");
+    }
+}
+
+#[test]
+fn test_pprof_event_kinds_when_summarized_then_without_derived() {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.no_entry_point.out");
+    let mut map = parser.parse(&output).unwrap();
+    map.make_summary().unwrap();
+
+    let event_kinds = Pprof::new(Config { event_kinds: None }).event_kinds(&map);
+
+    assert_eq!(
+        event_kinds,
+        vec![
+            EventKind::Ir,
+            EventKind::Dr,
+            EventKind::Dw,
+            EventKind::I1mr,
+            EventKind::D1mr,
+            EventKind::D1mw,
+            EventKind::ILmr,
+            EventKind::DLmr,
+            EventKind::DLmw
+        ]
+    );
+}
+
+#[test]
+fn test_pprof_encode_when_missing_event_kind() {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.out");
+    let map = parser.parse(&output).unwrap();
+
+    let result = Pprof::new(Config {
+        event_kinds: Some(vec![EventKind::Dr]),
+    })
+    .encode(&map);
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Failed creating the call graph: Missing event type 'Dr'"
+    );
+}
@@ -35,6 +35,20 @@ fn test_sentinel_parser(#[case] sentinel: &str, #[case] costs: [u64; 9]) {
     assert_eq!(actual_costs, expected_costs);
 }
 
+#[rstest]
+#[case::main("main", 125)]
+#[case::defined_in_cfn("foo", 80)]
+#[case::relative_positions("bar", 60)]
+fn test_sentinel_parser_when_compressed(#[case] sentinel: &str, #[case] ir: u64) {
+    let callgrind_output =
+        Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.compressed.out");
+
+    let parser = SentinelParser::new(&Sentinel::new(sentinel));
+    let actual_costs = parser.parse(&callgrind_output).unwrap();
+
+    assert_eq!(actual_costs, Costs::with_event_kinds([(EventKind::Ir, ir)]));
+}
+
 #[test]
 fn test_sentinel_parser_when_not_found_then_error() {
     let callgrind_output =
@@ -0,0 +1,136 @@
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::callgrind::hashmap_parser::HashMapParser;
+use iai_callgrind_runner::runner::callgrind::parser::Parser;
+use iai_callgrind_runner::runner::callgrind::speedscope::{Config, File, Speedscope};
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::load_totals;
+use crate::common::Fixtures;
+
+/// Return the samples of each profile as `function` stacks from the root to the leaf
+fn to_stacks(file: &File) -> Vec<(String, Vec<(String, u64)>)> {
+    file.profiles
+        .iter()
+        .map(|profile| {
+            let samples = profile
+                .samples
+                .iter()
+                .zip(profile.weights.iter())
+                .map(|(sample, weight)| {
+                    let stack = sample
+                        .iter()
+                        .map(|index| file.shared.frames[*index].name.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(";");
+                    (stack, *weight)
+                })
+                .collect();
+            (profile.name.clone(), samples)
+        })
+        .collect()
+}
+
+#[rstest]
+#[case::all_event_kinds(None, &["Ir"])]
+#[case::with_event_kinds(Some(vec![EventKind::Ir, EventKind::Ir]), &["Ir", "Ir"])]
+fn test_speedscope_create(
+    #[case] event_kinds: Option<Vec<EventKind>>,
+    #[case] expected_profiles: &[&str],
+) {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.out");
+    let map = parser.parse(&output).unwrap();
+
+    let file = Speedscope::new(Config { event_kinds })
+        .create("callgrind.valid.calls.out".to_owned(), &map)
+        .unwrap();
+
+    let expected_samples = vec![
+        ("main".to_owned(), 5),
+        ("main;foo".to_owned(), 60),
+        ("main;foo;bar".to_owned(), 20),
+        ("main;bar".to_owned(), 40),
+    ];
+    let expected = expected_profiles
+        .iter()
+        .map(|name| ((*name).to_owned(), expected_samples.clone()))
+        .collect::<Vec<(String, Vec<(String, u64)>)>>();
+    assert_eq!(to_stacks(&file), expected);
+    assert!(file.profiles.iter().all(|profile| profile.end_value == 125));
+    assert!(file
+        .shared
+        .frames
+        .iter()
+        .all(|frame| frame.file.as_deref() == Some("src/main.rs")));
+}
+
+#[test]
+fn test_speedscope_event_kinds_when_summarized_then_without_derived() {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.no_entry_point.out");
+    let mut map = parser.parse(&output).unwrap();
+    map.make_summary().unwrap();
+
+    let event_kinds = Speedscope::new(Config { event_kinds: None }).event_kinds(&map);
+
+    assert_eq!(
+        event_kinds,
+        vec![
+            EventKind::Ir,
+            EventKind::Dr,
+            EventKind::Dw,
+            EventKind::I1mr,
+            EventKind::D1mr,
+            EventKind::D1mw,
+            EventKind::ILmr,
+            EventKind::DLmr,
+            EventKind::DLmw
+        ]
+    );
+}
+
+#[test]
+fn test_speedscope_create_when_missing_event_kind() {
+    let parser = HashMapParser::default();
+    let output = Fixtures::get_callgrind_output_path("callgrind.out/callgrind.valid.calls.out");
+    let map = parser.parse(&output).unwrap();
+
+    let result = Speedscope::new(Config {
+        event_kinds: Some(vec![EventKind::Dr]),
+    })
+    .create("callgrind.valid.calls.out".to_owned(), &map);
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Failed creating the call graph: Missing event type 'Dr'"
+    );
+}
+
+#[rstest]
+#[case::calls("valid.calls")]
+#[case::recursion("valid.recursion")]
+#[case::cycle("valid.cycle")]
+#[case::no_entry_point("no_entry_point")]
+fn test_speedscope_create_totals(#[case] fixture: &str) {
+    let parser = HashMapParser::default();
+    let output =
+        Fixtures::get_callgrind_output_path(format!("callgrind.out/callgrind.{fixture}.out"));
+    let map = parser.parse(&output).unwrap();
+
+    let file = Speedscope::new(Config { event_kinds: None })
+        .create(fixture.to_owned(), &map)
+        .unwrap();
+
+    let totals = load_totals(&output);
+    assert_eq!(file.profiles.len(), totals.len());
+    for (profile, (event_kind, total)) in file.profiles.iter().zip(totals) {
+        assert_eq!(profile.name, event_kind.to_string());
+        assert_eq!(
+            profile.weights.iter().sum::<u64>(),
+            total,
+            "Sum of the weights of event kind {event_kind}"
+        );
+        assert_eq!(profile.end_value, total);
+    }
+}
@@ -0,0 +1,34 @@
+mod test_baselines;
+mod test_revision;
+mod test_worktree;
+
+use std::path::Path;
+use std::process::Command;
+
+/// Run git with the `args` in `dir` and return the trimmed stdout
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(["-c", "user.name=iai", "-c", "user.email=iai@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_owned()
+}
+
+/// Initialize a git repository in `dir` with the `main` branch and an empty commit
+fn init_repository(dir: &Path) {
+    git(dir, &["init", "-q", "-b", "main"]);
+    commit(dir, "initial");
+}
+
+/// Create an empty commit and return its hash
+fn commit(dir: &Path, message: &str) -> String {
+    git(dir, &["commit", "-q", "--allow-empty", "-m", message]);
+    git(dir, &["rev-parse", "HEAD"])
+}
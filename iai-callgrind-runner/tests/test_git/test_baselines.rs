@@ -0,0 +1,165 @@
+use std::path::{Path, PathBuf};
+
+use iai_callgrind_runner::runner::git::{GitBaseline, GitBaselines};
+use iai_callgrind_runner::runner::tool::{ToolOutputPath, ValgrindTool};
+use pretty_assertions::assert_eq;
+
+fn output_path(base_dir: &Path) -> ToolOutputPath {
+    let output_path = ToolOutputPath::new(
+        ValgrindTool::Callgrind,
+        base_dir,
+        "bench::group",
+        "bench_fn",
+    );
+    output_path.init();
+    output_path
+}
+
+fn write_output(output_path: &ToolOutputPath, content: &str) {
+    std::fs::write(output_path.to_path(), content).unwrap();
+}
+
+fn read(path: PathBuf) -> String {
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn test_save_baseline_then_load_baseline() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = output_path(temp_dir.path());
+    let baseline_dir = Path::new("commits/abc123");
+
+    write_output(&output_path, "baseline");
+    output_path.save_baseline(baseline_dir).unwrap();
+    assert_eq!(
+        read(output_path.to_baseline_output(baseline_dir).to_path()),
+        "baseline"
+    );
+
+    output_path.init();
+    write_output(&output_path, "new");
+    assert_eq!(read(output_path.to_old_output().to_path()), "baseline");
+
+    output_path.init();
+    write_output(&output_path, "newer");
+    assert_eq!(read(output_path.to_old_output().to_path()), "new");
+
+    output_path.load_baseline(baseline_dir).unwrap();
+    assert_eq!(read(output_path.to_old_output().to_path()), "baseline");
+    assert_eq!(read(output_path.to_path()), "newer");
+}
+
+#[test]
+fn test_save_baseline_replaces_previous_baseline() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = output_path(temp_dir.path());
+    let baseline_dir = Path::new("branches/main");
+
+    std::fs::write(output_path.dir.join("callgrind.bench_fn.out.2"), "thread").unwrap();
+    write_output(&output_path, "first");
+    output_path.save_baseline(baseline_dir).unwrap();
+
+    output_path.init();
+    write_output(&output_path, "second");
+    output_path.save_baseline(baseline_dir).unwrap();
+
+    let baseline = output_path.to_baseline_output(baseline_dir);
+    assert_eq!(read(baseline.to_path()), "second");
+    assert_eq!(baseline.real_paths(), vec![baseline.to_path()]);
+}
+
+#[test]
+fn test_git_baselines_load_uses_first_existing_baseline() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = output_path(temp_dir.path());
+
+    write_output(&output_path, "branch");
+    output_path
+        .save_baseline(Path::new("branches/main"))
+        .unwrap();
+    output_path.init();
+    write_output(&output_path, "new");
+
+    let baselines = GitBaselines {
+        save: vec![],
+        compare: Some(GitBaseline {
+            revision: "main".to_owned(),
+            dirs: vec![
+                PathBuf::from("commits/abc123"),
+                PathBuf::from("branches/main"),
+            ],
+        }),
+    };
+    baselines.load(&output_path).unwrap();
+    assert_eq!(read(output_path.to_old_output().to_path()), "branch");
+}
+
+#[test]
+fn test_git_baselines_load_when_no_baseline_then_no_old_output() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = output_path(temp_dir.path());
+
+    write_output(&output_path, "previous");
+    output_path.init();
+    assert!(output_path.to_old_output().exists());
+
+    let baselines = GitBaselines {
+        save: vec![],
+        compare: Some(GitBaseline {
+            revision: "main".to_owned(),
+            dirs: vec![PathBuf::from("commits/abc123")],
+        }),
+    };
+    baselines.load(&output_path).unwrap();
+    assert!(!output_path.to_old_output().exists());
+}
+
+#[test]
+fn test_git_baselines_save_in_all_dirs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = output_path(temp_dir.path());
+    write_output(&output_path, "new");
+
+    let dirs = vec![
+        PathBuf::from("commits/abc123"),
+        PathBuf::from("branches/main"),
+    ];
+    let baselines = GitBaselines {
+        save: dirs.clone(),
+        compare: None,
+    };
+    baselines.save(&output_path).unwrap();
+    for dir in dirs {
+        assert_eq!(read(output_path.to_baseline_output(&dir).to_path()), "new");
+    }
+}
+
+#[test]
+fn test_git_baselines_of_tool_output_and_log_are_separate() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let callgrind = output_path(temp_dir.path());
+    let dhat = callgrind.to_tool_output(ValgrindTool::DHAT);
+    let dhat_log = dhat.to_log_output();
+    let baselines = GitBaselines {
+        save: vec![PathBuf::from("commits/abc123")],
+        compare: Some(GitBaseline {
+            revision: "main".to_owned(),
+            dirs: vec![PathBuf::from("commits/abc123")],
+        }),
+    };
+
+    write_output(&callgrind, "callgrind");
+    write_output(&dhat, "dhat");
+    write_output(&dhat_log, "dhat log");
+    for path in [&callgrind, &dhat, &dhat_log] {
+        baselines.save(path).unwrap();
+    }
+
+    for path in [&callgrind, &dhat, &dhat_log] {
+        path.init();
+        baselines.load(path).unwrap();
+    }
+    assert_eq!(read(callgrind.to_old_output().to_path()), "callgrind");
+    assert_eq!(read(dhat.to_old_output().to_path()), "dhat");
+    assert_eq!(read(dhat_log.to_old_output().to_path()), "dhat log");
+}
@@ -0,0 +1,94 @@
+use iai_callgrind_runner::runner::git::{
+    branch_dir, commit_dir, current_branch, head_commit, GitRevision,
+};
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::{commit, git, init_repository};
+
+#[rstest]
+#[case::branch("main", GitRevision::Rev("main".to_owned()))]
+#[case::hash("abc123", GitRevision::Rev("abc123".to_owned()))]
+#[case::merge_base("merge-base(main)", GitRevision::MergeBase("main".to_owned()))]
+#[case::merge_base_with_spaces(
+    " merge-base( origin/main ) ",
+    GitRevision::MergeBase("origin/main".to_owned())
+)]
+fn test_git_revision_parse(#[case] value: &str, #[case] expected: GitRevision) {
+    assert_eq!(GitRevision::parse(value).unwrap(), expected);
+}
+
+#[rstest]
+#[case::empty("")]
+#[case::blank("  ")]
+#[case::empty_merge_base("merge-base()")]
+fn test_git_revision_parse_when_invalid_then_error(#[case] value: &str) {
+    assert!(GitRevision::parse(value).is_err());
+}
+
+#[rstest]
+#[case::rev(GitRevision::Rev("main".to_owned()), "main")]
+#[case::merge_base(GitRevision::MergeBase("main".to_owned()), "merge-base(main)")]
+fn test_git_revision_display(#[case] revision: GitRevision, #[case] expected: &str) {
+    assert_eq!(revision.to_string(), expected);
+}
+
+#[test]
+fn test_git_revision_resolve() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    init_repository(dir);
+    let base = commit(dir, "base");
+    git(dir, &["checkout", "-q", "-b", "feature/parser"]);
+    let feature = commit(dir, "feature");
+    git(dir, &["checkout", "-q", "main"]);
+    let main = commit(dir, "main");
+    git(dir, &["checkout", "-q", "feature/parser"]);
+
+    assert_eq!(head_commit(dir), Some(feature.clone()));
+    assert_eq!(current_branch(dir), Some("feature/parser".to_owned()));
+    assert_eq!(
+        GitRevision::Rev("main".to_owned()).resolve(dir).unwrap(),
+        main
+    );
+    assert_eq!(
+        GitRevision::Rev("HEAD~1".to_owned()).resolve(dir).unwrap(),
+        base
+    );
+    assert_eq!(
+        GitRevision::MergeBase("main".to_owned())
+            .resolve(dir)
+            .unwrap(),
+        base
+    );
+    assert!(GitRevision::Rev("does-not-exist".to_owned())
+        .resolve(dir)
+        .is_err());
+}
+
+#[test]
+fn test_git_when_detached_then_no_branch() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    init_repository(dir);
+    let hash = commit(dir, "detached");
+    git(dir, &["checkout", "-q", "--detach"]);
+
+    assert_eq!(head_commit(dir), Some(hash));
+    assert_eq!(current_branch(dir), None);
+}
+
+#[test]
+fn test_git_when_no_repository_then_none() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    assert_eq!(head_commit(temp_dir.path()), None);
+    assert_eq!(current_branch(temp_dir.path()), None);
+}
+
+#[rstest]
+#[case::commit(commit_dir("abc123"), "commits/abc123")]
+#[case::branch(branch_dir("main"), "branches/main")]
+#[case::nested_branch(branch_dir("feature/parser"), "branches/feature/parser")]
+fn test_baseline_dirs(#[case] actual: std::path::PathBuf, #[case] expected: &str) {
+    assert_eq!(actual, std::path::PathBuf::from(expected));
+}
@@ -0,0 +1,44 @@
+use iai_callgrind_runner::runner::git::{head_commit, toplevel, Worktree};
+use pretty_assertions::assert_eq;
+
+use super::{commit, git, init_repository};
+
+#[test]
+fn test_worktree_add_then_remove_on_drop() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let repository = temp_dir.path().join("repository");
+    std::fs::create_dir(&repository).unwrap();
+    init_repository(&repository);
+    let base = commit(&repository, "base");
+    let head = commit(&repository, "head");
+
+    let path = temp_dir.path().join("worktree");
+    let worktree = Worktree::add(&repository, &path, &base).unwrap();
+    assert_eq!(head_commit(&worktree.path), Some(base));
+    assert_eq!(
+        toplevel(&worktree.path).unwrap().canonicalize().unwrap(),
+        path.canonicalize().unwrap()
+    );
+    assert_eq!(head_commit(&repository), Some(head));
+
+    drop(worktree);
+    assert!(!path.exists());
+    assert_eq!(git(&repository, &["worktree", "list"]).lines().count(), 1);
+}
+
+#[test]
+fn test_worktree_add_when_stale_then_replaced() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let repository = temp_dir.path().join("repository");
+    std::fs::create_dir(&repository).unwrap();
+    init_repository(&repository);
+    let hash = commit(&repository, "commit");
+
+    let path = temp_dir.path().join("worktree");
+    std::fs::create_dir(&path).unwrap();
+    std::fs::write(path.join("leftover"), "stale").unwrap();
+
+    let worktree = Worktree::add(&repository, &path, &hash).unwrap();
+    assert!(!path.join("leftover").exists());
+    assert_eq!(head_commit(&worktree.path), Some(hash));
+}
@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod test_chart;
+#[cfg(test)]
+mod test_store;
+#[cfg(test)]
+mod test_trend;
+
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::callgrind::model::Costs;
+use iai_callgrind_runner::runner::history::{Fingerprint, HistoryEntry, VERSION};
+use iai_callgrind_runner::runner::summary::CostsSummary;
+
+/// The timestamp of the first entry: 2025-10-09 08:53:20 UTC
+pub const TIMESTAMP: u64 = 1_760_000_000_000;
+
+pub fn fingerprint() -> Fingerprint {
+    Fingerprint {
+        arch: "x86_64".to_owned(),
+        os: "linux".to_owned(),
+        runner_version: "0.8.0".to_owned(),
+        valgrind_version: Some("valgrind-3.22.0".to_owned()),
+    }
+}
+
+/// Create a `HistoryEntry` of the `index`th run an hour after the previous run
+pub fn entry(
+    module_path: &str,
+    index: u64,
+    git_commit: Option<&str>,
+    costs: &[(EventKind, u64)],
+) -> HistoryEntry {
+    HistoryEntry {
+        version: VERSION.to_owned(),
+        timestamp: TIMESTAMP + index * 3_600_000,
+        git_commit: git_commit.map(ToOwned::to_owned),
+        fingerprint: fingerprint(),
+        module_path: module_path.to_owned(),
+        id: Some("id".to_owned()),
+        costs: CostsSummary::new(&Costs::with_event_kinds(costs.iter().copied()), None),
+    }
+}
@@ -0,0 +1,38 @@
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::history::chart::LineChart;
+use iai_callgrind_runner::runner::history::trend::Trend;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::entry;
+use crate::common::Fixtures;
+
+#[rstest]
+#[case::multiple_runs("multiple_runs", &[100, 120, 118])]
+#[case::single_run("single_run", &[100])]
+#[case::constant("constant", &[100, 100])]
+fn test_line_chart_create(#[case] fixture: &str, #[case] costs: &[u64]) {
+    let entries = costs
+        .iter()
+        .enumerate()
+        .map(|(index, cost)| {
+            entry(
+                "bench::group::bench <a>",
+                index as u64,
+                Some("abc"),
+                &[(EventKind::Ir, *cost)],
+            )
+        })
+        .collect::<Vec<_>>();
+    let trends = Trend::from_entries(&entries, &[EventKind::Ir], 10, None);
+    let chart = LineChart::new(&trends[0]);
+
+    assert_eq!(chart.file_name(), "bench__group__bench__a__id.Ir.svg");
+
+    let actual = chart.create();
+    let expected = std::fs::read_to_string(Fixtures::get_path_of(format!(
+        "history/chart.{fixture}.exp_svg"
+    )))
+    .unwrap();
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,89 @@
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::history::{format_timestamp, History};
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::{entry, TIMESTAMP};
+
+#[test]
+fn test_history_append_then_load() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let history = History::new(temp_dir.path());
+    let first = vec![
+        entry(
+            "bench_a::group::bench",
+            0,
+            Some("abc"),
+            &[(EventKind::Ir, 100)],
+        ),
+        entry(
+            "bench_a::group::other",
+            0,
+            Some("abc"),
+            &[(EventKind::Ir, 10)],
+        ),
+    ];
+    let second = vec![entry(
+        "bench_a::group::bench",
+        2,
+        None,
+        &[(EventKind::Ir, 90)],
+    )];
+    let other_file = vec![entry(
+        "bench_b::group::bench",
+        1,
+        None,
+        &[(EventKind::Ir, 5)],
+    )];
+
+    history.append("bench_a", &first).unwrap();
+    history.append("bench_a", &second).unwrap();
+    history.append("bench_b", &other_file).unwrap();
+
+    let expected = vec![
+        first[0].clone(),
+        first[1].clone(),
+        other_file[0].clone(),
+        second[0].clone(),
+    ];
+    assert_eq!(history.load().unwrap(), expected);
+}
+
+#[test]
+fn test_history_load_skips_invalid_lines() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let history = History::new(temp_dir.path());
+    let entries = vec![entry(
+        "bench_a::group::bench",
+        0,
+        None,
+        &[(EventKind::Ir, 100)],
+    )];
+    history.append("bench_a", &entries).unwrap();
+    std::fs::write(
+        temp_dir.path().join("bench_b.jsonl"),
+        "{\"version\":\"0\"}\n\nnot json\n",
+    )
+    .unwrap();
+
+    assert_eq!(history.load().unwrap(), entries);
+}
+
+#[test]
+fn test_history_load_when_no_history() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    assert!(History::new(temp_dir.path().join("does_not_exist"))
+        .load()
+        .unwrap()
+        .is_empty());
+}
+
+#[rstest]
+#[case::epoch(0, "1970-01-01 00:00:00")]
+#[case::millis_are_truncated(999, "1970-01-01 00:00:00")]
+#[case::leap_day(1_709_210_096_000, "2024-02-29 12:34:56")]
+#[case::end_of_year(1_704_067_199_000, "2023-12-31 23:59:59")]
+#[case::timestamp(TIMESTAMP, "2025-10-09 08:53:20")]
+fn test_format_timestamp(#[case] millis: u64, #[case] expected: &str) {
+    assert_eq!(format_timestamp(millis), expected);
+}
@@ -0,0 +1,96 @@
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::history::trend::{format_trends, Trend};
+use iai_callgrind_runner::runner::history::HistoryEntry;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::entry;
+use crate::common::Fixtures;
+
+fn entries() -> Vec<HistoryEntry> {
+    vec![
+        entry(
+            "bench::group::bench_a",
+            0,
+            Some("0123456789abcdef"),
+            &[(EventKind::Ir, 100), (EventKind::EstimatedCycles, 200)],
+        ),
+        entry("bench::group::bench_b", 0, None, &[(EventKind::Ir, 1000)]),
+        entry(
+            "bench::group::bench_a",
+            1,
+            Some("123456789abcdef0"),
+            &[(EventKind::Ir, 120), (EventKind::EstimatedCycles, 260)],
+        ),
+        entry("bench::group::bench_b", 1, None, &[(EventKind::Ir, 900)]),
+        entry(
+            "bench::group::bench_a",
+            2,
+            Some("abc"),
+            &[(EventKind::Ir, 118), (EventKind::EstimatedCycles, 250)],
+        ),
+    ]
+}
+
+#[rstest]
+#[case::all(10, None, &[
+    ("bench::group::bench_a id", EventKind::Ir, vec![100, 120, 118]),
+    ("bench::group::bench_a id", EventKind::EstimatedCycles, vec![200, 260, 250]),
+    ("bench::group::bench_b id", EventKind::Ir, vec![1000, 900]),
+])]
+#[case::last(2, None, &[
+    ("bench::group::bench_a id", EventKind::Ir, vec![120, 118]),
+    ("bench::group::bench_a id", EventKind::EstimatedCycles, vec![260, 250]),
+    ("bench::group::bench_b id", EventKind::Ir, vec![1000, 900]),
+])]
+#[case::filter(10, Some("bench_b"), &[
+    ("bench::group::bench_b id", EventKind::Ir, vec![1000, 900]),
+])]
+#[case::no_match(10, Some("does_not_exist"), &[])]
+#[case::last_zero(0, None, &[])]
+fn test_trend_from_entries(
+    #[case] last: usize,
+    #[case] filter: Option<&str>,
+    #[case] expected: &[(&str, EventKind, Vec<u64>)],
+) {
+    let entries = entries();
+    let actual = Trend::from_entries(
+        &entries,
+        &[EventKind::Ir, EventKind::EstimatedCycles],
+        last,
+        filter,
+    )
+    .into_iter()
+    .map(|trend| {
+        (
+            trend.title,
+            trend.event_kind,
+            trend
+                .points
+                .iter()
+                .map(|(_, cost)| *cost)
+                .collect::<Vec<u64>>(),
+        )
+    })
+    .collect::<Vec<(String, EventKind, Vec<u64>)>>();
+    let expected = expected
+        .iter()
+        .map(|(title, event_kind, costs)| ((*title).to_owned(), *event_kind, costs.clone()))
+        .collect::<Vec<(String, EventKind, Vec<u64>)>>();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_format_trends() {
+    let entries = entries();
+    let trends = Trend::from_entries(
+        &entries,
+        &[EventKind::Ir, EventKind::EstimatedCycles],
+        10,
+        None,
+    );
+
+    let actual = format_trends(&trends);
+    let expected = std::fs::read_to_string(Fixtures::get_path_of("history/trends.exp")).unwrap();
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,2 @@
+mod test_load;
+mod test_pattern;
@@ -0,0 +1,179 @@
+use std::ffi::OsString;
+
+use iai_callgrind_runner::api::{
+    EventKind, FlamegraphConfig, FlamegraphKind, LibraryBenchmarkConfig, RawArgs,
+    RegressionConfig, Tool, Tools, ValgrindTool,
+};
+use iai_callgrind_runner::runner::project_config::{ProjectConfig, FILE_NAME};
+use pretty_assertions::assert_eq;
+
+const CONFIG: &str = r#"
+[default]
+callgrind_args = ["--toggle-collect=my_lib::*"]
+envs = ["HOME", "RUST_LOG=off"]
+regression = { limits = { ir = 5.0, EstimatedCycles = 10.0 }, fail_fast = true }
+flamegraph = { kind = "All" }
+
+[benchmarks."parser::*"]
+callgrind_args = ["branch-sim=yes"]
+env_clear = false
+tools = [{ kind = "DHAT", args = ["--time-stamp=yes"] }]
+
+[benchmarks."parser::bench_json"]
+regression = { limits = { Ir = 2.0 } }
+"#;
+
+#[test]
+fn test_project_config_when_not_matching() {
+    let config = ProjectConfig::from_toml(CONFIG).unwrap();
+    assert_eq!(
+        config.library_benchmark_config("bench::lexer::bench_token"),
+        LibraryBenchmarkConfig {
+            raw_callgrind_args: RawArgs::new(vec!["--toggle-collect=my_lib::*".to_owned()]),
+            envs: vec![
+                (OsString::from("HOME"), None),
+                (OsString::from("RUST_LOG"), Some(OsString::from("off")))
+            ],
+            regression: Some(RegressionConfig {
+                limits: vec![(EventKind::Ir, 5.0f64), (EventKind::EstimatedCycles, 10.0f64)],
+                fail_fast: Some(true)
+            }),
+            flamegraph: Some(FlamegraphConfig {
+                kind: Some(FlamegraphKind::All),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_project_config_when_matching_then_later_tables_win() {
+    let config = ProjectConfig::from_toml(CONFIG).unwrap();
+    let actual = config.library_benchmark_config("bench::parser::bench_json");
+
+    assert_eq!(
+        actual.raw_callgrind_args,
+        RawArgs::new(vec![
+            "--toggle-collect=my_lib::*".to_owned(),
+            "--branch-sim=yes".to_owned()
+        ])
+    );
+    assert_eq!(actual.env_clear, Some(false));
+    assert_eq!(
+        actual.regression,
+        Some(RegressionConfig {
+            limits: vec![(EventKind::Ir, 2.0f64)],
+            fail_fast: None
+        })
+    );
+    assert_eq!(
+        actual.tools,
+        Tools(vec![Tool {
+            kind: ValgrindTool::DHAT,
+            enable: None,
+            raw_args: RawArgs::new(vec!["--time-stamp=yes".to_owned()]),
+            outfile_modifier: None,
+            show_log: None
+        }])
+    );
+}
+
+#[test]
+fn test_project_config_when_code_config_then_code_config_wins() {
+    let config = ProjectConfig::from_toml(CONFIG).unwrap();
+    let code_config = LibraryBenchmarkConfig {
+        raw_callgrind_args: RawArgs::new(vec!["--branch-sim=no".to_owned()]),
+        regression: Some(RegressionConfig::default()),
+        ..Default::default()
+    };
+
+    let actual = config
+        .library_benchmark_config("bench::parser::bench_json")
+        .update_from_all([Some(&code_config)]);
+    assert_eq!(
+        actual.raw_callgrind_args,
+        RawArgs::new(vec![
+            "--toggle-collect=my_lib::*".to_owned(),
+            "--branch-sim=yes".to_owned(),
+            "--branch-sim=no".to_owned()
+        ])
+    );
+    assert_eq!(actual.regression, Some(RegressionConfig::default()));
+}
+
+#[test]
+fn test_project_config_binary_benchmark_config() {
+    let config = ProjectConfig::from_toml(CONFIG).unwrap();
+    let actual = config.binary_benchmark_config("bench::parser::group");
+
+    assert_eq!(actual.env_clear, Some(false));
+    assert_eq!(
+        actual.raw_callgrind_args,
+        RawArgs::new(vec![
+            "--toggle-collect=my_lib::*".to_owned(),
+            "--branch-sim=yes".to_owned()
+        ])
+    );
+}
+
+#[test]
+fn test_project_config_when_empty() {
+    let config = ProjectConfig::from_toml("").unwrap();
+    assert_eq!(config, ProjectConfig::default());
+    assert_eq!(
+        config.library_benchmark_config("bench::group::function"),
+        LibraryBenchmarkConfig::default()
+    );
+}
+
+#[test]
+fn test_project_config_when_unknown_field_then_error() {
+    assert!(ProjectConfig::from_toml("[default]\ncallgrind_arg = []").is_err());
+}
+
+#[test]
+fn test_project_config_when_unknown_event_kind_then_error() {
+    let error =
+        ProjectConfig::from_toml("[default]\nregression = { limits = { Unknown = 1.0 } }")
+            .unwrap_err();
+    assert!(
+        format!("{error:#}").contains("Unknown event kind: 'Unknown'"),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn test_project_config_when_invalid_pattern_then_error() {
+    assert!(ProjectConfig::from_toml("[benchmarks.\"bench::[\"]\nenv_clear = true").is_err());
+}
+
+#[test]
+fn test_project_config_load_prefers_package_dir() {
+    let project_root = tempfile::tempdir().unwrap();
+    let package_dir = project_root.path().join("package");
+    std::fs::create_dir(&package_dir).unwrap();
+    std::fs::write(
+        project_root.path().join(FILE_NAME),
+        "[default]\nenv_clear = true",
+    )
+    .unwrap();
+
+    let config = ProjectConfig::load(Some(&package_dir), project_root.path()).unwrap();
+    assert_eq!(config.path, Some(project_root.path().join(FILE_NAME)));
+    assert_eq!(config.default.env_clear, Some(true));
+
+    std::fs::write(package_dir.join(FILE_NAME), "[default]\nenv_clear = false").unwrap();
+    let config = ProjectConfig::load(Some(&package_dir), project_root.path()).unwrap();
+    assert_eq!(config.path, Some(package_dir.join(FILE_NAME)));
+    assert_eq!(config.default.env_clear, Some(false));
+}
+
+#[test]
+fn test_project_config_load_when_no_file() {
+    let project_root = tempfile::tempdir().unwrap();
+    assert_eq!(
+        ProjectConfig::load(None, project_root.path()).unwrap(),
+        ProjectConfig::default()
+    );
+}
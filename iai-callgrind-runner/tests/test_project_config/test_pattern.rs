@@ -0,0 +1,32 @@
+use iai_callgrind_runner::runner::project_config::ModulePathPattern;
+use rstest::rstest;
+
+#[rstest]
+#[case::exact("bench::group::function", "bench::group::function")]
+#[case::star_at_end("bench::*", "bench::group::function")]
+#[case::suffix("group::*", "bench::group::function")]
+#[case::suffix_exact("function", "bench::group::function")]
+#[case::star_in_middle("bench::*::function", "bench::group::function")]
+#[case::question_mark("bench::group::functio?", "bench::group::function")]
+#[case::star_only("*", "bench::group::function")]
+#[case::whitespace(" bench::* ", "bench::group::function")]
+fn test_module_path_pattern_matches(#[case] pattern: &str, #[case] module_path: &str) {
+    assert!(ModulePathPattern::new(pattern).unwrap().matches(module_path));
+}
+
+#[rstest]
+#[case::other_module("other::*", "bench::group::function")]
+#[case::partial_segment("oup::*", "bench::group::function")]
+#[case::prefix_only("bench::group", "bench::group::function")]
+#[case::longer("bench::group::function::other", "bench::group::function")]
+fn test_module_path_pattern_not_matches(#[case] pattern: &str, #[case] module_path: &str) {
+    assert!(!ModulePathPattern::new(pattern).unwrap().matches(module_path));
+}
+
+#[rstest]
+#[case::empty("")]
+#[case::blank("  ")]
+#[case::invalid_range("bench::[")]
+fn test_module_path_pattern_when_invalid(#[case] pattern: &str) {
+    assert!(ModulePathPattern::new(pattern).is_err());
+}
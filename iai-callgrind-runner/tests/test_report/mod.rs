@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod test_annotations;
+#[cfg(test)]
+mod test_bmf;
+#[cfg(test)]
+mod test_csv;
+#[cfg(test)]
+mod test_html;
+#[cfg(test)]
+mod test_junit;
+#[cfg(test)]
+mod test_markdown;
+#[cfg(test)]
+mod test_message;
+#[cfg(test)]
+mod test_openmetrics;
+#[cfg(test)]
+mod test_run_summary;
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::callgrind::model::Costs;
+use iai_callgrind_runner::runner::summary::{
+    BenchmarkKind, BenchmarkSummary, CallgrindRegressionSummary, CallgrindSummary, CostsSummary,
+    FlamegraphSummary, RunSummary,
+};
+use iai_callgrind_runner::runner::tool::{ToolOutputPath, ValgrindTool};
+
+pub fn summary(
+    target_dir: &Path,
+    module: &str,
+    new: u64,
+    old: u64,
+    regressed: bool,
+) -> BenchmarkSummary {
+    let output_path = ToolOutputPath::new(ValgrindTool::Callgrind, target_dir, module, "bench");
+    let mut summary = BenchmarkSummary::new(
+        BenchmarkKind::LibraryBenchmark,
+        PathBuf::from("/project"),
+        PathBuf::from("/project"),
+        PathBuf::from("benches/bench.rs"),
+        PathBuf::from("target/release/deps/bench"),
+        &[module, "bench"],
+        Some("id".to_owned()),
+        Some("details <with> html".to_owned()),
+        None,
+    );
+
+    let mut callgrind_summary = CallgrindSummary::new(
+        regressed,
+        vec![output_path.to_log_output().to_path()],
+        vec![output_path.to_path()],
+    );
+    let mut flamegraph = FlamegraphSummary::new(EventKind::Ir);
+    flamegraph.regular_path = Some(output_path.to_path().with_extension("Ir.svg"));
+    callgrind_summary.flamegraphs.push(flamegraph);
+
+    let regressions = if regressed {
+        vec![CallgrindRegressionSummary {
+            event_kind: EventKind::Ir,
+            new,
+            old,
+            diff_pct: 100f64,
+            limit: 10f64,
+        }]
+    } else {
+        vec![]
+    };
+    callgrind_summary.add_summary(
+        Path::new("target/release/deps/bench"),
+        &[],
+        &output_path.to_old_output(),
+        CostsSummary::new(
+            &Costs::with_event_kinds([(EventKind::Ir, new)]),
+            Some(&Costs::with_event_kinds([(EventKind::Ir, old)])),
+        ),
+        regressions,
+    );
+    summary.callgrind_summary = Some(callgrind_summary);
+    summary
+}
+
+pub const RUN_ID: &str = "1234-5000";
+
+pub fn run_summary(benchmarks: Vec<BenchmarkSummary>) -> RunSummary {
+    RunSummary::new(
+        Some(RUN_ID.to_owned()),
+        vec!["--html-report".to_owned()],
+        SystemTime::UNIX_EPOCH + Duration::from_millis(1000),
+        SystemTime::UNIX_EPOCH + Duration::from_millis(2000),
+        benchmarks,
+    )
+}
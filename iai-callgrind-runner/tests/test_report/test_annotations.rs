@@ -0,0 +1,120 @@
+use iai_callgrind_runner::runner::annotations::{
+    find_binary_benchmark_group, find_library_benchmark, GithubAnnotations,
+};
+use iai_callgrind_runner::runner::summary::BenchmarkKind;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::summary;
+
+const LIBRARY_BENCHMARK: &str = r#"use iai_callgrind::{library_benchmark, library_benchmark_group, main};
+
+fn helper() -> u64 {
+    1
+}
+
+/// Some documentation
+#[library_benchmark]
+#[bench::short(
+    10
+)]
+fn bench(value: u64) -> u64 {
+    value + helper()
+}
+
+#[inline(never)]
+fn no_attribute() {}
+
+library_benchmark_group!(name = group; benchmarks = bench);
+main!(library_benchmark_groups = group);
+"#;
+
+const BINARY_BENCHMARK: &str = r#"use iai_callgrind::{binary_benchmark_group, main};
+
+binary_benchmark_group!(
+    name = group_1;
+    benchmark = |group: &mut BinaryBenchmarkGroup| {}
+);
+
+binary_benchmark_group!(
+    name = group;
+    benchmark = |group: &mut BinaryBenchmarkGroup| {}
+);
+
+main!(binary_benchmark_groups = group_1, group);
+"#;
+
+#[rstest]
+#[case::library_benchmark("bench", Some(8))]
+#[case::helper("helper", Some(3))]
+#[case::no_attribute("no_attribute", Some(17))]
+#[case::not_found("does_not_exist", None)]
+fn test_find_library_benchmark(#[case] function: &str, #[case] expected: Option<usize>) {
+    assert_eq!(
+        find_library_benchmark(LIBRARY_BENCHMARK, function),
+        expected
+    );
+}
+
+#[rstest]
+#[case::first("group_1", Some(3))]
+#[case::second("group", Some(8))]
+#[case::not_found("does_not_exist", None)]
+fn test_find_binary_benchmark_group(#[case] group: &str, #[case] expected: Option<usize>) {
+    assert_eq!(
+        find_binary_benchmark_group(BINARY_BENCHMARK, group),
+        expected
+    );
+}
+
+#[test]
+fn test_github_annotations_when_not_regressed() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let summary = summary(temp_dir.path(), "bench_file::group", 100, 100, false);
+    assert_eq!(GithubAnnotations::create(&summary), "");
+}
+
+#[rstest]
+#[case::warning(false, "warning")]
+#[case::error(true, "error")]
+fn test_github_annotations_library_benchmark(#[case] fail_fast: bool, #[case] level: &str) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_root = temp_dir.path();
+    std::fs::create_dir_all(project_root.join("benches")).unwrap();
+    std::fs::write(project_root.join("benches/bench.rs"), LIBRARY_BENCHMARK).unwrap();
+
+    let mut summary = summary(project_root, "bench_file::group", 200, 100, true);
+    summary.project_root = project_root.to_owned();
+    summary.benchmark_file = project_root.join("benches/bench.rs");
+    summary
+        .callgrind_summary
+        .as_mut()
+        .unwrap()
+        .regression_fail_fast = fail_fast;
+
+    let expected = format!(
+        "::{level} file=benches/bench.rs,line=8,title=bench_file%3A%3Agroup%3A%3Abench \
+         id::Performance has regressed: Ir (100 -> 200): +100.000%25 exceeds limit of \
+         +10.0000%25\n"
+    );
+    assert_eq!(GithubAnnotations::create(&summary), expected);
+}
+
+#[test]
+fn test_github_annotations_binary_benchmark_with_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_root = temp_dir.path();
+    std::fs::create_dir_all(project_root.join("benches")).unwrap();
+    std::fs::write(project_root.join("benches/bench.rs"), BINARY_BENCHMARK).unwrap();
+
+    let mut summary = summary(project_root, "bench_file::group", 0, 0, false);
+    summary.project_root = project_root.to_owned();
+    summary.benchmark_file = project_root.join("benches/bench.rs");
+    summary.kind = BenchmarkKind::BinaryBenchmark;
+    summary.callgrind_summary = None;
+    summary.error = Some("Failed: 100%\nCaused by: error".to_owned());
+
+    let expected = "::error file=benches/bench.rs,line=8,title=bench_file%3A%3Agroup%3A%3Abench \
+                    id::Failed: 100%25%0ACaused by: error\n";
+    assert_eq!(GithubAnnotations::create(&summary), expected);
+}
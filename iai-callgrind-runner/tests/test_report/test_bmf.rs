@@ -0,0 +1,27 @@
+use anyhow::anyhow;
+use iai_callgrind_runner::runner::report::bmf::BmfReport;
+use pretty_assertions::assert_eq;
+
+use super::summary;
+use crate::common::Fixtures;
+
+#[test]
+fn test_bmf_report_create() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let mut error = summary(target_dir, "bench_c::group", 0, 0, false)
+        .with_error(&anyhow!("Failed to run benchmark"));
+    error.callgrind_summary = None;
+    let mut without_id = summary(target_dir, "bench_b::group", 200, 100, true);
+    without_id.id = None;
+    let summaries = [
+        summary(target_dir, "bench_a::group", 100, 100, false),
+        without_id,
+        error,
+    ];
+
+    let actual = BmfReport::create(&summaries).unwrap();
+    let expected =
+        std::fs::read_to_string(Fixtures::get_path_of("report/report.bmf.json")).unwrap();
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,30 @@
+use iai_callgrind_runner::runner::report::csv::CsvReport;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::summary;
+use crate::common::Fixtures;
+
+#[rstest]
+#[case::csv("csv", CsvReport::csv())]
+#[case::tsv("tsv", CsvReport::tsv())]
+fn test_csv_report_create(#[case] expected: &str, #[case] report: CsvReport) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let mut quoted = summary(target_dir, "bench_b::group", 90, 100, false);
+    quoted.details = Some("with, \"quotes\"\tand tab".to_owned());
+    let mut error = summary(target_dir, "bench_c::group", 0, 0, false);
+    error.callgrind_summary = None;
+    let summaries = [
+        summary(target_dir, "bench_a::group", 100, 100, false),
+        summary(target_dir, "bench_b::group", 200, 100, true),
+        quoted,
+        error,
+    ];
+
+    let actual = report.create(&summaries);
+    let expected =
+        std::fs::read_to_string(Fixtures::get_path_of(format!("report/report.{expected}")))
+            .unwrap();
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,163 @@
+use iai_callgrind_runner::runner::report::html::{HtmlReport, REPORT_FILE_NAME};
+use iai_callgrind_runner::runner::report::{ReportData, DATA_FILE_NAME, DEFAULT_EVENT_KINDS};
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::{run_summary, summary, RUN_ID};
+
+#[test]
+fn test_html_report_save() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let data = ReportData::new(target_dir);
+
+    data.save(
+        "bench_b",
+        &run_summary(vec![summary(target_dir, "bench_b::group", 200, 100, true)]),
+    )
+    .unwrap();
+    data.save(
+        "bench_a",
+        &run_summary(vec![summary(target_dir, "bench_a::group", 100, 100, false)]),
+    )
+    .unwrap();
+    let summaries = data
+        .load(RUN_ID, 0)
+        .unwrap()
+        .into_iter()
+        .flat_map(|run| run.benchmarks)
+        .collect::<Vec<_>>();
+    let path = HtmlReport::new(target_dir, DEFAULT_EVENT_KINDS.to_vec())
+        .save(&summaries)
+        .unwrap();
+
+    assert_eq!(path, target_dir.join(REPORT_FILE_NAME));
+    assert!(target_dir.join("bench_a").join(DATA_FILE_NAME).exists());
+    assert!(target_dir.join("bench_b").join(DATA_FILE_NAME).exists());
+
+    assert_eq!(
+        summaries
+            .iter()
+            .map(|summary| summary.module_path.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["bench_a::group::bench", "bench_b::group::bench"]
+    );
+
+    let html = std::fs::read_to_string(path).unwrap();
+    assert!(html.contains("<p>2 benchmarks, 1 regressed</p>"));
+    assert!(html.contains(
+        "<tr class=\"regressed\"><td class=\"name\"><a href=\"#b1\">bench_b::group::bench \
+         id</a></td><td data-value=\"200\">200</td><td data-value=\"100\" \
+         class=\"worse\">+100.000%</td>"
+    ));
+    assert!(html.contains(
+        "<tr><td class=\"name\"><a href=\"#b0\">bench_a::group::bench id</a></td><td \
+         data-value=\"100\">100</td><td data-value=\"0\">+0.00000%</td>"
+    ));
+    assert!(html.contains("<p>details &lt;with&gt; html</p>"));
+    assert!(html.contains(
+        "<li>Performance has regressed: Ir (100 -> 200): +100.000% exceeds limit of +10.0000%</li>"
+    ));
+    assert!(html.contains(
+        "<li><a href=\"bench_a/group/bench/callgrind.bench.Ir.svg\">Ir flamegraph</a></li>"
+    ));
+    assert!(html.contains(
+        "<li><a href=\"bench_b/group/bench/callgrind.bench.log\">callgrind log</a></li>"
+    ));
+}
+
+#[rstest]
+#[case::other_run_id("1", 1000)]
+#[case::same_run_id_started_before_run(RUN_ID, 500)]
+fn test_html_report_when_previous_run_then_not_included(
+    #[case] previous_run_id: &str,
+    #[case] previous_start_time: u64,
+) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let data = ReportData::new(target_dir);
+
+    let mut previous = run_summary(vec![summary(target_dir, "bench_b::group", 200, 100, true)]);
+    previous.run_id = Some(previous_run_id.to_owned());
+    previous.start_time = previous_start_time;
+    data.save("bench_b", &previous).unwrap();
+    data.save(
+        "bench_a",
+        &run_summary(vec![summary(target_dir, "bench_a::group", 100, 100, false)]),
+    )
+    .unwrap();
+
+    let summaries = data
+        .load(RUN_ID, 1000)
+        .unwrap()
+        .into_iter()
+        .flat_map(|run| run.benchmarks)
+        .collect::<Vec<_>>();
+    let path = HtmlReport::new(target_dir, DEFAULT_EVENT_KINDS.to_vec())
+        .save(&summaries)
+        .unwrap();
+
+    let html = std::fs::read_to_string(path).unwrap();
+    assert!(html.contains("<p>1 benchmarks, 0 regressed</p>"));
+    assert!(html.contains("bench_a::group::bench"));
+    assert!(!html.contains("bench_b::group::bench"));
+}
+
+#[test]
+fn test_report_data_load_when_invalid_data_then_skip() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let data = ReportData::new(target_dir);
+
+    data.save(
+        "bench_a",
+        &run_summary(vec![summary(target_dir, "bench_a::group", 100, 100, false)]),
+    )
+    .unwrap();
+    std::fs::create_dir_all(target_dir.join("bench_b")).unwrap();
+    std::fs::write(target_dir.join("bench_b").join(DATA_FILE_NAME), "invalid").unwrap();
+
+    assert_eq!(data.load(RUN_ID, 0).unwrap().len(), 1);
+}
+
+#[test]
+fn test_report_data_load_when_other_run_then_skip() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let data = ReportData::new(target_dir);
+
+    data.save(
+        "bench_a",
+        &run_summary(vec![summary(target_dir, "bench_a::group", 100, 100, false)]),
+    )
+    .unwrap();
+    let mut stale = run_summary(vec![summary(target_dir, "bench_b::group", 200, 100, true)]);
+    stale.run_id = Some("1".to_owned());
+    data.save("bench_b", &stale).unwrap();
+    let mut without_id = run_summary(vec![summary(target_dir, "bench_c::group", 200, 100, true)]);
+    without_id.run_id = None;
+    data.save("bench_c", &without_id).unwrap();
+
+    let actual = data.load(RUN_ID, 0).unwrap();
+    assert_eq!(actual.len(), 1);
+    assert_eq!(
+        actual[0].benchmarks[0].module_path,
+        "bench_a::group::bench".to_owned()
+    );
+}
+
+#[test]
+fn test_report_data_load_when_started_before_run_then_skip() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let data = ReportData::new(target_dir);
+
+    data.save(
+        "bench_a",
+        &run_summary(vec![summary(target_dir, "bench_a::group", 100, 100, false)]),
+    )
+    .unwrap();
+
+    assert_eq!(data.load(RUN_ID, 1000).unwrap().len(), 1);
+    assert!(data.load(RUN_ID, 1001).unwrap().is_empty());
+}
@@ -0,0 +1,42 @@
+use anyhow::anyhow;
+use iai_callgrind_runner::runner::report::junit::JunitReport;
+use pretty_assertions::assert_eq;
+
+use super::summary;
+use crate::common::Fixtures;
+
+#[test]
+fn test_junit_report_create() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let mut error = summary(target_dir, "bench_b::group", 0, 0, false)
+        .with_error(&anyhow!("No such file").context("Failed to run benchmark"));
+    error.callgrind_summary = None;
+    let summaries = [
+        summary(target_dir, "bench_a::group", 100, 100, false),
+        summary(target_dir, "bench_b::group", 200, 100, true),
+        error,
+    ];
+
+    let actual = JunitReport::create(&summaries);
+    let expected =
+        std::fs::read_to_string(Fixtures::get_path_of("report/report.exp_junit")).unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_junit_report_save_creates_directories() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let path = target_dir.join("some").join("junit.xml");
+
+    JunitReport::save(
+        &path,
+        &[summary(target_dir, "bench_a::group", 100, 100, false)],
+    )
+    .unwrap();
+
+    assert!(std::fs::read_to_string(path)
+        .unwrap()
+        .starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+}
@@ -0,0 +1,46 @@
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::report::markdown::MarkdownReport;
+use iai_callgrind_runner::runner::report::DEFAULT_EVENT_KINDS;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use super::summary;
+use crate::common::Fixtures;
+
+#[rstest]
+#[case::default("default", DEFAULT_EVENT_KINDS.to_vec())]
+#[case::ir_only("ir_only", vec![EventKind::Ir])]
+fn test_markdown_report_create(#[case] expected: &str, #[case] event_kinds: Vec<EventKind>) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let summaries = [
+        summary(target_dir, "bench_a::group", 100, 100, false),
+        summary(target_dir, "bench_b::group", 200, 100, true),
+        summary(target_dir, "bench_b::group", 90, 100, false),
+    ];
+
+    let actual = MarkdownReport::new(event_kinds).create(&summaries);
+    let expected = std::fs::read_to_string(Fixtures::get_path_of(format!(
+        "report/report.{expected}.exp_md"
+    )))
+    .unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_markdown_report_save_creates_directories() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let path = target_dir.join("some").join("report.md");
+
+    MarkdownReport::new(vec![EventKind::Ir])
+        .save(
+            &path,
+            &[summary(target_dir, "bench_a::group", 100, 100, false)],
+        )
+        .unwrap();
+
+    assert!(std::fs::read_to_string(path)
+        .unwrap()
+        .starts_with("## Iai-Callgrind\n"));
+}
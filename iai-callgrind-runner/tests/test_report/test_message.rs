@@ -0,0 +1,94 @@
+use iai_callgrind_runner::api::EventKind;
+use iai_callgrind_runner::runner::message::Message;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use serde_json::Value;
+
+use super::summary;
+
+fn to_json(message: &Message) -> Value {
+    serde_json::to_value(message).unwrap()
+}
+
+#[test]
+fn test_message_benchmark_started() {
+    let actual = to_json(&Message::BenchmarkStarted {
+        module_path: "bench::group::function",
+        id: Some("id"),
+        details: None,
+    });
+    let expected = serde_json::json!({
+        "reason": "benchmark-started",
+        "module_path": "bench::group::function",
+        "id": "id",
+        "details": null
+    });
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_message_error() {
+    let actual = to_json(&Message::Error {
+        module_path: "bench::group::function",
+        id: None,
+        message: "Failed",
+    });
+    let expected = serde_json::json!({
+        "reason": "error",
+        "module_path": "bench::group::function",
+        "id": null,
+        "message": "Failed"
+    });
+    assert_eq!(actual, expected);
+}
+
+#[rstest]
+#[case::not_regressed(false)]
+#[case::regressed(true)]
+fn test_message_costs_and_regression(#[case] regressed: bool) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let summary = summary(temp_dir.path(), "bench::group", 200, 100, regressed);
+    let run = &summary.callgrind_summary.as_ref().unwrap().summaries[0];
+
+    let costs = to_json(&Message::Costs {
+        module_path: &summary.module_path,
+        id: summary.id.as_deref(),
+        command: &run.command,
+        costs: &run.events,
+    });
+    assert_eq!(costs["reason"], "costs");
+    assert_eq!(costs["module_path"], "bench::group::bench");
+    assert_eq!(costs["costs"][EventKind::Ir.to_string()]["new"], 200);
+    assert_eq!(costs["costs"][EventKind::Ir.to_string()]["old"], 100);
+
+    let regressions = run
+        .regressions
+        .iter()
+        .map(|regression| {
+            to_json(&Message::Regression {
+                module_path: &summary.module_path,
+                id: summary.id.as_deref(),
+                command: &run.command,
+                regression,
+            })
+        })
+        .collect::<Vec<Value>>();
+    if regressed {
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0]["reason"], "regression");
+        assert_eq!(regressions[0]["regression"]["event_kind"], "Ir");
+        assert_eq!(regressions[0]["regression"]["limit"], 10f64);
+    } else {
+        assert!(regressions.is_empty());
+    }
+}
+
+#[test]
+fn test_message_benchmark_finished() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let summary = summary(temp_dir.path(), "bench::group", 100, 100, false);
+
+    let actual = to_json(&Message::BenchmarkFinished { summary: &summary });
+    assert_eq!(actual["reason"], "benchmark-finished");
+    assert_eq!(actual["summary"], serde_json::to_value(&summary).unwrap());
+}
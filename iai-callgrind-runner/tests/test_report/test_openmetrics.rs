@@ -0,0 +1,27 @@
+use iai_callgrind_runner::runner::report::openmetrics::OpenMetricsReport;
+use pretty_assertions::assert_eq;
+
+use super::summary;
+use crate::common::Fixtures;
+
+#[test]
+fn test_openmetrics_report_create() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let mut quoted = summary(target_dir, "bench_c::group", 100, 100, false);
+    quoted.id = Some("with \"quotes\" and \\".to_owned());
+    let mut error = summary(target_dir, "bench_d::group", 0, 0, false);
+    error.callgrind_summary = None;
+    error.error = Some("Failed to run the benchmark".to_owned());
+    error.id = None;
+    let summaries = [
+        summary(target_dir, "bench_a::group", 100, 100, false),
+        summary(target_dir, "bench_b::group", 200, 100, true),
+        quoted,
+        error,
+    ];
+
+    let actual = OpenMetricsReport::create(&summaries);
+    let expected = std::fs::read_to_string(Fixtures::get_path_of("report/report.prom")).unwrap();
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,77 @@
+use std::time::{Duration, SystemTime};
+
+use iai_callgrind_runner::runner::summary::RunSummary;
+use pretty_assertions::assert_eq;
+
+use super::{run_summary, summary, RUN_ID};
+
+#[test]
+fn test_run_summary_new() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+
+    let run = run_summary(vec![
+        summary(target_dir, "bench_a::group", 100, 100, false),
+        summary(target_dir, "bench_a::group", 200, 100, true),
+    ]);
+
+    assert_eq!(run.version, "1");
+    assert_eq!(run.runner_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(run.run_id.as_deref(), Some(RUN_ID));
+    assert_eq!(run.command_line_args, vec!["--html-report".to_owned()]);
+    assert_eq!(run.start_time, 1000);
+    assert_eq!(run.end_time, 2000);
+    assert!(run.is_regressed);
+    assert_eq!(run.benchmarks.len(), 2);
+}
+
+#[test]
+fn test_run_summary_merge() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+
+    let mut run = run_summary(vec![summary(target_dir, "bench_a::group", 100, 100, false)]);
+    assert!(!run.is_regressed);
+
+    run.merge(RunSummary::new(
+        Some(RUN_ID.to_owned()),
+        vec![],
+        SystemTime::UNIX_EPOCH + Duration::from_millis(500),
+        SystemTime::UNIX_EPOCH + Duration::from_millis(1500),
+        vec![summary(target_dir, "bench_b::group", 200, 100, true)],
+    ));
+
+    assert_eq!(run.start_time, 500);
+    assert_eq!(run.end_time, 2000);
+    assert!(run.is_regressed);
+    assert_eq!(
+        run.benchmarks
+            .iter()
+            .map(|summary| summary.module_path.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["bench_a::group::bench", "bench_b::group::bench"]
+    );
+}
+
+#[test]
+fn test_run_summary_is_same_run() {
+    let run = run_summary(vec![]);
+    assert!(run.is_same_run(RUN_ID));
+    assert!(!run.is_same_run("1"));
+
+    let run = RunSummary::new(None, vec![], SystemTime::now(), SystemTime::now(), vec![]);
+    assert!(!run.is_same_run(RUN_ID));
+}
+
+#[test]
+fn test_run_summary_save() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let target_dir = temp_dir.path();
+    let path = target_dir.join("some").join("summary.json");
+
+    let run = run_summary(vec![summary(target_dir, "bench_a::group", 200, 100, true)]);
+    run.save(&path).unwrap();
+
+    let actual: RunSummary = serde_json::from_reader(std::fs::File::open(path).unwrap()).unwrap();
+    assert_eq!(actual, run);
+}
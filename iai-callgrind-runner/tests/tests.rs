@@ -2,3 +2,11 @@
 mod common;
 #[cfg(test)]
 mod test_callgrind;
+#[cfg(test)]
+mod test_git;
+#[cfg(test)]
+mod test_history;
+#[cfg(test)]
+mod test_project_config;
+#[cfg(test)]
+mod test_report;
@@ -671,6 +671,192 @@ impl BinaryBenchmarkConfig {
         self
     }
 
+    /// Show the most expensive functions configured with a [`crate::HotFunctionsConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark_group, Arg, BinaryBenchmarkGroup, Run};
+    /// # binary_benchmark_group!(
+    /// #    name = my_group;
+    /// #    benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {});
+    /// use iai_callgrind::{main, BinaryBenchmarkConfig, HotFunctionsConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default().hot_functions(HotFunctionsConfig::default());
+    ///     binary_benchmark_groups = my_group
+    /// );
+    /// # }
+    /// ```
+    pub fn hot_functions<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalHotFunctionsConfig>,
+    {
+        self.0.hot_functions = Some(config.into());
+        self
+    }
+
+    /// Create a function-level diff report configured with a [`crate::FunctionDiffConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark_group, Arg, BinaryBenchmarkGroup, Run};
+    /// # binary_benchmark_group!(
+    /// #    name = my_group;
+    /// #    benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {});
+    /// use iai_callgrind::{main, BinaryBenchmarkConfig, FunctionDiffConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default().function_diff(FunctionDiffConfig::default());
+    ///     binary_benchmark_groups = my_group
+    /// );
+    /// # }
+    /// ```
+    pub fn function_diff<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalFunctionDiffConfig>,
+    {
+        self.0.function_diff = Some(config.into());
+        self
+    }
+
+    /// Create a source line annotation report configured with an [`crate::AnnotateConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark_group, Arg, BinaryBenchmarkGroup, Run};
+    /// # binary_benchmark_group!(
+    /// #    name = my_group;
+    /// #    benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {});
+    /// use iai_callgrind::{main, AnnotateConfig, BinaryBenchmarkConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default().annotate(AnnotateConfig::default());
+    ///     binary_benchmark_groups = my_group
+    /// );
+    /// # }
+    /// ```
+    pub fn annotate<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalAnnotateConfig>,
+    {
+        self.0.annotate = Some(config.into());
+        self
+    }
+
+    /// Create an instruction-level hotspot report configured with a
+    /// [`crate::HotInstructionsConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark_group, Arg, BinaryBenchmarkGroup, Run};
+    /// # binary_benchmark_group!(
+    /// #    name = my_group;
+    /// #    benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {});
+    /// use iai_callgrind::{main, BinaryBenchmarkConfig, HotInstructionsConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default()
+    ///         .hot_instructions(HotInstructionsConfig::default());
+    ///     binary_benchmark_groups = my_group
+    /// );
+    /// # }
+    /// ```
+    pub fn hot_instructions<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalHotInstructionsConfig>,
+    {
+        self.0.hot_instructions = Some(config.into());
+        self
+    }
+
+    /// Export the callgrind output in the pprof format configured with a [`crate::PprofConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark_group, Arg, BinaryBenchmarkGroup, Run};
+    /// # binary_benchmark_group!(
+    /// #    name = my_group;
+    /// #    benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {});
+    /// use iai_callgrind::{main, BinaryBenchmarkConfig, PprofConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default().pprof(PprofConfig::default());
+    ///     binary_benchmark_groups = my_group
+    /// );
+    /// # }
+    /// ```
+    pub fn pprof<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalPprofConfig>,
+    {
+        self.0.pprof = Some(config.into());
+        self
+    }
+
+    /// Export the callgrind output in the speedscope format configured with a
+    /// [`crate::SpeedscopeConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark_group, Arg, BinaryBenchmarkGroup, Run};
+    /// # binary_benchmark_group!(
+    /// #    name = my_group;
+    /// #    benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {});
+    /// use iai_callgrind::{main, BinaryBenchmarkConfig, SpeedscopeConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default().speedscope(SpeedscopeConfig::default());
+    ///     binary_benchmark_groups = my_group
+    /// );
+    /// # }
+    /// ```
+    pub fn speedscope<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalSpeedscopeConfig>,
+    {
+        self.0.speedscope = Some(config.into());
+        self
+    }
+
+    /// Export the callgrind output as call graph in the DOT format configured with a
+    /// [`crate::DotConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{binary_benchmark_group, Arg, BinaryBenchmarkGroup, Run};
+    /// # binary_benchmark_group!(
+    /// #    name = my_group;
+    /// #    benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {});
+    /// use iai_callgrind::{main, BinaryBenchmarkConfig, DotConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = BinaryBenchmarkConfig::default().dot(DotConfig::default());
+    ///     binary_benchmark_groups = my_group
+    /// );
+    /// # }
+    /// ```
+    pub fn dot<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalDotConfig>,
+    {
+        self.0.dot = Some(config.into());
+        self
+    }
+
     /// Add a configuration to run a valgrind [`crate::Tool`] in addition to callgrind
     ///
     /// # Examples
@@ -1621,6 +1807,232 @@ impl Run {
         self
     }
 
+    /// Show the most expensive functions configured with a [`crate::HotFunctionsConfig`]
+    ///
+    /// See also [`BinaryBenchmarkConfig::hot_functions`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::main;
+    /// use iai_callgrind::{
+    ///     binary_benchmark_group, Arg, BinaryBenchmarkGroup, HotFunctionsConfig, Run,
+    /// };
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {
+    ///         group.bench(
+    ///             Run::with_arg(Arg::empty("empty foo"))
+    ///                 .hot_functions(HotFunctionsConfig::default().limit(5))
+    ///         );
+    ///     }
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn hot_functions<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalHotFunctionsConfig>,
+    {
+        self.0.config.hot_functions = Some(config.into());
+        self
+    }
+
+    /// Create a function-level diff report configured with a [`crate::FunctionDiffConfig`]
+    ///
+    /// See also [`BinaryBenchmarkConfig::function_diff`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::main;
+    /// use iai_callgrind::{
+    ///     binary_benchmark_group, Arg, BinaryBenchmarkGroup, FunctionDiffConfig, Run,
+    /// };
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {
+    ///         group.bench(
+    ///             Run::with_arg(Arg::empty("empty foo"))
+    ///                 .function_diff(FunctionDiffConfig::default())
+    ///         );
+    ///     }
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn function_diff<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalFunctionDiffConfig>,
+    {
+        self.0.config.function_diff = Some(config.into());
+        self
+    }
+
+    /// Create a source line annotation report configured with an [`crate::AnnotateConfig`]
+    ///
+    /// See also [`BinaryBenchmarkConfig::annotate`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::main;
+    /// use iai_callgrind::{binary_benchmark_group, AnnotateConfig, Arg, BinaryBenchmarkGroup, Run};
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {
+    ///         group.bench(
+    ///             Run::with_arg(Arg::empty("empty foo"))
+    ///                 .annotate(AnnotateConfig::default())
+    ///         );
+    ///     }
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn annotate<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalAnnotateConfig>,
+    {
+        self.0.config.annotate = Some(config.into());
+        self
+    }
+
+    /// Create an instruction-level hotspot report configured with a
+    /// [`crate::HotInstructionsConfig`]
+    ///
+    /// See also [`BinaryBenchmarkConfig::hot_instructions`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::main;
+    /// use iai_callgrind::{
+    ///     binary_benchmark_group, Arg, BinaryBenchmarkGroup, HotInstructionsConfig, Run,
+    /// };
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {
+    ///         group.bench(
+    ///             Run::with_arg(Arg::empty("empty foo"))
+    ///                 .hot_instructions(HotInstructionsConfig::default())
+    ///         );
+    ///     }
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn hot_instructions<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalHotInstructionsConfig>,
+    {
+        self.0.config.hot_instructions = Some(config.into());
+        self
+    }
+
+    /// Export the callgrind output in the pprof format configured with a [`crate::PprofConfig`]
+    ///
+    /// See also [`BinaryBenchmarkConfig::pprof`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::main;
+    /// use iai_callgrind::{binary_benchmark_group, Arg, BinaryBenchmarkGroup, PprofConfig, Run};
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {
+    ///         group.bench(
+    ///             Run::with_arg(Arg::empty("empty foo"))
+    ///                 .pprof(PprofConfig::default())
+    ///         );
+    ///     }
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn pprof<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalPprofConfig>,
+    {
+        self.0.config.pprof = Some(config.into());
+        self
+    }
+
+    /// Export the callgrind output in the speedscope format configured with a
+    /// [`crate::SpeedscopeConfig`]
+    ///
+    /// See also [`BinaryBenchmarkConfig::speedscope`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::main;
+    /// use iai_callgrind::{binary_benchmark_group, Arg, BinaryBenchmarkGroup, SpeedscopeConfig, Run};
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {
+    ///         group.bench(
+    ///             Run::with_arg(Arg::empty("empty foo"))
+    ///                 .speedscope(SpeedscopeConfig::default())
+    ///         );
+    ///     }
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn speedscope<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalSpeedscopeConfig>,
+    {
+        self.0.config.speedscope = Some(config.into());
+        self
+    }
+
+    /// Export the callgrind output as call graph in the DOT format configured with a
+    /// [`crate::DotConfig`]
+    ///
+    /// See also [`BinaryBenchmarkConfig::dot`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::main;
+    /// use iai_callgrind::{binary_benchmark_group, Arg, BinaryBenchmarkGroup, DotConfig, Run};
+    ///
+    /// binary_benchmark_group!(
+    ///     name = my_group;
+    ///     benchmark = |"my-exe", group: &mut BinaryBenchmarkGroup| {
+    ///         group.bench(
+    ///             Run::with_arg(Arg::empty("empty foo"))
+    ///                 .dot(DotConfig::default())
+    ///         );
+    ///     }
+    /// );
+    /// # fn main() {
+    /// # main!(binary_benchmark_groups = my_group);
+    /// # }
+    /// ```
+    pub fn dot<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalDotConfig>,
+    {
+        self.0.config.dot = Some(config.into());
+        self
+    }
+
     /// Add a configuration to run a valgrind [`crate::Tool`] in addition to callgrind
     ///
     /// # Examples
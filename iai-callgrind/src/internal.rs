@@ -3,17 +3,21 @@
 #![allow(missing_docs)]
 
 pub use iai_callgrind_runner::api::{
-    Arg as InternalArg, Assistant as InternalAssistant, BinaryBenchmark as InternalBinaryBenchmark,
+    AnnotateConfig as InternalAnnotateConfig, Arg as InternalArg, Assistant as InternalAssistant,
+    BinaryBenchmark as InternalBinaryBenchmark,
     BinaryBenchmarkConfig as InternalBinaryBenchmarkConfig,
     BinaryBenchmarkGroup as InternalBinaryBenchmarkGroup, Cmd as InternalCmd,
-    ExitWith as InternalExitWith, Fixtures as InternalFixtures,
-    FlamegraphConfig as InternalFlamegraphConfig, LibraryBenchmark as InternalLibraryBenchmark,
+    DotConfig as InternalDotConfig, ExitWith as InternalExitWith, Fixtures as InternalFixtures,
+    FlamegraphConfig as InternalFlamegraphConfig, FunctionDiffConfig as InternalFunctionDiffConfig,
+    HotFunctionsConfig as InternalHotFunctionsConfig,
+    HotInstructionsConfig as InternalHotInstructionsConfig,
+    LibraryBenchmark as InternalLibraryBenchmark,
     LibraryBenchmarkBench as InternalLibraryBenchmarkBench,
     LibraryBenchmarkBenches as InternalLibraryBenchmarkBenches,
     LibraryBenchmarkConfig as InternalLibraryBenchmarkConfig,
-    LibraryBenchmarkGroup as InternalLibraryBenchmarkGroup, RawArgs as InternalRawArgs,
-    RegressionConfig as InternalRegressionConfig, Run as InternalRun, Tool as InternalTool,
-    Tools as InternalTools,
+    LibraryBenchmarkGroup as InternalLibraryBenchmarkGroup, PprofConfig as InternalPprofConfig,
+    RawArgs as InternalRawArgs, RegressionConfig as InternalRegressionConfig, Run as InternalRun,
+    SpeedscopeConfig as InternalSpeedscopeConfig, Tool as InternalTool, Tools as InternalTools,
 };
 
 #[derive(Debug, Clone)]
@@ -328,6 +328,61 @@ pub use iai_callgrind_macros::library_benchmark;
 pub use iai_callgrind_runner::api::{Direction, EventKind, FlamegraphKind, ValgrindTool};
 pub use lib_bench::LibraryBenchmarkConfig;
 
+/// Configure the source line annotation report
+///
+/// Like `callgrind_annotate`, the report maps the exclusive costs of an [`EventKind`] (default:
+/// [`EventKind::Ir`]) to the source lines of all files in the project. The report is saved as
+/// text file `callgrind.*.annotate.txt` and as self-contained html page `callgrind.*.annotate.html`
+/// with the hottest lines highlighted next to the callgrind output files. If there is an old
+/// callgrind output, the costs of the old benchmark run and the change are shown, too.
+///
+/// # Examples
+///
+/// ```rust
+/// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+/// use iai_callgrind::{main, AnnotateConfig, LibraryBenchmarkConfig};
+/// # #[library_benchmark]
+/// # fn some_func() {}
+/// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+/// # fn main() {
+/// main!(
+///     config = LibraryBenchmarkConfig::default()
+///                 .annotate(AnnotateConfig::default());
+///     library_benchmark_groups = some_group
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnnotateConfig(internal::InternalAnnotateConfig);
+
+/// Configure the export of the callgrind output as call graph in the DOT format of
+/// [Graphviz](https://graphviz.org)
+///
+/// The nodes of the call graph are the functions with their inclusive and self costs of an
+/// [`EventKind`] (default: [`EventKind::Ir`]) as percentage of the total costs. The edges are the
+/// calls between the functions with the number of calls and the inclusive costs of the calls.
+/// Nodes and edges below a threshold are pruned. The exported `callgrind.*.dot` file can be
+/// rendered for example with `dot -Tsvg callgrind.*.dot -o callgrind.svg`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+/// use iai_callgrind::{main, DotConfig, LibraryBenchmarkConfig};
+/// # #[library_benchmark]
+/// # fn some_func() {}
+/// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+/// # fn main() {
+/// main!(
+///     config = LibraryBenchmarkConfig::default()
+///                 .dot(DotConfig::default());
+///     library_benchmark_groups = some_group
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DotConfig(internal::InternalDotConfig);
+
 /// The `FlamegraphConfig` which allows the customization of the created flamegraphs
 ///
 /// Callgrind flamegraphs are very similar to `callgrind_annotate` output. In contrast to
@@ -354,6 +409,116 @@ pub use lib_bench::LibraryBenchmarkConfig;
 #[derive(Debug, Clone, Default)]
 pub struct FlamegraphConfig(internal::InternalFlamegraphConfig);
 
+/// Configure the function-level differential report between the new and old benchmark run
+///
+/// If enabled and there is an old benchmark run to compare with, all functions which appeared,
+/// disappeared or changed their costs are sorted by the absolute difference of their inclusive
+/// costs for each [`EventKind`]. The `limit` most changed functions are shown in the terminal
+/// output and the full report is saved in json and markdown format next to the callgrind output
+/// files (`callgrind.*.function_diff.json` and `callgrind.*.function_diff.md`).
+///
+/// # Examples
+///
+/// ```rust
+/// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+/// use iai_callgrind::{main, FunctionDiffConfig, LibraryBenchmarkConfig};
+/// # #[library_benchmark]
+/// # fn some_func() {}
+/// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+/// # fn main() {
+/// main!(
+///     config = LibraryBenchmarkConfig::default()
+///                 .function_diff(FunctionDiffConfig::default());
+///     library_benchmark_groups = some_group
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FunctionDiffConfig(internal::InternalFunctionDiffConfig);
+
+/// Configure the report of the most expensive functions of a benchmark run
+///
+/// If enabled, the `N` functions with the highest inclusive and exclusive (self) costs are shown
+/// after the usual callgrind output of each benchmark together with the difference to the old
+/// benchmark run if present. The same information is saved in the summary file when running with
+/// `--save-summary`. The limit can be overridden on the command line with `--show-functions=N` or
+/// `IAI_CALLGRIND_SHOW_FUNCTIONS=N`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+/// use iai_callgrind::{main, HotFunctionsConfig, LibraryBenchmarkConfig};
+/// # #[library_benchmark]
+/// # fn some_func() {}
+/// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+/// # fn main() {
+/// main!(
+///     config = LibraryBenchmarkConfig::default()
+///                 .hot_functions(HotFunctionsConfig::default());
+///     library_benchmark_groups = some_group
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HotFunctionsConfig(internal::InternalHotFunctionsConfig);
+
+/// Configure the report of the most expensive instructions of a benchmark run
+///
+/// If enabled, callgrind runs with `--dump-instr=yes` and the costs are recorded per instruction
+/// address. The report shows the most expensive functions with their hottest instructions
+/// disassembled with `objdump`, which must be installed. This can help to find regressions caused
+/// by changes in the generated code when the source code stayed the same. The report is saved as
+/// `*.hot_instructions.txt` file next to the callgrind output files.
+///
+/// # Examples
+///
+/// ```rust
+/// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+/// use iai_callgrind::{main, HotInstructionsConfig, LibraryBenchmarkConfig};
+/// # #[library_benchmark]
+/// # fn some_func() {}
+/// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+/// # fn main() {
+/// main!(
+///     config = LibraryBenchmarkConfig::default()
+///                 .hot_instructions(HotInstructionsConfig::default());
+///     library_benchmark_groups = some_group
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HotInstructionsConfig(internal::InternalHotInstructionsConfig);
+
+/// Configure the export of the callgrind output in the [pprof](https://github.com/google/pprof)
+/// format
+///
+/// If enabled, the callgrind output is converted into a pprof profile with the functions, source
+/// files, lines and the call stacks reconstructed from the calls between the functions. Each
+/// [`EventKind`] is a sample type of the profile. The profile is saved next to the callgrind output
+/// file (`callgrind.*.pb`) and can be opened with `go tool pprof` or any other pprof-compatible
+/// viewer. The export can also be enabled on the command line with `--export=pprof` or
+/// `IAI_CALLGRIND_EXPORT=pprof`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+/// use iai_callgrind::{main, LibraryBenchmarkConfig, PprofConfig};
+/// # #[library_benchmark]
+/// # fn some_func() {}
+/// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+/// # fn main() {
+/// main!(
+///     config = LibraryBenchmarkConfig::default()
+///                 .pprof(PprofConfig::default());
+///     library_benchmark_groups = some_group
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PprofConfig(internal::InternalPprofConfig);
+
 /// Configure performance regression checks and behavior
 ///
 /// A performance regression check consists of an [`EventKind`] and a percentage over which a
@@ -384,6 +549,31 @@ pub struct FlamegraphConfig(internal::InternalFlamegraphConfig);
 #[derive(Debug, Default, Clone)]
 pub struct RegressionConfig(internal::InternalRegressionConfig);
 
+/// Configure the export of the callgrind output in the [speedscope](https://www.speedscope.app)
+/// json format
+///
+/// The exported `callgrind.*.speedscope.json` file contains a profile for each [`EventKind`] and
+/// can be opened in the speedscope web application to browse the call stacks interactively.
+///
+/// # Examples
+///
+/// ```rust
+/// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+/// use iai_callgrind::{main, LibraryBenchmarkConfig, SpeedscopeConfig};
+/// # #[library_benchmark]
+/// # fn some_func() {}
+/// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+/// # fn main() {
+/// main!(
+///     config = LibraryBenchmarkConfig::default()
+///                 .speedscope(SpeedscopeConfig::default());
+///     library_benchmark_groups = some_group
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SpeedscopeConfig(internal::InternalSpeedscopeConfig);
+
 /// Configure to run other valgrind tools like `DHAT` or `Massif` in addition to callgrind
 ///
 /// For a list of possible tools see [`ValgrindTool`].
@@ -409,6 +599,129 @@ pub struct RegressionConfig(internal::InternalRegressionConfig);
 /// ```
 pub struct Tool(internal::InternalTool);
 
+impl AnnotateConfig {
+    /// Set the [`EventKind`] of the costs in the report
+    ///
+    /// The default is [`EventKind::Ir`]. Derived [`EventKind`]s like
+    /// [`EventKind::EstimatedCycles`] require the cache simulation (`--cache-sim=yes`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::{AnnotateConfig, EventKind};
+    ///
+    /// let config = AnnotateConfig::default().event_kind(EventKind::EstimatedCycles);
+    /// ```
+    pub fn event_kind(&mut self, event_kind: EventKind) -> &mut Self {
+        self.0.event_kind = Some(event_kind);
+        self
+    }
+
+    /// Set the number of lines shown around the lines with costs in the text report
+    ///
+    /// The default is `8`. The html report always shows the whole source files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::AnnotateConfig;
+    ///
+    /// let config = AnnotateConfig::default().context(3);
+    /// ```
+    pub fn context(&mut self, context: usize) -> &mut Self {
+        self.0.context = Some(context);
+        self
+    }
+
+    /// Set the number of the hottest lines which are listed at the top of the report
+    ///
+    /// The default is `10`. These lines are also highlighted in the html report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::AnnotateConfig;
+    ///
+    /// let config = AnnotateConfig::default().limit(20);
+    /// ```
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.0.limit = Some(limit);
+        self
+    }
+}
+
+impl_traits!(AnnotateConfig, internal::InternalAnnotateConfig);
+
+impl DotConfig {
+    /// Set the [`EventKind`] of the costs in the call graph
+    ///
+    /// The default is [`EventKind::Ir`]. Derived [`EventKind`]s like
+    /// [`EventKind::EstimatedCycles`] require the cache simulation (`--cache-sim=yes`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::{DotConfig, EventKind};
+    ///
+    /// let config = DotConfig::default().event_kind(EventKind::EstimatedCycles);
+    /// ```
+    pub fn event_kind(&mut self, event_kind: EventKind) -> &mut Self {
+        self.0.event_kind = Some(event_kind);
+        self
+    }
+
+    /// Set the threshold in percent of the total costs below which functions are pruned
+    ///
+    /// The default is `0.5`. To show all functions, set the `node_threshold` to `0f64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::DotConfig;
+    ///
+    /// let config = DotConfig::default().node_threshold(1f64);
+    /// ```
+    pub fn node_threshold(&mut self, threshold: f64) -> &mut Self {
+        self.0.node_threshold = Some(threshold);
+        self
+    }
+
+    /// Set the threshold in percent of the total costs below which calls are pruned
+    ///
+    /// The default is `0.1`. Calls from or to pruned functions are always pruned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::DotConfig;
+    ///
+    /// let config = DotConfig::default().edge_threshold(0f64);
+    /// ```
+    pub fn edge_threshold(&mut self, threshold: f64) -> &mut Self {
+        self.0.edge_threshold = Some(threshold);
+        self
+    }
+
+    /// If true, color the functions by the change of their inclusive costs to the old run
+    ///
+    /// More expensive functions are colored red and cheaper functions green. This option has no
+    /// effect if there is no old callgrind output. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::DotConfig;
+    ///
+    /// let config = DotConfig::default().differential(true);
+    /// ```
+    pub fn differential(&mut self, value: bool) -> &mut Self {
+        self.0.differential = Some(value);
+        self
+    }
+}
+
+impl_traits!(DotConfig, internal::InternalDotConfig);
+
 impl FlamegraphConfig {
     /// Option to change the [`FlamegraphKind`]
     ///
@@ -581,10 +894,222 @@ impl FlamegraphConfig {
         self.0.min_width = Some(min_width);
         self
     }
+
+    /// Set the maximum depth of the call stacks in the flamegraph
+    ///
+    /// The costs of deeper calls are attributed to the function at the maximum depth. The default
+    /// is to show the whole call stack. Recursive calls are always cut off at the first repetition
+    /// of a function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::FlamegraphConfig;
+    ///
+    /// let config = FlamegraphConfig::default().max_depth(20);
+    /// ```
+    pub fn max_depth(&mut self, max_depth: usize) -> &mut Self {
+        self.0.max_depth = Some(max_depth);
+        self
+    }
+
+    /// If true, save the folded stacks next to the flamegraph svg files
+    ///
+    /// The folded (or collapsed) stacks are the input of the flamegraphs and are saved in files
+    /// with the `.folded` extension for each [`EventKind`] and for the old callgrind run if
+    /// present. These files can be processed further with other tools like `flamegraph.pl`,
+    /// `inferno` or `speedscope`. The default is false.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::FlamegraphConfig;
+    ///
+    /// let config = FlamegraphConfig::default().folded(true);
+    /// ```
+    pub fn folded(&mut self, folded: bool) -> &mut Self {
+        self.0.folded = Some(folded);
+        self
+    }
 }
 
 impl_traits!(FlamegraphConfig, internal::InternalFlamegraphConfig);
 
+impl FunctionDiffConfig {
+    /// Set the [`EventKind`]s for which a function diff is created
+    ///
+    /// The default is [`EventKind::Ir`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::{EventKind, FunctionDiffConfig};
+    ///
+    /// let config =
+    ///     FunctionDiffConfig::default().event_kinds([EventKind::Ir, EventKind::EstimatedCycles]);
+    /// ```
+    pub fn event_kinds<T>(&mut self, event_kinds: T) -> &mut Self
+    where
+        T: IntoIterator<Item = EventKind>,
+    {
+        self.0.event_kinds = Some(event_kinds.into_iter().collect());
+        self
+    }
+
+    /// Set the maximum number of functions shown in the terminal output for each [`EventKind`]
+    ///
+    /// The files of the report always contain all changed functions. The default is `10`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::FunctionDiffConfig;
+    ///
+    /// let config = FunctionDiffConfig::default().limit(20);
+    /// ```
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.0.limit = Some(limit);
+        self
+    }
+}
+
+impl_traits!(FunctionDiffConfig, internal::InternalFunctionDiffConfig);
+
+impl HotFunctionsConfig {
+    /// Set the number of functions to show
+    ///
+    /// The default is `10`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::HotFunctionsConfig;
+    ///
+    /// let config = HotFunctionsConfig::default().limit(5);
+    /// ```
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.0.limit = Some(limit);
+        self
+    }
+
+    /// Set the [`EventKind`] by which the functions are sorted
+    ///
+    /// The default is [`EventKind::Ir`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::{EventKind, HotFunctionsConfig};
+    ///
+    /// let config = HotFunctionsConfig::default().event_kind(EventKind::EstimatedCycles);
+    /// ```
+    pub fn event_kind(&mut self, event_kind: EventKind) -> &mut Self {
+        self.0.event_kind = Some(event_kind);
+        self
+    }
+}
+
+impl_traits!(HotFunctionsConfig, internal::InternalHotFunctionsConfig);
+
+impl HotInstructionsConfig {
+    /// Set the [`EventKind`] of the costs in the report
+    ///
+    /// The default is [`EventKind::Ir`]. Derived [`EventKind`]s like
+    /// [`EventKind::EstimatedCycles`] require the cache simulation (`--cache-sim=yes`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::{EventKind, HotInstructionsConfig};
+    ///
+    /// let config = HotInstructionsConfig::default().event_kind(EventKind::EstimatedCycles);
+    /// ```
+    pub fn event_kind(&mut self, event_kind: EventKind) -> &mut Self {
+        self.0.event_kind = Some(event_kind);
+        self
+    }
+
+    /// Set the number of the most expensive functions shown in the report
+    ///
+    /// The default is `5`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::HotInstructionsConfig;
+    ///
+    /// let config = HotInstructionsConfig::default().functions(3);
+    /// ```
+    pub fn functions(&mut self, functions: usize) -> &mut Self {
+        self.0.functions = Some(functions);
+        self
+    }
+
+    /// Set the number of the hottest instructions shown per function
+    ///
+    /// The default is `10`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::HotInstructionsConfig;
+    ///
+    /// let config = HotInstructionsConfig::default().limit(20);
+    /// ```
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.0.limit = Some(limit);
+        self
+    }
+
+    /// Set the path to the `objdump` executable
+    ///
+    /// The default is `objdump` looked up in the `PATH`. If `objdump` is not available, the
+    /// report shows the instruction addresses without disassembly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::HotInstructionsConfig;
+    ///
+    /// let config = HotInstructionsConfig::default().objdump("/usr/bin/llvm-objdump");
+    /// ```
+    pub fn objdump<T>(&mut self, path: T) -> &mut Self
+    where
+        T: Into<std::path::PathBuf>,
+    {
+        self.0.objdump = Some(path.into());
+        self
+    }
+}
+
+impl_traits!(HotInstructionsConfig, internal::InternalHotInstructionsConfig);
+
+impl PprofConfig {
+    /// Set the [`EventKind`]s which are exported as sample types
+    ///
+    /// The default are all [`EventKind`]s recorded in the callgrind output. Derived
+    /// [`EventKind`]s like [`EventKind::EstimatedCycles`] require the cache simulation
+    /// (`--cache-sim=yes`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::{EventKind, PprofConfig};
+    ///
+    /// let config =
+    ///     PprofConfig::default().event_kinds([EventKind::Ir, EventKind::EstimatedCycles]);
+    /// ```
+    pub fn event_kinds<T>(&mut self, event_kinds: T) -> &mut Self
+    where
+        T: IntoIterator<Item = EventKind>,
+    {
+        self.0.event_kinds = Some(event_kinds.into_iter().collect());
+        self
+    }
+}
+
+impl_traits!(PprofConfig, internal::InternalPprofConfig);
+
 /// Enable performance regression checks with a [`RegressionConfig`]
 ///
 /// A performance regression check consists of an [`EventKind`] and a percentage over which a
@@ -658,6 +1183,32 @@ impl RegressionConfig {
 
 impl_traits!(RegressionConfig, internal::InternalRegressionConfig);
 
+impl SpeedscopeConfig {
+    /// Set the [`EventKind`]s for which a profile is exported
+    ///
+    /// The default are all [`EventKind`]s recorded in the callgrind output. Derived
+    /// [`EventKind`]s like [`EventKind::EstimatedCycles`] require the cache simulation
+    /// (`--cache-sim=yes`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iai_callgrind::{EventKind, SpeedscopeConfig};
+    ///
+    /// let config =
+    ///     SpeedscopeConfig::default().event_kinds([EventKind::Ir, EventKind::EstimatedCycles]);
+    /// ```
+    pub fn event_kinds<T>(&mut self, event_kinds: T) -> &mut Self
+    where
+        T: IntoIterator<Item = EventKind>,
+    {
+        self.0.event_kinds = Some(event_kinds.into_iter().collect());
+        self
+    }
+}
+
+impl_traits!(SpeedscopeConfig, internal::InternalSpeedscopeConfig);
+
 impl Tool {
     /// Create a new `Tool` configuration
     ///
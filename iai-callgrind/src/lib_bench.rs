@@ -58,6 +58,13 @@ impl LibraryBenchmarkConfig {
             envs: Vec::default(),
             flamegraph: Option::default(),
             regression: Option::default(),
+            hot_functions: Option::default(),
+            function_diff: Option::default(),
+            annotate: Option::default(),
+            hot_instructions: Option::default(),
+            pprof: Option::default(),
+            speedscope: Option::default(),
+            dot: Option::default(),
             tools: internal::InternalTools::default(),
             tools_override: Option::default(),
             custom_entry_point: Option::default(),
@@ -354,6 +361,192 @@ impl LibraryBenchmarkConfig {
         self
     }
 
+    /// Show the most expensive functions configured with a [`crate::HotFunctionsConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+    /// use iai_callgrind::{main, HotFunctionsConfig, LibraryBenchmarkConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default().hot_functions(HotFunctionsConfig::default());
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn hot_functions<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalHotFunctionsConfig>,
+    {
+        self.0.hot_functions = Some(config.into());
+        self
+    }
+
+    /// Create a function-level diff report configured with a [`crate::FunctionDiffConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+    /// use iai_callgrind::{main, FunctionDiffConfig, LibraryBenchmarkConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default().function_diff(FunctionDiffConfig::default());
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn function_diff<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalFunctionDiffConfig>,
+    {
+        self.0.function_diff = Some(config.into());
+        self
+    }
+
+    /// Create a source line annotation report configured with an [`crate::AnnotateConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+    /// use iai_callgrind::{main, AnnotateConfig, LibraryBenchmarkConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default().annotate(AnnotateConfig::default());
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn annotate<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalAnnotateConfig>,
+    {
+        self.0.annotate = Some(config.into());
+        self
+    }
+
+    /// Create an instruction-level hotspot report configured with a
+    /// [`crate::HotInstructionsConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+    /// use iai_callgrind::{main, HotInstructionsConfig, LibraryBenchmarkConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default()
+    ///         .hot_instructions(HotInstructionsConfig::default());
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn hot_instructions<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalHotInstructionsConfig>,
+    {
+        self.0.hot_instructions = Some(config.into());
+        self
+    }
+
+    /// Export the callgrind output in the pprof format configured with a [`crate::PprofConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+    /// use iai_callgrind::{main, LibraryBenchmarkConfig, PprofConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default().pprof(PprofConfig::default());
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn pprof<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalPprofConfig>,
+    {
+        self.0.pprof = Some(config.into());
+        self
+    }
+
+    /// Export the callgrind output in the speedscope format configured with a
+    /// [`crate::SpeedscopeConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+    /// use iai_callgrind::{main, LibraryBenchmarkConfig, SpeedscopeConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default().speedscope(SpeedscopeConfig::default());
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn speedscope<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalSpeedscopeConfig>,
+    {
+        self.0.speedscope = Some(config.into());
+        self
+    }
+
+    /// Export the callgrind output as call graph in the DOT format configured with a
+    /// [`crate::DotConfig`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use iai_callgrind::{library_benchmark, library_benchmark_group};
+    /// # #[library_benchmark]
+    /// # fn some_func() {}
+    /// # library_benchmark_group!(name = some_group; benchmarks = some_func);
+    /// use iai_callgrind::{main, DotConfig, LibraryBenchmarkConfig};
+    ///
+    /// # fn main() {
+    /// main!(
+    ///     config = LibraryBenchmarkConfig::default().dot(DotConfig::default());
+    ///     library_benchmark_groups = some_group
+    /// );
+    /// # }
+    /// ```
+    pub fn dot<T>(&mut self, config: T) -> &mut Self
+    where
+        T: Into<internal::InternalDotConfig>,
+    {
+        self.0.dot = Some(config.into());
+        self
+    }
+
     /// Add a configuration to run a valgrind [`crate::Tool`] in addition to callgrind
     ///
     /// # Examples
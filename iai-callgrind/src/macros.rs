@@ -182,6 +182,9 @@ macro_rules! main {
             cmd.arg(file!());
             cmd.arg(module_path!());
             cmd.arg(this_args.next().unwrap()); // The executable benchmark binary
+            // The runner identifies the `cargo bench` run by the process id of cargo
+            #[cfg(unix)]
+            cmd.env("IAI_CALLGRIND_CARGO_PID", std::os::unix::process::parent_id().to_string());
 
             let mut config: Option<$crate::internal::InternalBinaryBenchmarkConfig> = None;
             $(
@@ -282,6 +285,9 @@ macro_rules! main {
             cmd.arg(file!());
             cmd.arg(module_path!());
             cmd.arg(this_args.next().unwrap()); // The executable benchmark binary
+            // The runner identifies the `cargo bench` run by the process id of cargo
+            #[cfg(unix)]
+            cmd.env("IAI_CALLGRIND_CARGO_PID", std::os::unix::process::parent_id().to_string());
 
             let mut config: Option<$crate::internal::InternalLibraryBenchmarkConfig> = None;
             $(